@@ -0,0 +1,666 @@
+use crate::art_handler::get_achievement_art;
+use crate::basic_strategy::{follows_basic_strategy, StrategyAction};
+use crate::formatting::BoxFormatter;
+use crate::game_history::{GameHistory, GameOutcome, GameRound};
+use crate::game_state::GameState;
+use crate::player_handler::hand_value;
+use crate::profiles::Profile;
+use crate::round_recovery::RoundPhase;
+use crate::safe_file;
+use crate::streaks::{StreakKind, StreakStats};
+use crate::table_rules::GameVariant;
+use chrono::{DateTime, Local, Timelike};
+use serde::{Deserialize, Serialize};
+
+/// Bankroll the Comeback Kid has to climb back to from a single coin
+const COMEBACK_TARGET: i32 = 100;
+
+/// Something to aim for beyond winning coins
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Achievement {
+    FirstHand,
+    FirstWin,
+    FirstBlackjack,
+    TenBlackjacks,
+    HundredHands,
+    ThousandHands,
+    HundredWins,
+    Marathon,
+    FiveCardWin,
+    LuckySevens,
+    HardTwentyOne,
+    NervesOfSteel,
+    DoubleWin,
+    SplitBothWin,
+    SplitAcesWin,
+    Surrender,
+    PushOnTwentyOne,
+    PhotoFinish,
+    DealerMeltdown,
+    Overkill,
+    HotHand,
+    OnFire,
+    ColdDeck,
+    ComebackKid,
+    DoubleUp,
+    HighRoller,
+    Whale,
+    AllIn,
+    BigScore,
+    OnTheHouse,
+    Student,
+    ByTheBook,
+    TableHopper,
+    NightOwl,
+}
+
+impl Achievement {
+    pub const ALL: [Achievement; 34] = [
+        Achievement::FirstHand,
+        Achievement::FirstWin,
+        Achievement::FirstBlackjack,
+        Achievement::TenBlackjacks,
+        Achievement::HundredHands,
+        Achievement::ThousandHands,
+        Achievement::HundredWins,
+        Achievement::Marathon,
+        Achievement::FiveCardWin,
+        Achievement::LuckySevens,
+        Achievement::HardTwentyOne,
+        Achievement::NervesOfSteel,
+        Achievement::DoubleWin,
+        Achievement::SplitBothWin,
+        Achievement::SplitAcesWin,
+        Achievement::Surrender,
+        Achievement::PushOnTwentyOne,
+        Achievement::PhotoFinish,
+        Achievement::DealerMeltdown,
+        Achievement::Overkill,
+        Achievement::HotHand,
+        Achievement::OnFire,
+        Achievement::ColdDeck,
+        Achievement::ComebackKid,
+        Achievement::DoubleUp,
+        Achievement::HighRoller,
+        Achievement::Whale,
+        Achievement::AllIn,
+        Achievement::BigScore,
+        Achievement::OnTheHouse,
+        Achievement::Student,
+        Achievement::ByTheBook,
+        Achievement::TableHopper,
+        Achievement::NightOwl,
+    ];
+
+    #[must_use]
+    pub fn name(self) -> &'static str {
+        match self {
+            Achievement::FirstHand => "First Deal",
+            Achievement::FirstWin => "Beginner's Luck",
+            Achievement::FirstBlackjack => "Natural",
+            Achievement::TenBlackjacks => "Natural Talent",
+            Achievement::HundredHands => "Regular",
+            Achievement::ThousandHands => "Veteran",
+            Achievement::HundredWins => "Centurion",
+            Achievement::Marathon => "Marathon",
+            Achievement::FiveCardWin => "Five Card Charlie",
+            Achievement::LuckySevens => "Lucky Sevens",
+            Achievement::HardTwentyOne => "The Hard Way",
+            Achievement::NervesOfSteel => "Nerves of Steel",
+            Achievement::DoubleWin => "Double Trouble",
+            Achievement::SplitBothWin => "Two for Two",
+            Achievement::SplitAcesWin => "Aces Up",
+            Achievement::Surrender => "Live to Fight Again",
+            Achievement::PushOnTwentyOne => "Stalemate",
+            Achievement::PhotoFinish => "Photo Finish",
+            Achievement::DealerMeltdown => "Dealer Meltdown",
+            Achievement::Overkill => "Overkill",
+            Achievement::HotHand => "Hot Hand",
+            Achievement::OnFire => "On Fire",
+            Achievement::ColdDeck => "Cold Deck",
+            Achievement::ComebackKid => "Comeback Kid",
+            Achievement::DoubleUp => "Double Up",
+            Achievement::HighRoller => "High Roller",
+            Achievement::Whale => "Whale",
+            Achievement::AllIn => "All In",
+            Achievement::BigScore => "Big Score",
+            Achievement::OnTheHouse => "On the House",
+            Achievement::Student => "Student of the Game",
+            Achievement::ByTheBook => "By the Book",
+            Achievement::TableHopper => "Table Hopper",
+            Achievement::NightOwl => "Night Owl",
+        }
+    }
+
+    #[must_use]
+    pub fn description(self) -> &'static str {
+        match self {
+            Achievement::FirstHand => "Play your first hand",
+            Achievement::FirstWin => "Win a hand",
+            Achievement::FirstBlackjack => "Be dealt a blackjack",
+            Achievement::TenBlackjacks => "Be dealt 10 blackjacks",
+            Achievement::HundredHands => "Play 100 hands",
+            Achievement::ThousandHands => "Play 1,000 hands",
+            Achievement::HundredWins => "Win 100 hands",
+            Achievement::Marathon => "Play 200 hands in one session",
+            Achievement::FiveCardWin => "Win with a hand of five or more cards",
+            Achievement::LuckySevens => "Win with three sevens",
+            Achievement::HardTwentyOne => "Win with 21 made from three or more cards",
+            Achievement::NervesOfSteel => "Win a hand standing on 12 or less",
+            Achievement::DoubleWin => "Win a hand you doubled down on",
+            Achievement::SplitBothWin => "Win both hands of a split",
+            Achievement::SplitAcesWin => "Win a hand after splitting aces",
+            Achievement::Surrender => "Surrender a hand",
+            Achievement::PushOnTwentyOne => "Tie the dealer on 21",
+            Achievement::PhotoFinish => "Beat a dealer's 20 with 21",
+            Achievement::DealerMeltdown => "See the dealer bust with five or more cards",
+            Achievement::Overkill => "Bust with a total of 26 or more",
+            Achievement::HotHand => "Win 5 hands in a row",
+            Achievement::OnFire => "Win 10 hands in a row",
+            Achievement::ColdDeck => "Lose 5 hands in a row",
+            Achievement::ComebackKid => "Get back to 100 coins after dropping to 1",
+            Achievement::DoubleUp => "Reach 200 coins",
+            Achievement::HighRoller => "Reach 1,000 coins",
+            Achievement::Whale => "Reach 10,000 coins",
+            Achievement::AllIn => "Bet your whole bankroll on one hand and win",
+            Achievement::BigScore => "Win 500 coins on a single hand",
+            Achievement::OnTheHouse => "Win a hand played partly with a free bet",
+            Achievement::Student => "Play 25 hands in a row by basic strategy",
+            Achievement::ByTheBook => "Play 100 hands in a row by basic strategy",
+            Achievement::TableHopper => "Play a hand of every table variant",
+            Achievement::NightOwl => "Play a hand between midnight and 4 a.m.",
+        }
+    }
+
+    /// Whether the hand just recorded in `hand` earns this achievement
+    #[must_use]
+    pub fn is_earned(self, hand: &HandContext, progress: &Progress) -> bool {
+        let round = hand.round;
+        let won = is_win(round.outcome);
+        match self {
+            Achievement::FirstHand => true,
+            Achievement::FirstWin => won,
+            Achievement::FirstBlackjack => is_natural(round),
+            Achievement::TenBlackjacks => {
+                hand.history.rounds.iter().filter(|r| is_natural(r)).count() >= 10
+            }
+            Achievement::HundredHands => hand.history.total_games_played >= 100,
+            Achievement::ThousandHands => hand.history.total_games_played >= 1000,
+            Achievement::HundredWins => hand.history.total_wins >= 100,
+            Achievement::Marathon => hand.session_hands >= 200,
+            Achievement::FiveCardWin => won && round.player_cards.len() >= 5,
+            Achievement::LuckySevens => {
+                won && round.player_cards.len() == 3
+                    && round.player_cards.iter().all(|card| rank(card) == "7")
+            }
+            Achievement::HardTwentyOne => {
+                won && round.player_total == 21 && round.player_cards.len() >= 3
+            }
+            Achievement::NervesOfSteel => won && round.player_total <= 12,
+            Achievement::DoubleWin => won && round.was_double_down,
+            Achievement::SplitBothWin => won && round.was_split && split_hands_all_won(hand),
+            Achievement::SplitAcesWin => {
+                won && round.was_split && round.player_cards.first().is_some_and(|c| rank(c) == "A")
+            }
+            Achievement::Surrender => round.outcome == GameOutcome::Surrender,
+            Achievement::PushOnTwentyOne => {
+                round.outcome == GameOutcome::Tie && round.player_total == 21
+            }
+            Achievement::PhotoFinish => won && round.player_total == 21 && round.dealer_total == 20,
+            Achievement::DealerMeltdown => round.dealer_busted && round.dealer_cards.len() >= 5,
+            Achievement::Overkill => round.player_total >= 26,
+            Achievement::HotHand => current_streak(hand.history, StreakKind::Win) >= 5,
+            Achievement::OnFire => current_streak(hand.history, StreakKind::Win) >= 10,
+            Achievement::ColdDeck => current_streak(hand.history, StreakKind::Loss) >= 5,
+            Achievement::ComebackKid => {
+                progress.down_to_one_coin && round.money_after >= COMEBACK_TARGET
+            }
+            Achievement::DoubleUp => round.money_after >= 200,
+            Achievement::HighRoller => round.money_after >= 1000,
+            Achievement::Whale => round.money_after >= 10_000,
+            Achievement::AllIn => {
+                won && round.base_bet() - round.free_bet_amount >= hand.money_before
+            }
+            Achievement::BigScore => round.money_change >= 500,
+            Achievement::OnTheHouse => won && round.free_bet_amount > 0,
+            Achievement::Student => progress.perfect_hands >= 25,
+            Achievement::ByTheBook => progress.perfect_hands >= 100,
+            Achievement::TableHopper => GameVariant::ALL
+                .iter()
+                .all(|variant| progress.variants_played.contains(variant)),
+            Achievement::NightOwl => round.timestamp.hour() < 4,
+        }
+    }
+}
+
+/// What is known about a hand when it has just been recorded
+pub struct HandContext<'a> {
+    pub round: &'a GameRound,
+    /// Every hand the profile has played, ending with `round`
+    pub history: &'a GameHistory,
+    /// The hands of `round`'s own round settled so far, ending with `round`
+    pub round_hands: &'a [GameRound],
+    pub variant: GameVariant,
+    /// Bankroll when the hand's bet was placed
+    pub money_before: i32,
+    /// Hands played so far in this session, including `round`
+    pub session_hands: u32,
+}
+
+fn is_win(outcome: GameOutcome) -> bool {
+    matches!(outcome, GameOutcome::PlayerWin | GameOutcome::DealerBust)
+}
+
+fn is_natural(round: &GameRound) -> bool {
+    !round.was_split
+        && !round.was_switched
+        && round.player_cards.len() == 2
+        && hand_value(&round.player_cards) == 21
+}
+
+fn rank(card: &str) -> &str {
+    card.split_whitespace().next().unwrap_or("")
+}
+
+/// Length of the streak of `kind` the last hand in `history` ends, or 0
+fn current_streak(history: &GameHistory, kind: StreakKind) -> usize {
+    StreakStats::from_rounds(&history.rounds)
+        .current()
+        .filter(|streak| streak.kind == kind)
+        .map_or(0, |streak| streak.length)
+}
+
+/// Whether `hand` completes a split whose every hand has won
+fn split_hands_all_won(hand: &HandContext) -> bool {
+    let mut split_hands = hand.round_hands.iter().filter(|round| round.was_split);
+    split_hands.clone().count() >= 2 && split_hands.all(|round| is_win(round.outcome))
+}
+
+/// Counters kept between hands for achievements that span several of them
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Progress {
+    /// Hands in a row, at a classic table, played by basic strategy
+    pub perfect_hands: u32,
+    /// Whether the bankroll has dropped to a single coin without going
+    /// broke since
+    pub down_to_one_coin: bool,
+    pub variants_played: Vec<GameVariant>,
+}
+
+impl Progress {
+    /// Update the counters with the hand in `hand`
+    pub fn record_hand(&mut self, hand: &HandContext) {
+        let round = hand.round;
+        if hand.variant == GameVariant::Classic && !round.was_split {
+            let actions: Option<Vec<StrategyAction>> = round
+                .actions
+                .chars()
+                .map(StrategyAction::from_code)
+                .collect();
+            let judged = actions
+                .filter(|actions| !actions.is_empty())
+                .and_then(|actions| {
+                    let upcard = round.dealer_cards.first()?;
+                    let can_afford = hand.money_before >= 2 * round.base_bet();
+                    follows_basic_strategy(&round.player_cards, upcard, &actions, can_afford)
+                });
+            match judged {
+                Some(true) => self.perfect_hands += 1,
+                Some(false) => self.perfect_hands = 0,
+                None => {}
+            }
+        }
+
+        match round.money_after {
+            0 => self.down_to_one_coin = false,
+            1 => self.down_to_one_coin = true,
+            _ => {}
+        }
+
+        if !self.variants_played.contains(&hand.variant) {
+            self.variants_played.push(hand.variant);
+        }
+    }
+}
+
+/// An achievement and when it was unlocked
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Unlocked {
+    pub achievement: Achievement,
+    pub at: DateTime<Local>,
+}
+
+/// Everything a profile has unlocked, and its progress towards the rest
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AchievementBook {
+    pub unlocked: Vec<Unlocked>,
+    pub progress: Progress,
+}
+
+impl AchievementBook {
+    #[must_use]
+    pub fn is_unlocked(&self, achievement: Achievement) -> bool {
+        self.unlocked
+            .iter()
+            .any(|unlocked| unlocked.achievement == achievement)
+    }
+
+    /// Count the hand in `hand` and unlock what it earns. Returns the
+    /// achievements that were newly unlocked.
+    pub fn record_hand(&mut self, hand: &HandContext) -> Vec<Achievement> {
+        self.progress.record_hand(hand);
+        let earned: Vec<Achievement> = Achievement::ALL
+            .into_iter()
+            .filter(|&achievement| {
+                !self.is_unlocked(achievement) && achievement.is_earned(hand, &self.progress)
+            })
+            .collect();
+        let now = Local::now();
+        self.unlocked
+            .extend(earned.iter().map(|&achievement| Unlocked {
+                achievement,
+                at: now,
+            }));
+        earned
+    }
+}
+
+/// Check the hand `state` has just recorded against the achievements of
+/// its profile, announcing and storing any it unlocks
+pub fn check_hand(state: &GameState) {
+    let (Some(profile), Some(round)) = (&state.profile, state.history.rounds.last()) else {
+        return;
+    };
+    let path = profile.achievements_path();
+    let mut book: AchievementBook = match safe_file::read_json(&path) {
+        Ok(book) => book.unwrap_or_default(),
+        Err(e) => {
+            eprintln!("⚠ Warning: Could not read your achievements: {e}");
+            return;
+        }
+    };
+
+    // Hands are recorded one by one as the round settles, so the round's
+    // earlier hands are the ones just before this one
+    let settled_before = match state.round_phase {
+        RoundPhase::Settling(settled) => settled,
+        _ => 0,
+    };
+    let rounds = &state.history.rounds;
+    let hand = HandContext {
+        round,
+        history: &state.history,
+        round_hands: &rounds[rounds.len().saturating_sub(settled_before + 1)..],
+        variant: state.rules.variant,
+        money_before: state.current_round_start_money,
+        session_hands: state
+            .session
+            .as_ref()
+            .map_or(0, |session| session.hands_played),
+    };
+    let earned = book.record_hand(&hand);
+    if let Err(e) = safe_file::write_json(&path, &book) {
+        eprintln!("⚠ Warning: Could not save your achievements: {e}");
+    }
+    for achievement in earned {
+        announce(achievement);
+    }
+}
+
+fn announce(achievement: Achievement) {
+    println!("\n\x1b[1;33m{}\x1b[0m", get_achievement_art());
+    println!(
+        "\x1b[1;33m  {}\x1b[0m - {}\n",
+        achievement.name(),
+        achievement.description()
+    );
+}
+
+/// Show every achievement of `profile`, unlocked or not, with progress
+/// towards the basic strategy ones
+pub fn show_achievements(profile: &Profile) {
+    let book: AchievementBook = safe_file::read_json(&profile.achievements_path())
+        .unwrap_or_else(|e| {
+            eprintln!("⚠ Warning: Could not read your achievements: {e}");
+            None
+        })
+        .unwrap_or_default();
+
+    let mut formatter = BoxFormatter::new(
+        66,
+        &format!(
+            "ACHIEVEMENTS ({}/{})",
+            book.unlocked.len(),
+            Achievement::ALL.len()
+        ),
+    );
+    for achievement in Achievement::ALL {
+        let unlocked = book
+            .unlocked
+            .iter()
+            .find(|unlocked| unlocked.achievement == achievement);
+        let (mark, date) = unlocked.map_or(("[ ]", String::new()), |unlocked| {
+            ("[x]", unlocked.at.format("%Y-%m-%d").to_string())
+        });
+        formatter.add_line(&format!("{mark} {:<47}{date:>10}", achievement.name()));
+        formatter.add_line(&format!("    {}", achievement.description()));
+    }
+    formatter.add_separator();
+    formatter.add_field_aligned(
+        "Hands in a row by basic strategy",
+        &book.progress.perfect_hands,
+    );
+    formatter.add_field_aligned(
+        "Table variants played",
+        &format!(
+            "{}/{}",
+            book.progress.variants_played.len(),
+            GameVariant::ALL.len()
+        ),
+    );
+    println!("\n{}\n", formatter.build());
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn cards(names: &[&str]) -> Vec<String> {
+        names.iter().map(ToString::to_string).collect()
+    }
+
+    /// A 10 coin Classic hand played at noon
+    fn hand(player: &[&str], dealer: &[&str], outcome: GameOutcome, money_after: i32) -> GameRound {
+        let player_cards = cards(player);
+        let dealer_cards = cards(dealer);
+        GameRound {
+            round_number: 0,
+            session: 1,
+            timestamp: Local.with_ymd_and_hms(2026, 6, 15, 12, 0, 0).unwrap(),
+            variant: GameVariant::Classic,
+            bet_amount: 10,
+            free_bet_amount: 0,
+            free_double_amount: 0,
+            player_total: hand_value(&player_cards),
+            dealer_total: hand_value(&dealer_cards),
+            player_busted: hand_value(&player_cards) > 21,
+            dealer_busted: hand_value(&dealer_cards) > 21,
+            player_cards,
+            dealer_cards,
+            outcome,
+            money_change: if is_win(outcome) { 10 } else { -10 },
+            money_after,
+            was_double_down: false,
+            was_split: false,
+            was_switched: false,
+            actions: "S".to_string(),
+        }
+    }
+
+    fn win(player: &[&str]) -> GameRound {
+        hand(
+            player,
+            &["10 Spades", "8 Clubs"],
+            GameOutcome::PlayerWin,
+            110,
+        )
+    }
+
+    fn loss() -> GameRound {
+        hand(
+            &["10 Hearts", "7 Clubs"],
+            &["10 Spades", "8 Clubs"],
+            GameOutcome::DealerWin,
+            90,
+        )
+    }
+
+    /// A profile's achievements and the history they were earned over
+    #[derive(Default)]
+    struct Player {
+        book: AchievementBook,
+        history: GameHistory,
+    }
+
+    impl Player {
+        /// Record the hands of one round, returning what each unlocked
+        fn play_round(
+            &mut self,
+            hands: Vec<GameRound>,
+            money_before: i32,
+        ) -> Vec<Vec<Achievement>> {
+            let start = self.history.rounds.len();
+            hands
+                .into_iter()
+                .map(|round| {
+                    self.history.add_round(round);
+                    let rounds = &self.history.rounds;
+                    self.book.record_hand(&HandContext {
+                        round: &rounds[rounds.len() - 1],
+                        history: &self.history,
+                        round_hands: &rounds[start..],
+                        variant: GameVariant::Classic,
+                        money_before,
+                        session_hands: self.history.total_games_played,
+                    })
+                })
+                .collect()
+        }
+
+        fn play(&mut self, round: GameRound) -> Vec<Achievement> {
+            self.play_round(vec![round], 100).remove(0)
+        }
+    }
+
+    #[test]
+    fn first_blackjack_is_unlocked_once_and_not_by_a_switched_21() {
+        let mut player = Player::default();
+        let mut switched = win(&["A Hearts", "K Clubs"]);
+        switched.was_switched = true;
+        assert!(!player.play(switched).contains(&Achievement::FirstBlackjack));
+
+        let earned = player.play(win(&["A Spades", "Q Hearts"]));
+        assert!(earned.contains(&Achievement::FirstBlackjack));
+        assert!(!player
+            .play(win(&["A Clubs", "J Diamonds"]))
+            .contains(&Achievement::FirstBlackjack));
+    }
+
+    #[test]
+    fn five_card_hand_must_win() {
+        let five_cards = ["2 Hearts", "3 Clubs", "4 Spades", "2 Clubs", "9 Hearts"];
+        let mut player = Player::default();
+        let mut lost = win(&five_cards);
+        lost.outcome = GameOutcome::DealerWin;
+        assert!(!player.play(lost).contains(&Achievement::FiveCardWin));
+        assert!(player
+            .play(win(&five_cards))
+            .contains(&Achievement::FiveCardWin));
+    }
+
+    #[test]
+    fn both_split_hands_of_one_round_must_win() {
+        let split = |mut round: GameRound| {
+            round.was_split = true;
+            round
+        };
+        let mut player = Player::default();
+
+        // Split hands won in different rounds against the same dealer cards
+        player.play(split(win(&["8 Hearts", "K Clubs"])));
+        let earned = player.play(split(win(&["8 Spades", "Q Clubs"])));
+        assert!(!earned.contains(&Achievement::SplitBothWin));
+
+        let earned = player.play_round(
+            vec![split(loss()), split(win(&["8 Spades", "Q Clubs"]))],
+            100,
+        );
+        assert!(!earned[1].contains(&Achievement::SplitBothWin));
+
+        let earned = player.play_round(
+            vec![
+                split(win(&["8 Hearts", "K Clubs"])),
+                split(win(&["8 Spades", "Q Clubs"])),
+            ],
+            100,
+        );
+        assert!(!earned[0].contains(&Achievement::SplitBothWin));
+        assert!(earned[1].contains(&Achievement::SplitBothWin));
+    }
+
+    #[test]
+    fn comeback_kid_needs_one_coin_then_100() {
+        let mut player = Player::default();
+        let mut at_100 = win(&["10 Hearts", "9 Clubs"]);
+        at_100.money_after = 100;
+        assert!(!player
+            .play(at_100.clone())
+            .contains(&Achievement::ComebackKid));
+
+        let mut at_one = loss();
+        at_one.money_after = 1;
+        player.play(at_one);
+        let mut at_50 = win(&["10 Hearts", "9 Clubs"]);
+        at_50.money_after = 50;
+        assert!(!player.play(at_50).contains(&Achievement::ComebackKid));
+        assert!(player.play(at_100).contains(&Achievement::ComebackKid));
+    }
+
+    #[test]
+    fn ten_wins_in_a_row_are_on_fire() {
+        let mut player = Player::default();
+        player.play(win(&["10 Hearts", "9 Clubs"]));
+        player.play(loss());
+        for wins in 1..=10 {
+            let earned = player.play(win(&["10 Hearts", "9 Clubs"]));
+            assert_eq!(earned.contains(&Achievement::HotHand), wins == 5);
+            assert_eq!(earned.contains(&Achievement::OnFire), wins == 10);
+        }
+    }
+
+    #[test]
+    fn hundred_hands_by_basic_strategy_in_a_row() {
+        let mut player = Player::default();
+        let by_the_book = || win(&["10 Hearts", "9 Clubs"]);
+        for _ in 0..30 {
+            player.play(by_the_book());
+        }
+        // Hitting a hard 19 breaks the run
+        let mut mistake = hand(
+            &["10 Hearts", "9 Clubs", "2 Spades"],
+            &["10 Spades", "8 Clubs"],
+            GameOutcome::PlayerWin,
+            110,
+        );
+        mistake.actions = "HS".to_string();
+        player.play(mistake);
+        assert_eq!(player.book.progress.perfect_hands, 0);
+        assert!(player.book.is_unlocked(Achievement::Student));
+
+        for hands in 1..=100 {
+            let earned = player.play(by_the_book());
+            assert_eq!(earned.contains(&Achievement::ByTheBook), hands == 100);
+        }
+    }
+}
@@ -62,9 +62,39 @@ pub fn get_splash_screen() -> String {
     splash_lines.join("\n")
 }
 
+#[must_use]
+pub fn get_achievement_art() -> String {
+    let sections = load_art_sections();
+    let Some(art_lines) =
+        sections.get("// --- Achievement Unlocked Art (from achievements.rs) ---")
+    else {
+        eprintln!("Warning: Achievement art section missing. Using fallback.");
+        return "🏆 ACHIEVEMENT UNLOCKED! 🏆".to_string();
+    };
+    art_lines.join("\n").trim_end().to_string()
+}
+
+/// Look up a game message. When a state is given and its table variant has
+/// its own message set, a message from that set takes precedence.
 #[must_use]
 pub fn get_message(key: &str, state: Option<&GameState>) -> String {
     let sections = load_art_sections();
+
+    // Variant message sets map default text to replacement text, one
+    // "default => variant" pair per line
+    if let Some(lines) = state
+        .and_then(|s| s.rules.variant.message_section())
+        .and_then(|header| sections.get(header))
+    {
+        for line in lines {
+            if let Some((default, variant)) = line.split_once(" => ") {
+                if default.contains(key) {
+                    return fill_placeholders(variant.to_string(), state);
+                }
+            }
+        }
+    }
+
     let Some(msg_lines) =
         sections.get("// --- Game Prompts and Messages (from card_handler.rs) ---")
     else {
@@ -73,30 +103,33 @@ pub fn get_message(key: &str, state: Option<&GameState>) -> String {
     };
     for line in msg_lines {
         if line.contains(key) {
-            let mut msg = line.to_string();
-            if let Some(s) = state {
-                msg = msg
-                    .replace("{{money}}", &s.money.to_string())
-                    .replace("{{gamesWon}}", &s.games_won.to_string())
-                    .replace("{{gamesLost}}", &s.games_lost.to_string())
-                    .replace("{{bet}}", &s.bet.to_string());
-                if !s.dealer_cards.is_empty() {
-                    msg = msg.replace("{{dealerCard}}", &s.dealer_cards[0]);
-                }
-                if !s.player_cards.is_empty() {
-                    msg = msg.replace("{{playerCards}}", &s.player_cards.join(", "));
-                }
-                msg = msg
-                    .replace("{{playerTotal}}", &hand_value(&s.player_cards).to_string())
-                    .replace("{{dealerTotal}}", &hand_value(&s.dealer_cards).to_string())
-                    .replace("{{dealerCards}}", &s.dealer_cards.join(", "));
-            }
-            return msg;
+            return fill_placeholders(line.to_string(), state);
         }
     }
     format!("[{key}]")
 }
 
+fn fill_placeholders(mut msg: String, state: Option<&GameState>) -> String {
+    if let Some(s) = state {
+        msg = msg
+            .replace("{{money}}", &s.money.to_string())
+            .replace("{{gamesWon}}", &s.games_won.to_string())
+            .replace("{{gamesLost}}", &s.games_lost.to_string())
+            .replace("{{bet}}", &s.bet.to_string());
+        if !s.dealer_cards.is_empty() {
+            msg = msg.replace("{{dealerCard}}", &s.dealer_cards[0]);
+        }
+        if !s.player_cards.is_empty() {
+            msg = msg.replace("{{playerCards}}", &s.player_cards.join(", "));
+        }
+        msg = msg
+            .replace("{{playerTotal}}", &hand_value(&s.player_cards).to_string())
+            .replace("{{dealerTotal}}", &hand_value(&s.dealer_cards).to_string())
+            .replace("{{dealerCards}}", &s.dealer_cards.join(", "));
+    }
+    msg
+}
+
 pub fn print_game_status(state: &GameState) {
     println!("\n{}", get_message("You have", Some(state)));
 }
@@ -0,0 +1,236 @@
+use crate::game_history_core::GameRound;
+use chrono::TimeDelta;
+use std::env;
+use terminal_size::{terminal_size, Width};
+
+/// Rows of braille cells in the chart itself
+pub const CHART_HEIGHT: usize = 12;
+
+/// Width used when the terminal size cannot be found
+const DEFAULT_COLUMNS: usize = 80;
+
+/// Hands from before sessions were tracked this far apart in time are
+/// counted as different sessions
+const SESSION_GAP_MINUTES: i64 = 30;
+
+/// Each braille cell holds a grid of dots two wide and four high
+const DOTS_WIDE: usize = 2;
+const DOTS_HIGH: usize = 4;
+
+/// Width of the terminal in columns: `$COLUMNS` if set, otherwise the size
+/// of the terminal on stdout, otherwise 80
+#[must_use]
+pub fn terminal_width() -> usize {
+    if let Some(columns) = env::var("COLUMNS")
+        .ok()
+        .and_then(|columns| columns.trim().parse().ok())
+    {
+        return columns;
+    }
+    terminal_size().map_or(DEFAULT_COLUMNS, |(Width(columns), _)| usize::from(columns))
+}
+
+/// Positions in `rounds` where a new session starts. Hands from before
+/// sessions were tracked are split into sessions at long pauses.
+#[must_use]
+pub fn session_starts(rounds: &[GameRound]) -> Vec<usize> {
+    rounds
+        .windows(2)
+        .enumerate()
+        .filter(|(_, pair)| {
+            let (before, after) = (&pair[0], &pair[1]);
+            if before.session == 0 && after.session == 0 {
+                after.timestamp - before.timestamp >= TimeDelta::minutes(SESSION_GAP_MINUTES)
+            } else {
+                before.session != after.session
+            }
+        })
+        .map(|(i, _)| i + 1)
+        .collect()
+}
+
+/// A chart of the bankroll after each of `rounds`, at most `columns` wide:
+/// a braille line with the highest and lowest points marked, ticks where
+/// sessions start and the dates of the first and last hand.
+#[must_use]
+pub fn render(rounds: &[GameRound], columns: usize) -> Vec<String> {
+    let values: Vec<i32> = rounds.iter().map(|round| round.money_after).collect();
+    let (Some(first), Some(last)) = (rounds.first(), rounds.last()) else {
+        return Vec::new();
+    };
+    let (max_index, max) = extreme(&values, |value, best| value > best);
+    let (min_index, min) = extreme(&values, |value, best| value < best);
+
+    let label_width = max.to_string().len().max(min.to_string().len());
+    let width = columns.saturating_sub(label_width + 2).max(10);
+    let cells = braille_line(&values, width, CHART_HEIGHT);
+    let column_of = |index: usize| pixel_x(index, values.len(), width * DOTS_WIDE) / DOTS_WIDE;
+
+    let mut lines: Vec<String> = cells
+        .into_iter()
+        .enumerate()
+        .map(|(row, cells)| {
+            let (label, axis) = if row == 0 {
+                (max.to_string(), '┤')
+            } else if row == CHART_HEIGHT - 1 {
+                (min.to_string(), '┤')
+            } else {
+                (String::new(), '│')
+            };
+            format!("{label:>label_width$} {axis}{cells}")
+        })
+        .collect();
+
+    let sessions = session_starts(rounds);
+    let mut axis = vec!['─'; width];
+    for &start in &sessions {
+        axis[column_of(start)] = '┴';
+    }
+    lines.push(format!(
+        "{:label_width$} └{}",
+        "",
+        axis.into_iter().collect::<String>()
+    ));
+
+    let mut markers = vec![' '; width];
+    markers[column_of(min_index)] = 'L';
+    markers[column_of(max_index)] = 'H';
+    lines.push(format!(
+        "{:label_width$}  {}",
+        "",
+        markers.into_iter().collect::<String>()
+    ));
+
+    let first_date = first.timestamp.format("%Y-%m-%d %H:%M").to_string();
+    let last_date = last.timestamp.format("%Y-%m-%d %H:%M").to_string();
+    let gap = width
+        .saturating_sub(first_date.len() + last_date.len())
+        .max(1);
+    lines.push(format!(
+        "{:label_width$}  {first_date}{}{last_date}",
+        "",
+        " ".repeat(gap)
+    ));
+
+    lines.push(String::new());
+    lines.push(format!(
+        "H high {max} on {}   L low {min} on {}{}",
+        rounds[max_index].timestamp.format("%Y-%m-%d %H:%M"),
+        rounds[min_index].timestamp.format("%Y-%m-%d %H:%M"),
+        if sessions.is_empty() {
+            ""
+        } else {
+            "   ┴ new session"
+        }
+    ));
+    lines
+}
+
+/// Index and value of the first value that beats every other one
+fn extreme(values: &[i32], beats: impl Fn(i32, i32) -> bool) -> (usize, i32) {
+    values
+        .iter()
+        .copied()
+        .enumerate()
+        .fold((0, values[0]), |best, (i, value)| {
+            if beats(value, best.1) {
+                (i, value)
+            } else {
+                best
+            }
+        })
+}
+
+/// Dot column for the value at `index` of `count` values spread over
+/// `dots` columns
+fn pixel_x(index: usize, count: usize, dots: usize) -> usize {
+    if count <= 1 {
+        0
+    } else if count <= dots {
+        index * (dots - 1) / (count - 1)
+    } else {
+        index * dots / count
+    }
+}
+
+/// `height` rows of `width` braille cells tracing `values` from left to
+/// right, highest row first. When there are more values than dot columns,
+/// each column covers the full range of the values that fall in it.
+#[allow(
+    clippy::cast_possible_truncation,
+    clippy::cast_possible_wrap,
+    clippy::cast_sign_loss
+)]
+fn braille_line(values: &[i32], width: usize, height: usize) -> Vec<String> {
+    let dots_wide = width * DOTS_WIDE;
+    let dots_high = height * DOTS_HIGH;
+    let min = values.iter().copied().min().unwrap_or(0);
+    let max = values.iter().copied().max().unwrap_or(0);
+    let span = i64::from(max - min).max(1);
+    let to_y = |value: i32| {
+        let from_top = i64::from(max - value) * (dots_high as i64 - 1) / span;
+        usize::try_from(from_top).unwrap_or(0)
+    };
+
+    // The lowest and highest dot to fill in each dot column
+    let mut columns: Vec<Option<(usize, usize)>> = vec![None; dots_wide];
+    let mut mark = |x: usize, y_from: usize, y_to: usize| {
+        let (top, bottom) = (y_from.min(y_to), y_from.max(y_to));
+        let column = &mut columns[x];
+        *column = Some(column.map_or((top, bottom), |(t, b)| (t.min(top), b.max(bottom))));
+    };
+
+    let mut previous: Option<(usize, usize)> = None;
+    for (i, &value) in values.iter().enumerate() {
+        let x = pixel_x(i, values.len(), dots_wide);
+        let y = to_y(value);
+        match previous {
+            Some((px, py)) if x > px => {
+                // Fill each column up to this point so the line stays joined
+                let run = (x - px) as i64;
+                let at = |step: i64| {
+                    let y = py as i64 + (y as i64 - py as i64) * step / run;
+                    usize::try_from(y).unwrap_or(0)
+                };
+                for step in 1..=run {
+                    mark(px + step as usize, at(step - 1), at(step));
+                }
+            }
+            Some((_, py)) => mark(x, py, y),
+            None => mark(x, y, y),
+        }
+        previous = Some((x, y));
+    }
+
+    (0..height)
+        .map(|row| {
+            (0..width)
+                .map(|cell| {
+                    let mut bits = 0u32;
+                    for dx in 0..DOTS_WIDE {
+                        let Some((top, bottom)) = columns[cell * DOTS_WIDE + dx] else {
+                            continue;
+                        };
+                        for dy in 0..DOTS_HIGH {
+                            let y = row * DOTS_HIGH + dy;
+                            if (top..=bottom).contains(&y) {
+                                bits |= braille_bit(dx, dy);
+                            }
+                        }
+                    }
+                    char::from_u32(0x2800 + bits).unwrap_or(' ')
+                })
+                .collect()
+        })
+        .collect()
+}
+
+/// The bit of a braille character for the dot at column `dx`, row `dy`
+fn braille_bit(dx: usize, dy: usize) -> u32 {
+    match (dx, dy) {
+        (0, 3) => 0x40,
+        (1, 3) => 0x80,
+        (0, dy) => 1 << dy,
+        (_, dy) => 1 << (dy + 3),
+    }
+}
@@ -0,0 +1,198 @@
+use crate::game_state::GameState;
+use crate::player_handler::{draw, hand_value, is_soft_hand};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// A playing decision for a hand
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum StrategyAction {
+    Hit,
+    Stand,
+    Double,
+    Split,
+    Surrender,
+}
+
+impl StrategyAction {
+    /// One-letter code used in compact action logs
+    #[must_use]
+    pub fn code(self) -> char {
+        match self {
+            StrategyAction::Hit => 'H',
+            StrategyAction::Stand => 'S',
+            StrategyAction::Double => 'D',
+            StrategyAction::Split => 'P',
+            StrategyAction::Surrender => 'R',
+        }
+    }
+
+    /// The action logged as `code`, if any
+    #[must_use]
+    pub fn from_code(code: char) -> Option<Self> {
+        match code {
+            'H' => Some(StrategyAction::Hit),
+            'S' => Some(StrategyAction::Stand),
+            'D' => Some(StrategyAction::Double),
+            'P' => Some(StrategyAction::Split),
+            'R' => Some(StrategyAction::Surrender),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for StrategyAction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            StrategyAction::Hit => write!(f, "hit"),
+            StrategyAction::Stand => write!(f, "stand"),
+            StrategyAction::Double => write!(f, "double down"),
+            StrategyAction::Split => write!(f, "split"),
+            StrategyAction::Surrender => write!(f, "surrender"),
+        }
+    }
+}
+
+/// Value of the dealer's upcard, counting an ace as 11
+#[must_use]
+pub fn upcard_value(card: &str) -> i32 {
+    match card.split_whitespace().next() {
+        Some("A") => 11,
+        Some("K" | "Q" | "J") => 10,
+        Some(rank) => rank.parse::<i32>().unwrap_or(0),
+        None => 0,
+    }
+}
+
+/// Basic strategy for a dealer who hits soft 17. `can_double` and
+/// `can_split` say whether those moves are available for this hand.
+#[must_use]
+pub fn recommended_action(
+    cards: &[String],
+    dealer_upcard: &str,
+    can_double: bool,
+    can_split: bool,
+) -> StrategyAction {
+    let up = upcard_value(dealer_upcard);
+    let two_cards = cards.len() == 2;
+
+    if can_split && two_cards {
+        if let Some(action) = pair_action(&cards[0], &cards[1], up) {
+            return action;
+        }
+    }
+
+    let total = hand_value(cards);
+    let double_or = |fallback| {
+        if can_double && two_cards {
+            StrategyAction::Double
+        } else {
+            fallback
+        }
+    };
+
+    if is_soft_hand(cards) {
+        match total {
+            13 | 14 if (5..=6).contains(&up) => double_or(StrategyAction::Hit),
+            15 | 16 if (4..=6).contains(&up) => double_or(StrategyAction::Hit),
+            17 if (3..=6).contains(&up) => double_or(StrategyAction::Hit),
+            18 if (2..=6).contains(&up) => double_or(StrategyAction::Stand),
+            18 if (7..=8).contains(&up) => StrategyAction::Stand,
+            19 if up == 6 => double_or(StrategyAction::Stand),
+            19.. => StrategyAction::Stand,
+            _ => StrategyAction::Hit,
+        }
+    } else {
+        match total {
+            9 if (3..=6).contains(&up) => double_or(StrategyAction::Hit),
+            10 if up <= 9 => double_or(StrategyAction::Hit),
+            11 => double_or(StrategyAction::Hit),
+            12 if (4..=6).contains(&up) => StrategyAction::Stand,
+            13..=16 if up <= 6 => StrategyAction::Stand,
+            17.. => StrategyAction::Stand,
+            _ => StrategyAction::Hit,
+        }
+    }
+}
+
+/// Play `cards` out by basic strategy without splitting, drawing from the
+/// deck in `state`. Returns the actions taken, in order.
+pub fn play_hand(
+    state: &mut GameState,
+    cards: &mut Vec<String>,
+    can_double: bool,
+) -> Vec<StrategyAction> {
+    let upcard = state.dealer_cards[0].clone();
+    let mut actions = Vec::new();
+    loop {
+        let action = recommended_action(cards, &upcard, can_double, false);
+        actions.push(action);
+        match action {
+            StrategyAction::Hit => {
+                cards.push(draw(state));
+                if hand_value(cards) > 21 {
+                    break;
+                }
+            }
+            StrategyAction::Double => {
+                cards.push(draw(state));
+                break;
+            }
+            _ => break,
+        }
+    }
+    actions
+}
+
+/// Whether each of `actions`, taken in order on a hand dealt the first two
+/// of `cards`, is the move basic strategy recommends. `can_afford` says
+/// whether there were coins enough to double or split. Returns `None` if the
+/// actions do not fit the cards, such as for a hand that was split.
+#[must_use]
+pub fn follows_basic_strategy(
+    cards: &[String],
+    dealer_upcard: &str,
+    actions: &[StrategyAction],
+    can_afford: bool,
+) -> Option<bool> {
+    let mut hand = cards.get(..2)?.to_vec();
+    let mut drawn = cards[2..].iter();
+    for &action in actions {
+        if hand_value(&hand) > 21 {
+            return None;
+        }
+        let first_decision = hand.len() == 2;
+        let recommended = recommended_action(
+            &hand,
+            dealer_upcard,
+            can_afford && first_decision,
+            can_afford && first_decision,
+        );
+        if action != recommended {
+            return Some(false);
+        }
+        match action {
+            StrategyAction::Hit | StrategyAction::Double => hand.push(drawn.next()?.clone()),
+            StrategyAction::Stand => break,
+            StrategyAction::Split | StrategyAction::Surrender => return None,
+        }
+    }
+    Some(true)
+}
+
+/// Whether to split a pair, or `None` to play it as a normal total
+fn pair_action(first: &str, second: &str, up: i32) -> Option<StrategyAction> {
+    let rank = first.split_whitespace().next().unwrap_or("");
+    if rank != second.split_whitespace().next().unwrap_or("") {
+        return None;
+    }
+
+    let split = match rank {
+        "A" | "8" => true,
+        "9" => (2..=9).contains(&up) && up != 7,
+        "7" | "3" | "2" => up <= 7,
+        "6" => up <= 6,
+        "4" => (5..=6).contains(&up),
+        _ => false,
+    };
+    split.then_some(StrategyAction::Split)
+}
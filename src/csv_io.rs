@@ -0,0 +1,232 @@
+use std::borrow::Cow;
+use std::fmt;
+
+/// One record of a CSV file
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CsvRecord {
+    /// Line the record starts on, counting from 1
+    pub line: usize,
+    pub fields: Vec<String>,
+}
+
+/// A row that could not be read, and why
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RowError {
+    pub line: usize,
+    pub message: String,
+}
+
+impl fmt::Display for RowError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "line {}: {}", self.line, self.message)
+    }
+}
+
+/// Quote a field if it contains a comma, quote or line break, doubling any
+/// quotes inside it
+#[must_use]
+pub fn escape_field(field: &str) -> Cow<'_, str> {
+    if field.contains([',', '"', '\n', '\r']) {
+        Cow::Owned(format!("\"{}\"", field.replace('"', "\"\"")))
+    } else {
+        Cow::Borrowed(field)
+    }
+}
+
+/// Join `fields` into one CSV record, without a line break
+#[must_use]
+pub fn write_row<S: AsRef<str>>(fields: &[S]) -> String {
+    fields
+        .iter()
+        .map(|field| escape_field(field.as_ref()))
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
+/// Split CSV text into records as described by RFC 4180. Quoted fields may
+/// contain commas, doubled quotes and line breaks; records may end in
+/// `\n` or `\r\n`; blank lines are skipped. A malformed record is reported
+/// with the line it starts on and reading carries on from the next line.
+#[must_use]
+pub fn parse(content: &str) -> Vec<Result<CsvRecord, RowError>> {
+    let mut rows = Vec::new();
+    let mut pos = 0;
+    let mut line = 1;
+    while pos < content.len() {
+        match parse_record(content, pos, line) {
+            Ok((record, next_pos, next_line)) => {
+                let is_blank = record.fields.len() == 1 && record.fields[0].is_empty();
+                if !is_blank {
+                    rows.push(Ok(record));
+                }
+                pos = next_pos;
+                line = next_line;
+            }
+            Err(message) => {
+                rows.push(Err(RowError { line, message }));
+                let Some(offset) = content[pos..].find('\n') else {
+                    break;
+                };
+                pos += offset + 1;
+                line += 1;
+            }
+        }
+    }
+    rows
+}
+
+/// Parse the record starting at byte `start`, which is on line `line`.
+/// Returns the record with the position and line just past it.
+fn parse_record(
+    content: &str,
+    start: usize,
+    line: usize,
+) -> Result<(CsvRecord, usize, usize), String> {
+    let bytes = content.as_bytes();
+    let mut pos = start;
+    let mut current_line = line;
+    let mut fields = Vec::new();
+
+    loop {
+        let mut field = String::new();
+        if bytes.get(pos) == Some(&b'"') {
+            pos += 1;
+            loop {
+                let Some(offset) = content[pos..].find('"') else {
+                    return Err(format!("field {} has no closing quote", fields.len() + 1));
+                };
+                let text = &content[pos..pos + offset];
+                current_line += text.matches('\n').count();
+                field.push_str(text);
+                pos += offset + 1;
+                if bytes.get(pos) == Some(&b'"') {
+                    field.push('"');
+                    pos += 1;
+                } else {
+                    break;
+                }
+            }
+            let rest = &content[pos..];
+            if !(rest.is_empty() || rest.starts_with([',', '\n']) || rest.starts_with("\r\n")) {
+                return Err(format!(
+                    "unexpected text after the closing quote of field {}",
+                    fields.len() + 1
+                ));
+            }
+        } else {
+            let end = content[pos..]
+                .find([',', '\n'])
+                .map_or(content.len(), |i| pos + i);
+            let text = &content[pos..end];
+            if bytes.get(end) == Some(&b',') {
+                field.push_str(text);
+            } else {
+                field.push_str(text.strip_suffix('\r').unwrap_or(text));
+            }
+            pos = end;
+        }
+        fields.push(field);
+
+        match bytes.get(pos) {
+            Some(b',') => pos += 1,
+            Some(b'\r') => {
+                pos += 2;
+                current_line += 1;
+                break;
+            }
+            Some(b'\n') => {
+                pos += 1;
+                current_line += 1;
+                break;
+            }
+            _ => break,
+        }
+    }
+
+    Ok((CsvRecord { line, fields }, pos, current_line))
+}
+
+/// Column positions taken from a header record, so columns can be found by
+/// name whatever order they are in
+#[derive(Debug, Clone)]
+pub struct CsvHeader {
+    names: Vec<String>,
+}
+
+impl CsvHeader {
+    #[must_use]
+    pub fn new(record: &CsvRecord) -> Self {
+        CsvHeader {
+            names: record
+                .fields
+                .iter()
+                .map(|name| name.trim().to_lowercase())
+                .collect(),
+        }
+    }
+
+    #[must_use]
+    pub fn has_column(&self, name: &str) -> bool {
+        self.names.iter().any(|n| n.eq_ignore_ascii_case(name))
+    }
+
+    /// The value of column `name` in `record`, or `None` if there is no
+    /// such column or the record is too short to have it
+    #[must_use]
+    pub fn get<'a>(&self, record: &'a CsvRecord, name: &str) -> Option<&'a str> {
+        let index = self
+            .names
+            .iter()
+            .position(|n| n.eq_ignore_ascii_case(name))?;
+        record.fields.get(index).map(String::as_str)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fields(rows: &[Result<CsvRecord, RowError>]) -> Vec<Vec<&str>> {
+        rows.iter()
+            .map(|row| {
+                row.as_ref()
+                    .unwrap()
+                    .fields
+                    .iter()
+                    .map(String::as_str)
+                    .collect()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn parse_reads_quoted_fields_and_line_endings() {
+        let rows = parse("a,\"b, \"\"c\"\"\",d\r\n\n\"multi\nline\",,\"\"\nlast");
+        assert_eq!(
+            fields(&rows),
+            [
+                vec!["a", "b, \"c\"", "d"],
+                vec!["multi\nline", "", ""],
+                vec!["last"],
+            ]
+        );
+        let lines: Vec<usize> = rows.iter().map(|row| row.as_ref().unwrap().line).collect();
+        assert_eq!(lines, [1, 3, 5]);
+    }
+
+    #[test]
+    fn parse_reports_a_malformed_record_and_carries_on() {
+        let rows = parse("a,\"b\"x,c\nd,e\n\"open");
+        assert_eq!(rows[0].as_ref().unwrap_err().line, 1);
+        assert_eq!(rows[1].as_ref().unwrap().fields, ["d", "e"]);
+        assert_eq!(rows[2].as_ref().unwrap_err().line, 3);
+        assert_eq!(rows.len(), 3);
+    }
+
+    #[test]
+    fn written_rows_parse_back_to_their_fields() {
+        let row = ["plain", "with, comma", "with \"quotes\"", "two\nlines", ""];
+        let rows = parse(&write_row(&row));
+        assert_eq!(fields(&rows), [row.to_vec()]);
+    }
+}
@@ -0,0 +1,371 @@
+use crate::csv_io::{self, CsvHeader, CsvRecord, RowError};
+use crate::deck_manager::create_keyed_deck;
+use crate::duplicate::{deal_deck, play_human_hand};
+use crate::formatting::format_money;
+use crate::game_state::GameState;
+use chrono::{Datelike, NaiveDate};
+use std::fs::{self, OpenOptions};
+use std::io::{self, Write};
+use std::path::Path;
+use std::str::FromStr;
+
+const DAILY_HISTORY_HEADER: &str = "Date,Hands,StartingChips,FinalChips,Wins,Losses,Ties,Finished";
+
+/// Columns a daily history needs; `Finished` is missing from older files
+const REQUIRED_COLUMNS: [&str; 7] = [
+    "Date",
+    "Hands",
+    "StartingChips",
+    "FinalChips",
+    "Wins",
+    "Losses",
+    "Ties",
+];
+
+/// Mixed into every daily deck key, so the daily shoes are not ones any
+/// other mode deals
+const DAILY_SALT: u64 = 0xB1AC_4A5C_DA11_7C4E;
+
+/// Settings for the challenge of one day, the same for every player
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DailyConfig {
+    pub date: NaiveDate,
+    pub hands: u32,
+    pub starting_chips: i32,
+    pub min_bet: i32,
+}
+
+impl DailyConfig {
+    #[must_use]
+    pub fn for_date(date: NaiveDate) -> Self {
+        DailyConfig {
+            date,
+            hands: 10,
+            starting_chips: 500,
+            min_bet: 10,
+        }
+    }
+
+    /// The deck hand `hand_number` of the day is dealt from
+    #[must_use]
+    pub fn deck_for_hand(&self, hand_number: u32) -> Vec<String> {
+        create_keyed_deck(hand_key(self.date, hand_number))
+    }
+}
+
+/// Key of the deck for hand `hand_number` on `date`: the salted day and
+/// hand number stretched to 256 bits with `SplitMix64`
+fn hand_key(date: NaiveDate, hand_number: u32) -> [u8; 32] {
+    let day = u64::try_from(date.num_days_from_ce()).unwrap_or(0);
+    let mut state = DAILY_SALT ^ (day << 32 | u64::from(hand_number));
+    let mut key = [0; 32];
+    for chunk in key.chunks_exact_mut(8) {
+        state = state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        chunk.copy_from_slice(&(z ^ (z >> 31)).to_le_bytes());
+    }
+    key
+}
+
+/// How the player did in the challenge of one day
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DailyResult {
+    pub date: NaiveDate,
+    pub hands_played: u32,
+    pub starting_chips: i32,
+    /// The score: chips left after the last hand
+    pub final_chips: i32,
+    pub wins: u32,
+    pub losses: u32,
+    pub ties: u32,
+    /// Whether the attempt ended in the game rather than by it being closed
+    pub finished: bool,
+}
+
+impl DailyResult {
+    /// The attempt at `config` before any hand is played
+    #[must_use]
+    pub fn started(config: &DailyConfig) -> Self {
+        DailyResult {
+            date: config.date,
+            hands_played: 0,
+            starting_chips: config.starting_chips,
+            final_chips: config.starting_chips,
+            wins: 0,
+            losses: 0,
+            ties: 0,
+            finished: false,
+        }
+    }
+
+    #[must_use]
+    pub fn net(&self) -> i32 {
+        self.final_chips - self.starting_chips
+    }
+
+    fn to_csv_line(&self) -> String {
+        csv_io::write_row(&[
+            self.date.format("%Y-%m-%d").to_string(),
+            self.hands_played.to_string(),
+            self.starting_chips.to_string(),
+            self.final_chips.to_string(),
+            self.wins.to_string(),
+            self.losses.to_string(),
+            self.ties.to_string(),
+            self.finished.to_string(),
+        ])
+    }
+
+    /// Read a result from a record of a file with the given header.
+    ///
+    /// # Errors
+    ///
+    /// Returns the record's line and the first value that is missing or
+    /// cannot be parsed.
+    pub fn from_csv_record(header: &CsvHeader, record: &CsvRecord) -> Result<Self, RowError> {
+        let date = column(header, record, "Date")?;
+        Ok(DailyResult {
+            date: NaiveDate::parse_from_str(date.trim(), "%Y-%m-%d").map_err(|_| RowError {
+                line: record.line,
+                message: format!("Date \"{date}\" is not a valid date"),
+            })?,
+            hands_played: parse_column(header, record, "Hands")?,
+            starting_chips: parse_column(header, record, "StartingChips")?,
+            final_chips: parse_column(header, record, "FinalChips")?,
+            wins: parse_column(header, record, "Wins")?,
+            losses: parse_column(header, record, "Losses")?,
+            ties: parse_column(header, record, "Ties")?,
+            // Older files only recorded attempts once they were over
+            finished: match header.get(record, "Finished").map(str::trim) {
+                None | Some("") => true,
+                Some(_) => parse_column(header, record, "Finished")?,
+            },
+        })
+    }
+}
+
+fn column<'a>(header: &CsvHeader, record: &'a CsvRecord, name: &str) -> Result<&'a str, RowError> {
+    header.get(record, name).ok_or_else(|| RowError {
+        line: record.line,
+        message: format!("no value for {name}"),
+    })
+}
+
+fn parse_column<T: FromStr>(
+    header: &CsvHeader,
+    record: &CsvRecord,
+    name: &str,
+) -> Result<T, RowError> {
+    let value = column(header, record, name)?;
+    value.trim().parse().map_err(|_| RowError {
+        line: record.line,
+        message: format!("{name} \"{value}\" is not valid"),
+    })
+}
+
+/// Daily results read from a history file, and the rows that could not be
+/// read
+#[derive(Debug, Default)]
+pub struct DailyHistory {
+    /// One result per date, oldest first
+    pub results: Vec<DailyResult>,
+    pub errors: Vec<RowError>,
+}
+
+impl DailyHistory {
+    /// Read a daily history from CSV text with a header row. A date's
+    /// attempt is recorded again after every hand, so its last row is the
+    /// one that counts.
+    #[must_use]
+    pub fn parse(content: &str) -> Self {
+        let mut history = DailyHistory::default();
+        let mut records = csv_io::parse(content).into_iter();
+
+        let header = match records.next() {
+            None => return history,
+            Some(Err(e)) => {
+                history.errors.push(e);
+                return history;
+            }
+            Some(Ok(record)) => CsvHeader::new(&record),
+        };
+        let missing: Vec<&str> = REQUIRED_COLUMNS
+            .into_iter()
+            .filter(|name| !header.has_column(name))
+            .collect();
+        if !missing.is_empty() {
+            history.errors.push(RowError {
+                line: 1,
+                message: format!("header is missing column(s) {}", missing.join(", ")),
+            });
+            return history;
+        }
+
+        for record in records {
+            match record.and_then(|record| DailyResult::from_csv_record(&header, &record)) {
+                Ok(result) => match history.results.iter_mut().find(|r| r.date == result.date) {
+                    Some(earlier) => *earlier = result,
+                    None => history.results.push(result),
+                },
+                Err(e) => history.errors.push(e),
+            }
+        }
+        history.results.sort_by_key(|r| r.date);
+        history
+    }
+
+    #[must_use]
+    pub fn result_for(&self, date: NaiveDate) -> Option<&DailyResult> {
+        self.results.iter().find(|result| result.date == date)
+    }
+}
+
+/// Play the challenge of `config.date`, recording the attempt in the daily
+/// history at `path` before the first hand and again after every hand, so
+/// closing the game part way still uses up the day. Every hand is dealt
+/// from its own deck keyed by the date, so the cards depend only on the
+/// day and the decisions made.
+///
+/// # Errors
+///
+/// Returns an error, without dealing a card, if the attempt cannot be
+/// recorded.
+pub fn play_daily_challenge(config: &DailyConfig, path: &Path) -> io::Result<DailyResult> {
+    let mut result = DailyResult::started(config);
+    save_daily_result(&result, path)?;
+    let mut table = GameState::with_money(config.starting_chips);
+
+    for hand_number in 1..=config.hands {
+        if table.money < config.min_bet {
+            println!("\nYou don't have enough chips for the minimum bet.");
+            break;
+        }
+        println!(
+            "\n=== Daily challenge {}: hand {hand_number} of {} ({} chips) ===",
+            config.date, config.hands, table.money
+        );
+        let Some(bet) = ask_player_bet(table.money, config.min_bet) else {
+            break;
+        };
+
+        deal_deck(&mut table, config.deck_for_hand(hand_number));
+        let hand = play_human_hand(&mut table, bet);
+        println!("Hand result: {}", format_money(hand.net));
+        result.hands_played += 1;
+        match hand.net {
+            net if net > 0 => result.wins += 1,
+            net if net < 0 => result.losses += 1,
+            _ => result.ties += 1,
+        }
+        result.final_chips = table.money;
+        if let Err(e) = save_daily_result(&result, path) {
+            eprintln!("⚠ Warning: Could not save your daily progress: {e}");
+        }
+    }
+
+    result.finished = true;
+    save_daily_result(&result, path)?;
+    Ok(result)
+}
+
+fn ask_player_bet(chips: i32, min_bet: i32) -> Option<i32> {
+    loop {
+        print!("Your bet ({min_bet}-{chips} chips, q to stop here): ");
+        io::stdout().flush().ok();
+        let mut line = String::new();
+        match io::stdin().read_line(&mut line) {
+            Ok(0) | Err(_) => return None,
+            Ok(_) => {}
+        }
+
+        let trimmed = line.trim();
+        if trimmed.eq_ignore_ascii_case("q") {
+            return None;
+        }
+        match trimmed.parse::<i32>() {
+            Ok(n) if (min_bet..=chips).contains(&n) => return Some(n),
+            _ => println!("Please bet between {min_bet} and {chips} chips."),
+        }
+    }
+}
+
+/// Append the latest state of a day's attempt to the daily history at
+/// `path`, where it replaces any earlier row for the same date.
+///
+/// # Errors
+///
+/// Returns an error if the history file cannot be opened or written.
+pub fn save_daily_result(result: &DailyResult, path: &Path) -> io::Result<()> {
+    let is_new = !path.exists();
+    let mut file = OpenOptions::new().create(true).append(true).open(path)?;
+    if is_new {
+        writeln!(file, "{DAILY_HISTORY_HEADER}")?;
+    }
+    writeln!(file, "{}", result.to_csv_line())?;
+    file.sync_all()
+}
+
+/// Load the daily history at `path`; a missing file is an empty history.
+///
+/// # Errors
+///
+/// Returns an error if the file exists but cannot be read.
+pub fn load_daily_history(path: &Path) -> io::Result<DailyHistory> {
+    match fs::read_to_string(path) {
+        Ok(content) => Ok(DailyHistory::parse(&content)),
+        Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(DailyHistory::default()),
+        Err(e) => Err(e),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::deck_manager::create_seeded_deck;
+
+    fn date() -> NaiveDate {
+        NaiveDate::from_ymd_opt(2026, 10, 19).unwrap()
+    }
+
+    #[test]
+    fn daily_deck_is_not_a_typed_seed() {
+        let config = DailyConfig::for_date(date());
+        let deck = config.deck_for_hand(1);
+        assert_eq!(deck, config.deck_for_hand(1));
+        assert_ne!(deck, config.deck_for_hand(2));
+        for seed in [20_261_019, 20_261_019 ^ 0x9E37_79B9_7F4A_7C15] {
+            assert_ne!(deck, create_seeded_deck(seed));
+        }
+    }
+
+    #[test]
+    fn last_row_for_a_date_counts() {
+        let config = DailyConfig::for_date(date());
+        let mut result = DailyResult::started(&config);
+        let mut csv = format!("{DAILY_HISTORY_HEADER}\n{}\n", result.to_csv_line());
+        result.hands_played = 3;
+        result.final_chips = 620;
+        result.finished = true;
+        csv.push_str(&result.to_csv_line());
+
+        let history = DailyHistory::parse(&csv);
+        assert!(history.errors.is_empty());
+        assert_eq!(history.results, [result]);
+    }
+
+    #[test]
+    fn columns_are_mapped_by_name_and_bad_rows_reported() {
+        let csv = "Ties,Wins,Losses,Date,Hands,StartingChips,FinalChips\n\
+                   1,5,4,2026-10-18,10,500,640\n\
+                   0,0,0,yesterday,0,500,500\n";
+        let history = DailyHistory::parse(csv);
+        assert_eq!(history.results.len(), 1);
+        assert_eq!(history.results[0].final_chips, 640);
+        assert_eq!(history.results[0].ties, 1);
+        assert!(history.results[0].finished);
+        assert_eq!(history.errors.len(), 1);
+        assert_eq!(history.errors[0].line, 3);
+    }
+}
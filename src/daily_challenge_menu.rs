@@ -0,0 +1,147 @@
+use crate::daily_challenge::{load_daily_history, play_daily_challenge, DailyConfig, DailyHistory};
+use crate::formatting::{format_money, BoxFormatter};
+use crate::profiles::Profile;
+use crate::text_handler;
+use chrono::Local;
+use std::io::{self, Write};
+use std::path::PathBuf;
+
+pub struct DailyChallengeMenu {
+    history_path: PathBuf,
+}
+
+impl DailyChallengeMenu {
+    #[must_use]
+    pub fn new(history_path: PathBuf) -> Self {
+        DailyChallengeMenu { history_path }
+    }
+
+    pub fn show_menu(&self) {
+        loop {
+            self.print_daily_menu();
+            let Ok(input) = text_handler::read_menu_input() else {
+                break;
+            };
+
+            match input.trim().to_lowercase().as_str() {
+                "p" => self.play_today(),
+                "r" => self.show_past_results(),
+                "b" | "back" => break,
+                _ => println!("Invalid option. Please try again."),
+            }
+        }
+    }
+
+    fn today() -> DailyConfig {
+        DailyConfig::for_date(Local::now().date_naive())
+    }
+
+    /// The daily history, warning about any rows that could not be read
+    fn load_history(&self) -> DailyHistory {
+        match load_daily_history(&self.history_path) {
+            Ok(history) => {
+                for error in &history.errors {
+                    eprintln!(
+                        "⚠ Warning: Skipped a row of {}: {error}",
+                        self.history_path.display()
+                    );
+                }
+                history
+            }
+            Err(e) => {
+                eprintln!("⚠ Warning: Could not read daily results: {e}");
+                DailyHistory::default()
+            }
+        }
+    }
+
+    fn print_daily_menu(&self) {
+        let config = Self::today();
+        let mut formatter = BoxFormatter::new(50, "DAILY CHALLENGE");
+        formatter.add_field_aligned("Date", &config.date);
+        formatter.add_field_aligned("Hands", &config.hands);
+        formatter.add_field_aligned("Starting chips", &config.starting_chips);
+        formatter.add_field_aligned("Minimum bet", &config.min_bet);
+        match self.load_history().result_for(config.date) {
+            Some(result) if result.finished => {
+                formatter.add_field_aligned("Today's score", &result.final_chips);
+            }
+            Some(result) => formatter.add_field_aligned(
+                "Today's score",
+                &format!("{} (unfinished)", result.final_chips),
+            ),
+            None => formatter.add_field_aligned("Today's score", &"not played yet"),
+        }
+        formatter.add_line("Classic rules. Everyone gets the same cards");
+        formatter.add_line("today; your score is the chips you end with.");
+        formatter.add_empty_line();
+        formatter.add_line("(p) Play today's challenge");
+        formatter.add_line("(r) Show past results");
+        formatter.add_line("(b) Back to main menu");
+
+        println!("\n{}", formatter.build());
+        print!("Choose an option: ");
+        io::stdout().flush().ok();
+    }
+
+    fn play_today(&self) {
+        let config = Self::today();
+        if let Some(result) = self.load_history().result_for(config.date) {
+            println!(
+                "You have already played today's challenge and scored {} chips; only the first attempt counts. Come back tomorrow!",
+                result.final_chips
+            );
+            return;
+        }
+
+        match play_daily_challenge(&config, &self.history_path) {
+            Ok(result) => println!(
+                "Daily challenge over: {} chips ({}) after {} hands.\n✓ Score saved to {}",
+                result.final_chips,
+                format_money(result.net()),
+                result.hands_played,
+                self.history_path.display()
+            ),
+            Err(e) => eprintln!("✗ Could not record today's attempt, so it was not started: {e}"),
+        }
+    }
+
+    fn show_past_results(&self) {
+        let results = self.load_history().results;
+        let Some(best) = results.iter().max_by_key(|r| r.final_chips) else {
+            println!("No daily challenges played yet!");
+            return;
+        };
+
+        let mut formatter = BoxFormatter::new(70, "PAST DAILY CHALLENGES");
+        for result in results.iter().rev().take(10) {
+            formatter.add_line(&format!(
+                "{}  {:>5} chips {:>7}  {}-{}-{} in {} hands{}",
+                result.date,
+                result.final_chips,
+                format_money(result.net()),
+                result.wins,
+                result.losses,
+                result.ties,
+                result.hands_played,
+                if result.finished { "" } else { " (unfinished)" }
+            ));
+        }
+        let total: i64 = results.iter().map(|r| i64::from(r.final_chips)).sum();
+        #[allow(clippy::cast_precision_loss)]
+        let average = total as f64 / results.len() as f64;
+        formatter.add_empty_line();
+        formatter.add_field("Days played", &results.len());
+        formatter.add_field(
+            "Best score",
+            &format!("{} chips on {}", best.final_chips, best.date),
+        );
+        formatter.add_field("Average score", &format!("{average:.0} chips"));
+        println!("\n{}", formatter.build());
+    }
+}
+
+pub fn show_daily_challenge_menu(profile: &Profile) {
+    let menu = DailyChallengeMenu::new(profile.daily_history_path());
+    menu.show_menu();
+}
@@ -1,5 +1,7 @@
+use crate::table_rules::GameVariant;
 use rand::seq::SliceRandom;
-use rand::thread_rng;
+use rand::{thread_rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
 
 #[must_use]
 pub fn create_deck() -> Vec<String> {
@@ -13,6 +15,17 @@ pub fn create_deck() -> Vec<String> {
         .collect()
 }
 
+/// Create the deck used by a game variant. Spanish 21 removes the four tens
+/// (face cards stay) for a 48-card deck.
+#[must_use]
+pub fn create_deck_for(variant: GameVariant) -> Vec<String> {
+    let mut deck = create_deck();
+    if variant == GameVariant::Spanish21 {
+        deck.retain(|card| !card.starts_with("10 "));
+    }
+    deck
+}
+
 pub fn shuffle_deck(deck: &mut [String]) {
     deck.shuffle(&mut thread_rng());
 }
@@ -23,3 +36,86 @@ pub fn create_and_shuffle_deck() -> Vec<String> {
     shuffle_deck(&mut deck);
     deck
 }
+
+/// Shuffle with a seeded generator, so the same seed always gives the same
+/// order. `ChaCha8` is used because its output is fixed across platforms and
+/// rand versions, unlike `StdRng`.
+pub fn shuffle_deck_seeded(deck: &mut [String], seed: u64) {
+    deck.shuffle(&mut ChaCha8Rng::seed_from_u64(seed));
+}
+
+#[must_use]
+pub fn create_seeded_deck(seed: u64) -> Vec<String> {
+    let mut deck = create_deck();
+    shuffle_deck_seeded(&mut deck, seed);
+    deck
+}
+
+/// A deck shuffled from a full 256-bit key. No `u64` seed given to
+/// `create_seeded_deck` deals the same order, so keyed decks cannot be
+/// previewed in modes that take a typed seed.
+#[must_use]
+pub fn create_keyed_deck(key: [u8; 32]) -> Vec<String> {
+    let mut deck = create_deck();
+    deck.shuffle(&mut ChaCha8Rng::from_seed(key));
+    deck
+}
+
+/// Seed for one hand of a seeded shoe, giving every hand its own card order
+#[must_use]
+pub fn hand_seed(shoe_seed: u64, hand_number: u32) -> u64 {
+    shoe_seed ^ u64::from(hand_number).wrapping_mul(0x9E37_79B9_7F4A_7C15)
+}
+
+/// A deck that deals `top` first, in order, then the rest of the variant's
+/// deck shuffled from `seed`. The same arguments always give the same deck.
+#[must_use]
+pub fn create_stacked_deck_for(variant: GameVariant, top: &[String], seed: u64) -> Vec<String> {
+    let mut rest = create_deck_for(variant);
+    for card in top {
+        if let Some(i) = rest.iter().position(|c| c == card) {
+            rest.swap_remove(i);
+        }
+    }
+    shuffle_deck_seeded(&mut rest, seed);
+    top.iter().cloned().chain(rest).collect()
+}
+
+#[must_use]
+pub fn create_and_shuffle_deck_for(variant: GameVariant) -> Vec<String> {
+    let mut deck = create_deck_for(variant);
+    shuffle_deck(&mut deck);
+    deck
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn seeded_deck_order_is_fixed() {
+        // Every build must deal this order for seed 42, or players of a
+        // shared seed see different cards
+        assert_eq!(
+            create_seeded_deck(42)[..6],
+            [
+                "5 Diamonds",
+                "6 Clubs",
+                "10 Diamonds",
+                "2 Hearts",
+                "A Spades",
+                "A Clubs"
+            ]
+        );
+    }
+
+    #[test]
+    fn stacked_deck_deals_top_cards_first_without_duplicates() {
+        let top = vec!["A Hearts".to_string(), "7 Clubs".to_string()];
+        let deck = create_stacked_deck_for(GameVariant::Classic, &top, 7);
+        assert_eq!(deck, create_stacked_deck_for(GameVariant::Classic, &top, 7));
+        assert_eq!(deck[..2], top);
+        assert_eq!(deck.len(), 52);
+        assert!(!deck[2..].contains(&top[0]));
+    }
+}
@@ -0,0 +1,329 @@
+use crate::basic_strategy::{self, StrategyAction};
+use crate::deck_manager::{create_seeded_deck, hand_seed};
+use crate::enemy_ai_handler::{dealer_must_hit, dealer_turn};
+use crate::formatting::{format_money, BoxFormatter};
+use crate::game_history::GameOutcome;
+use crate::game_loop::take_played_hands;
+use crate::game_state::GameState;
+use crate::player_handler::{draw, hand_value, player_turn, print_initial_game_state};
+use crate::text_handler;
+use crate::win_checker::{compare_totals, settle_outcome};
+use rand::Rng;
+use std::io::{self, Write};
+
+/// Chips behind every seat, so doubling and splitting are never limited
+const DUPLICATE_BANKROLL: i32 = 1_000_000;
+
+/// Settings for a duplicate match
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DuplicateConfig {
+    pub hands: u32,
+    pub bet: i32,
+    /// Seed of the shoe every competitor plays
+    pub seed: u64,
+}
+
+impl DuplicateConfig {
+    #[must_use]
+    pub fn new(seed: u64) -> Self {
+        DuplicateConfig {
+            hands: 10,
+            bet: 10,
+            seed,
+        }
+    }
+}
+
+/// How one competitor played one hand of the shoe
+#[derive(Debug, Clone, PartialEq)]
+pub struct DuplicateHand {
+    pub actions: Vec<StrategyAction>,
+    /// Final cards of each hand played, more than one after a split
+    pub hands: Vec<Vec<String>>,
+    pub net: i32,
+}
+
+impl DuplicateHand {
+    fn action_codes(&self) -> String {
+        self.actions.iter().map(|action| action.code()).collect()
+    }
+}
+
+/// A player in a duplicate match, human or basic strategy bot
+#[derive(Debug, Clone, PartialEq)]
+pub struct Competitor {
+    pub name: String,
+    pub is_bot: bool,
+    pub results: Vec<DuplicateHand>,
+}
+
+impl Competitor {
+    #[must_use]
+    pub fn new(name: &str, is_bot: bool) -> Self {
+        Competitor {
+            name: name.to_string(),
+            is_bot,
+            results: Vec::new(),
+        }
+    }
+
+    #[must_use]
+    pub fn total_net(&self) -> i32 {
+        self.results.iter().map(|hand| hand.net).sum()
+    }
+}
+
+/// Every competitor plays the whole seeded shoe in turn, starting each hand
+/// from the same card order.
+pub fn run_duplicate(config: &DuplicateConfig, competitors: &mut [Competitor]) {
+    let humans = competitors.iter().filter(|c| !c.is_bot).count();
+    let mut humans_played = 0;
+    for competitor in competitors.iter_mut() {
+        if !competitor.is_bot && humans > 1 {
+            if humans_played > 0 {
+                wait_for_enter("\nPress Enter to hide your hands...");
+            }
+            // The next player is dealt the same cards, so nothing may be left
+            // on screen or in the scrollback
+            text_handler::clear_screen();
+            wait_for_enter(&format!(
+                "Pass the keyboard to {} and press Enter when ready...",
+                competitor.name
+            ));
+            humans_played += 1;
+        }
+
+        let mut table = GameState::with_money(DUPLICATE_BANKROLL);
+        competitor.results = (1..=config.hands)
+            .map(|hand_number| {
+                deal_hand(&mut table, hand_seed(config.seed, hand_number));
+                if competitor.is_bot {
+                    play_bot_hand(&mut table, config.bet)
+                } else {
+                    println!(
+                        "\n=== {}: hand {hand_number} of {} ===",
+                        competitor.name, config.hands
+                    );
+                    play_human_hand(&mut table, config.bet)
+                }
+            })
+            .collect();
+    }
+}
+
+fn wait_for_enter(message: &str) {
+    println!("{message}");
+    let mut input = String::new();
+    io::stdin().read_line(&mut input).ok();
+}
+
+fn deal_hand(table: &mut GameState, seed: u64) {
+    deal_deck(table, create_seeded_deck(seed));
+}
+
+/// Deal the first four cards of `deck`, which the rest of the hand draws from
+#[allow(clippy::cast_possible_truncation, clippy::cast_possible_wrap)]
+pub fn deal_deck(table: &mut GameState, deck: Vec<String>) {
+    table.card_deck = deck;
+    table.player_cards = vec![table.card_deck[0].clone(), table.card_deck[1].clone()];
+    table.dealer_cards = vec![table.card_deck[2].clone(), table.card_deck[3].clone()];
+    table.deck_index = 4;
+    table.player_card_count = table.player_cards.len() as i32;
+    table.dealer_card_count = table.dealer_cards.len() as i32;
+}
+
+/// Play the hand dealt on `table` for `bet`, asking the player for each
+/// decision
+#[allow(clippy::cast_possible_truncation, clippy::cast_possible_wrap)]
+pub fn play_human_hand(table: &mut GameState, bet: i32) -> DuplicateHand {
+    let start_money = table.money;
+    table.money -= bet;
+    table.bet = bet;
+    table.free_bet = 0;
+    table.free_double_bet = 0;
+    table.was_double_down = false;
+    table.surrendered = false;
+    table.was_switched = false;
+    table.split_hands.clear();
+    table.actions.clear();
+
+    print_initial_game_state(table);
+    let alive = player_turn(table);
+    let played = take_played_hands(table);
+    if alive {
+        dealer_turn(table);
+    }
+
+    let mut hands = Vec::new();
+    for hand in played {
+        table.player_cards = hand.cards;
+        table.player_card_count = table.player_cards.len() as i32;
+        table.bet = hand.bet;
+        table.was_double_down = hand.was_double_down;
+        table.was_split = hand.was_split;
+        table.was_switched = hand.was_switched;
+        settle_outcome(table);
+        hands.push(table.player_cards.clone());
+    }
+
+    DuplicateHand {
+        actions: std::mem::take(&mut table.actions),
+        hands,
+        net: table.money - start_money,
+    }
+}
+
+fn play_bot_hand(table: &mut GameState, bet: i32) -> DuplicateHand {
+    let mut cards = table.player_cards.clone();
+    let actions = basic_strategy::play_hand(table, &mut cards, true);
+    let bet = if actions.contains(&StrategyAction::Double) {
+        bet * 2
+    } else {
+        bet
+    };
+
+    let p_total = hand_value(&cards);
+    if p_total <= 21 {
+        while dealer_must_hit(&table.dealer_cards) {
+            let card = draw(table);
+            table.dealer_cards.push(card);
+        }
+    }
+    let net = match compare_totals(p_total, hand_value(&table.dealer_cards)) {
+        GameOutcome::PlayerWin | GameOutcome::DealerBust => bet,
+        GameOutcome::Tie => 0,
+        _ => -bet,
+    };
+
+    DuplicateHand {
+        actions,
+        hands: vec![cards],
+        net,
+    }
+}
+
+/// Print the totals, then every hand on which the competitors' decisions
+/// differed.
+#[allow(clippy::cast_possible_truncation)]
+pub fn print_comparison_report(config: &DuplicateConfig, competitors: &[Competitor]) {
+    let mut ranking: Vec<&Competitor> = competitors.iter().collect();
+    ranking.sort_by_key(|competitor| std::cmp::Reverse(competitor.total_net()));
+
+    let mut summary = BoxFormatter::new(60, "DUPLICATE RESULTS");
+    summary.add_field("Shoe seed", &config.seed);
+    summary.add_field("Hands", &config.hands);
+    summary.add_empty_line();
+    for (i, competitor) in ranking.iter().enumerate() {
+        summary.add_field_aligned(
+            &format!("{}. {}", i + 1, competitor.name),
+            &format_money(competitor.total_net()),
+        );
+    }
+    println!("\n{}", summary.build());
+
+    let mut report = BoxFormatter::new(70, "HANDS WHERE DECISIONS DIFFERED");
+    let mut identical = 0;
+    let mut differing = 0;
+    for hand_idx in 0..config.hands as usize {
+        let first = &competitors[0].results[hand_idx];
+        if competitors
+            .iter()
+            .all(|c| c.results[hand_idx].actions == first.actions)
+        {
+            identical += 1;
+            continue;
+        }
+
+        let deck = create_seeded_deck(hand_seed(config.seed, hand_idx as u32 + 1));
+        if differing > 0 {
+            report.add_separator();
+        }
+        differing += 1;
+        report.add_line(&format!(
+            "Hand {}: {}, {} against dealer {}",
+            hand_idx + 1,
+            deck[0],
+            deck[1],
+            deck[2]
+        ));
+        for competitor in competitors {
+            let hand = &competitor.results[hand_idx];
+            let totals: Vec<String> = hand
+                .hands
+                .iter()
+                .map(|cards| hand_value(cards).to_string())
+                .collect();
+            report.add_line(&format!(
+                "  {:<16} {:<10} total {:<8} {:>6}",
+                competitor.name,
+                hand.action_codes(),
+                totals.join("/"),
+                format_money(hand.net)
+            ));
+        }
+    }
+    if differing == 0 {
+        report.add_line("Every hand was played the same way.");
+    } else {
+        report.add_separator();
+        report.add_line("H hit, S stand, D double, P split, R surrender");
+    }
+    report.add_field(
+        "Hands played identically",
+        &format!("{identical} of {}", config.hands),
+    );
+    println!("\n{}", report.build());
+}
+
+/// Set up a duplicate match from the menu and play it
+pub fn show_duplicate_mode() {
+    let mut competitors = Vec::new();
+    let humans = prompt_number("How many human players (1-4)? ", 1, 4);
+    for i in 1..=humans {
+        let name = prompt_line(&format!("Name of player {i}: "));
+        let name = if name.is_empty() {
+            format!("Player {i}")
+        } else {
+            name
+        };
+        competitors.push(Competitor::new(&name, false));
+    }
+    if prompt_line("Add a basic strategy bot? (y/n): ").eq_ignore_ascii_case("y") {
+        competitors.push(Competitor::new("Basic Strategy", true));
+    }
+
+    let seed_input = prompt_line("Shoe seed (blank for a random shoe): ");
+    let seed = seed_input
+        .parse::<u64>()
+        .unwrap_or_else(|_| rand::thread_rng().gen());
+    let config = DuplicateConfig::new(seed);
+    println!(
+        "Playing {} hands of {} chips from shoe {seed}.",
+        config.hands, config.bet
+    );
+
+    run_duplicate(&config, &mut competitors);
+    print_comparison_report(&config, &competitors);
+}
+
+fn prompt_line(prompt: &str) -> String {
+    print!("{prompt}");
+    io::stdout().flush().ok();
+    let mut line = String::new();
+    io::stdin().read_line(&mut line).ok();
+    line.trim().to_string()
+}
+
+/// Ask for a number in `min..=max`; an empty answer picks `min`
+fn prompt_number(prompt: &str, min: usize, max: usize) -> usize {
+    loop {
+        let answer = prompt_line(prompt);
+        if answer.is_empty() {
+            return min;
+        }
+        match answer.parse::<usize>() {
+            Ok(n) if (min..=max).contains(&n) => return n,
+            _ => println!("Please enter a number between {min} and {max}."),
+        }
+    }
+}
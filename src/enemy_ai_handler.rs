@@ -1,4 +1,4 @@
-use crate::art_handler::get_card_art;
+use crate::art_handler::{get_card_art, get_message};
 use crate::game_state::GameState;
 use crate::player_handler::{card_art_index, draw, hand_value};
 
@@ -28,20 +28,33 @@ fn is_soft_17(hand: &[String]) -> bool {
     has_ace && total == 17
 }
 
+/// House rules: the dealer hits below 17 and on soft 17
+#[must_use]
+pub fn dealer_must_hit(hand: &[String]) -> bool {
+    hand_value(hand) < 17 || is_soft_17(hand)
+}
+
 #[allow(clippy::cast_possible_truncation, clippy::cast_possible_wrap)]
 pub fn dealer_turn(state: &mut GameState) {
-    println!("Dealer's turn:");
+    println!("{}", get_message("Dealer's turn:", Some(state)));
 
-    // First, reveal the hole card (second card)
-    println!("Dealer reveals hole card: {}", state.dealer_cards[1]);
+    // First, reveal whichever cards were dealt face down
+    match state.rules.variant.dealer_cards_face_up() {
+        0 => println!("{}", get_message("Dealer reveals", Some(state))),
+        1 => println!("Dealer reveals hole card: {}", state.dealer_cards[1]),
+        _ => {}
+    }
     print_dealer_cards(&state.dealer_cards);
 
     // Now dealer follows house rules (hit on soft 17, stand on hard 17)
-    while hand_value(&state.dealer_cards) < 17 || is_soft_17(&state.dealer_cards) {
+    while dealer_must_hit(&state.dealer_cards) {
         let card = draw(state);
         state.dealer_cards.push(card.clone());
         state.dealer_card_count = state.dealer_cards.len() as i32;
-        println!("Dealer draws: {card}");
+        println!(
+            "{}",
+            get_message("Dealer draws", Some(state)).replace("{{card}}", &card)
+        );
         print_dealer_cards(&state.dealer_cards);
     }
 }
@@ -0,0 +1,388 @@
+use crate::basic_strategy::{upcard_value, StrategyAction};
+use crate::game_history_core::GameRound;
+use crate::player_handler::{hand_value, is_soft_hand};
+use crate::table_rules::GameVariant;
+
+/// Card values with the chance of drawing each from an infinite shoe; an
+/// ace is 11 and tens include the face cards
+const DRAWS: [(i32, f64); 10] = [
+    (2, 1.0 / 13.0),
+    (3, 1.0 / 13.0),
+    (4, 1.0 / 13.0),
+    (5, 1.0 / 13.0),
+    (6, 1.0 / 13.0),
+    (7, 1.0 / 13.0),
+    (8, 1.0 / 13.0),
+    (9, 1.0 / 13.0),
+    (10, 4.0 / 13.0),
+    (11, 1.0 / 13.0),
+];
+
+/// Index of a dealer bust in `HandEvaluator::dealer`
+const DEALER_BUST: usize = 5;
+
+/// A hand's total and whether an ace in it counts as 11
+type Total = (i32, bool);
+
+fn add_card((total, soft): Total, value: i32) -> Total {
+    let value = if value == 11 && total + 11 > 21 {
+        1
+    } else {
+        value
+    };
+    let (total, soft) = (total + value, soft || value == 11);
+    if total > 21 && soft {
+        (total - 10, false)
+    } else {
+        (total, soft)
+    }
+}
+
+/// Position of a hand of 21 or less in `HandEvaluator::hit`
+fn slot((total, soft): Total) -> (usize, usize) {
+    (usize::try_from(total).unwrap_or(0), usize::from(soft))
+}
+
+/// Expected results, per unit bet, of each decision against one dealer
+/// upcard. Cards come from an infinite shoe and the dealer hits soft 17,
+/// the rules `basic_strategy` is written for.
+pub struct HandEvaluator {
+    /// Chances of the dealer finishing on 17 to 21, then of busting
+    dealer: [f64; 6],
+    /// Expected result of hitting each total, hard then soft
+    hit: [[f64; 2]; 22],
+}
+
+impl HandEvaluator {
+    /// Evaluator for a dealer showing `upcard`, 2 through 11 (ace)
+    #[must_use]
+    pub fn new(upcard: i32) -> Self {
+        let mut dealer = [0.0; 6];
+        dealer_outcomes((upcard, upcard == 11), 1.0, &mut dealer);
+
+        let mut evaluator = HandEvaluator {
+            dealer,
+            hit: [[0.0; 2]; 22],
+        };
+        let mut memo = [[None; 2]; 22];
+        for total in 2..=21 {
+            for soft in [false, true] {
+                let ev = evaluator.hit_ev((total, soft), &mut memo);
+                let (total, soft) = slot((total, soft));
+                evaluator.hit[total][soft] = ev;
+            }
+        }
+        evaluator
+    }
+
+    /// Expected result of standing on `total`
+    #[must_use]
+    pub fn stand(&self, total: i32) -> f64 {
+        if total > 21 {
+            return -1.0;
+        }
+        let mut ev = self.dealer[DEALER_BUST];
+        for (dealer_total, chance) in (17..=21).zip(self.dealer) {
+            if total > dealer_total {
+                ev += chance;
+            } else if total < dealer_total {
+                ev -= chance;
+            }
+        }
+        ev
+    }
+
+    /// Expected result of hitting and then playing on as well as possible
+    #[must_use]
+    pub fn hit(&self, hand: Total) -> f64 {
+        if hand.0 > 21 {
+            -1.0
+        } else {
+            let (total, soft) = slot(hand);
+            self.hit[total][soft]
+        }
+    }
+
+    /// Expected result of doubling the bet and taking one card
+    #[must_use]
+    pub fn double(&self, hand: Total) -> f64 {
+        2.0 * DRAWS
+            .iter()
+            .map(|&(value, chance)| chance * self.stand(add_card(hand, value).0))
+            .sum::<f64>()
+    }
+
+    /// Expected result of `action`, or `None` for a split, which is not
+    /// evaluated
+    #[must_use]
+    pub fn action(&self, action: StrategyAction, hand: Total) -> Option<f64> {
+        match action {
+            StrategyAction::Hit => Some(self.hit(hand)),
+            StrategyAction::Stand => Some(self.stand(hand.0)),
+            StrategyAction::Double => Some(self.double(hand)),
+            StrategyAction::Surrender => Some(-0.5),
+            StrategyAction::Split => None,
+        }
+    }
+
+    /// Expected result of the best of standing, hitting and, on the first
+    /// decision of a hand, doubling
+    #[must_use]
+    pub fn best(&self, hand: Total, first_decision: bool) -> f64 {
+        let best = self.stand(hand.0).max(self.hit(hand));
+        if first_decision {
+            best.max(self.double(hand))
+        } else {
+            best
+        }
+    }
+
+    fn hit_ev(&self, hand: Total, memo: &mut [[Option<f64>; 2]; 22]) -> f64 {
+        let (total, soft) = slot(hand);
+        if let Some(ev) = memo[total][soft] {
+            return ev;
+        }
+        let ev = DRAWS
+            .iter()
+            .map(|&(value, chance)| {
+                let next = add_card(hand, value);
+                if next.0 > 21 {
+                    -chance
+                } else {
+                    chance * self.stand(next.0).max(self.hit_ev(next, memo))
+                }
+            })
+            .sum();
+        memo[total][soft] = Some(ev);
+        ev
+    }
+}
+
+/// Add the chances of each way the dealer can finish from `hand`, reached
+/// with probability `chance`, into `outcomes`
+fn dealer_outcomes(hand: Total, chance: f64, outcomes: &mut [f64; 6]) {
+    let (total, soft) = hand;
+    if total > 21 {
+        outcomes[DEALER_BUST] += chance;
+    } else if total > 17 || (total == 17 && !soft) {
+        outcomes[slot(hand).0 - 17] += chance;
+    } else {
+        for &(value, draw_chance) in &DRAWS {
+            dealer_outcomes(add_card(hand, value), chance * draw_chance, outcomes);
+        }
+    }
+}
+
+/// What one hand was expected to win or lose, in coins
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct HandLuck {
+    /// Result the hand was expected to have, given the decisions made
+    pub expected: f64,
+    /// Expected coins given up by decisions worse than the best one
+    pub play_cost: f64,
+    pub actual: i32,
+}
+
+impl HandLuck {
+    /// How much better the hand went than expected
+    #[must_use]
+    pub fn luck(&self) -> f64 {
+        f64::from(self.actual) - self.expected
+    }
+}
+
+/// Work out what `round` was expected to return. Decisions are replayed
+/// from its action log where possible; split hands, and hands whose log
+/// does not match their cards, are judged on the dealer's cards alone.
+/// Returns `None` for hands from other variants than Classic, whose
+/// payouts the evaluator does not model, and for rounds with too few cards
+/// to judge.
+#[must_use]
+pub fn analyse_round(round: &GameRound) -> Option<HandLuck> {
+    if round.variant != GameVariant::Classic {
+        return None;
+    }
+    let start = round.player_cards.get(..2)?;
+    let evaluator = HandEvaluator::new(upcard_value(round.dealer_cards.first()?));
+    let base_bet = f64::from(round.base_bet());
+    let actual = round.money_change;
+
+    // A natural is paid even money like any other win, and pushes when the
+    // dealer also makes 21
+    if hand_value(start) == 21 && !round.was_split {
+        return Some(HandLuck {
+            expected: base_bet * evaluator.stand(21),
+            play_cost: 0.0,
+            actual,
+        });
+    }
+
+    let replayed = if round.was_split {
+        None
+    } else {
+        replay_decisions(round, &evaluator)
+    };
+    let expected = replayed.map_or_else(
+        || {
+            let final_ev = if round.actions.ends_with(StrategyAction::Surrender.code()) {
+                -0.5
+            } else {
+                evaluator.stand(round.player_total)
+            };
+            f64::from(round.bet_amount) * final_ev
+        },
+        |(best_start, cost)| base_bet * (best_start - cost),
+    );
+    let play_cost = replayed.map_or(0.0, |(_, cost)| base_bet * cost);
+
+    Some(HandLuck {
+        expected,
+        play_cost,
+        actual,
+    })
+}
+
+/// Replay the action log of `round` against its cards. Returns the expected
+/// result of the best play from the first two cards, counting the first
+/// decision made as available, and the expected result lost to each worse
+/// decision, per unit bet, or `None` if the log does not fit the cards.
+fn replay_decisions(round: &GameRound, evaluator: &HandEvaluator) -> Option<(f64, f64)> {
+    let cards = &round.player_cards;
+    let start = &cards[..2];
+    let mut hand = (hand_value(start), is_soft_hand(start));
+    let mut best_start = 0.0;
+    let mut cost = 0.0;
+    let mut next_card = 2;
+    let mut finished = false;
+
+    for (i, code) in round.actions.chars().enumerate() {
+        if finished || hand.0 > 21 {
+            return None;
+        }
+        let action = StrategyAction::from_code(code)?;
+        let chosen = evaluator.action(action, hand)?;
+        let best = evaluator.best(hand, i == 0).max(chosen);
+        if i == 0 {
+            best_start = best;
+        }
+        cost += best - chosen;
+
+        match action {
+            StrategyAction::Hit | StrategyAction::Double => {
+                hand = add_card(hand, upcard_value(cards.get(next_card)?));
+                next_card += 1;
+                finished = action == StrategyAction::Double;
+            }
+            _ => finished = true,
+        }
+    }
+
+    let played_out = finished || hand.0 > 21;
+    (played_out && next_card == cards.len()).then_some((best_start, cost))
+}
+
+/// Totals over many hands of what was expected and what happened
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct LuckReport {
+    pub hands: u32,
+    pub wagered: i32,
+    pub actual: i32,
+    pub expected: f64,
+    pub play_cost: f64,
+}
+
+impl LuckReport {
+    #[must_use]
+    pub fn from_rounds(rounds: &[GameRound]) -> Self {
+        let mut report = LuckReport::default();
+        for round in rounds {
+            if let Some(hand) = analyse_round(round) {
+                report.hands += 1;
+                report.wagered += round.bet_amount;
+                report.actual += hand.actual;
+                report.expected += hand.expected;
+                report.play_cost += hand.play_cost;
+            }
+        }
+        report
+    }
+
+    /// How much better the cards were than expected, in coins
+    #[must_use]
+    pub fn luck(&self) -> f64 {
+        f64::from(self.actual) - self.expected
+    }
+
+    /// `amount` as a percentage of the coins wagered
+    #[must_use]
+    pub fn share_of_wagered(&self, amount: f64) -> f64 {
+        if self.wagered == 0 {
+            0.0
+        } else {
+            amount / f64::from(self.wagered) * 100.0
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game_history::GameOutcome;
+    use chrono::Local;
+
+    fn round(player: &[&str], dealer: &[&str], outcome: GameOutcome, change: i32) -> GameRound {
+        let player_cards: Vec<String> = player.iter().map(ToString::to_string).collect();
+        let dealer_cards: Vec<String> = dealer.iter().map(ToString::to_string).collect();
+        GameRound {
+            round_number: 1,
+            session: 0,
+            timestamp: Local::now(),
+            variant: GameVariant::Classic,
+            bet_amount: 10,
+            free_bet_amount: 0,
+            free_double_amount: 0,
+            player_total: hand_value(&player_cards),
+            dealer_total: hand_value(&dealer_cards),
+            player_cards,
+            dealer_cards,
+            outcome,
+            money_change: change,
+            money_after: 100 + change,
+            was_double_down: false,
+            was_split: false,
+            was_switched: false,
+            actions: "S".to_string(),
+            player_busted: false,
+            dealer_busted: false,
+        }
+    }
+
+    #[test]
+    fn natural_is_expected_to_pay_even_money_less_pushes() {
+        let natural = round(
+            &["A Spades", "K Hearts"],
+            &["A Hearts", "K Clubs"],
+            GameOutcome::Tie,
+            0,
+        );
+        let hand = analyse_round(&natural).unwrap();
+        let expected = 10.0 * HandEvaluator::new(11).stand(21);
+        assert!((hand.expected - expected).abs() < 1e-9);
+        assert!(hand.expected < 10.0);
+        assert!((hand.luck() + expected).abs() < 1e-9);
+    }
+
+    #[test]
+    fn only_classic_rounds_are_judged() {
+        let mut spanish = round(
+            &["10 Spades", "8 Hearts"],
+            &["9 Hearts", "K Clubs"],
+            GameOutcome::DealerWin,
+            -10,
+        );
+        assert!(analyse_round(&spanish).is_some());
+        spanish.variant = GameVariant::Spanish21;
+        assert!(analyse_round(&spanish).is_none());
+        assert_eq!(LuckReport::from_rounds(&[spanish]).hands, 0);
+    }
+}
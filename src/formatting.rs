@@ -207,8 +207,13 @@ pub fn format_money(amount: i32) -> String {
 /// Format time duration
 #[must_use]
 pub fn format_duration(start: &DateTime<Local>) -> String {
-    let now = Local::now();
-    let duration = now.signed_duration_since(*start);
+    format_duration_between(start, &Local::now())
+}
+
+/// Format the time from `start` to `end`
+#[must_use]
+pub fn format_duration_between(start: &DateTime<Local>, end: &DateTime<Local>) -> String {
+    let duration = end.signed_duration_since(*start);
 
     if let Ok(std_duration) = duration.to_std() {
         let total_seconds = std_duration.as_secs();
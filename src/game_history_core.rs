@@ -1,11 +1,28 @@
+use crate::table_rules::GameVariant;
 use chrono::{DateTime, Local};
+use serde::{Deserialize, Serialize};
 use std::fmt;
+use std::str::FromStr;
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct GameRound {
     pub round_number: u32,
+    /// Session the hand was played in; 0 for hands from before sessions
+    /// were tracked
+    #[serde(default)]
+    pub session: u32,
     pub timestamp: DateTime<Local>,
+    /// Variant of the table the hand was played at; Classic for hands from
+    /// before variants were recorded
+    #[serde(default)]
+    pub variant: GameVariant,
     pub bet_amount: i32,
+    /// Portion of `bet_amount` that was a free bet rather than real money
+    pub free_bet_amount: i32,
+    /// Portion of `free_bet_amount` from a free double down; the rest is
+    /// the free stake of a split hand
+    #[serde(default)]
+    pub free_double_amount: i32,
     pub player_cards: Vec<String>,
     pub dealer_cards: Vec<String>,
     pub player_total: i32,
@@ -14,17 +31,48 @@ pub struct GameRound {
     pub money_change: i32,
     pub money_after: i32,
     pub was_double_down: bool,
+    /// Whether this hand was one half of a split
+    #[serde(default)]
+    pub was_split: bool,
+    /// Whether the hand's second card came from the other hand of a
+    /// Blackjack Switch round
+    #[serde(default)]
+    pub was_switched: bool,
+    /// Decisions made in the round, one letter each as given by
+    /// `StrategyAction::code`
+    #[serde(default)]
+    pub actions: String,
     pub player_busted: bool,
     pub dealer_busted: bool,
 }
 
-#[derive(Debug, Clone, Copy, PartialEq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum GameOutcome {
     PlayerWin,
     DealerWin,
     Tie,
     PlayerBust,
     DealerBust,
+    Surrender,
+}
+
+impl GameRound {
+    /// The bet before any double down
+    #[must_use]
+    pub fn base_bet(&self) -> i32 {
+        if self.was_double_down {
+            self.bet_amount / 2
+        } else {
+            self.bet_amount
+        }
+    }
+
+    /// The free stake of a split hand, which Free Bet tables give the second
+    /// hand of a free split
+    #[must_use]
+    pub fn free_split_amount(&self) -> i32 {
+        self.free_bet_amount - self.free_double_amount
+    }
 }
 
 impl fmt::Display for GameOutcome {
@@ -35,11 +83,30 @@ impl fmt::Display for GameOutcome {
             GameOutcome::Tie => write!(f, "Tie"),
             GameOutcome::PlayerBust => write!(f, "Player Bust"),
             GameOutcome::DealerBust => write!(f, "Dealer Bust"),
+            GameOutcome::Surrender => write!(f, "Surrender"),
+        }
+    }
+}
+
+impl FromStr for GameOutcome {
+    type Err = String;
+
+    /// Parse an outcome as written by its `Display` implementation
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim() {
+            "Player Win" => Ok(GameOutcome::PlayerWin),
+            "Dealer Win" => Ok(GameOutcome::DealerWin),
+            "Tie" => Ok(GameOutcome::Tie),
+            "Player Bust" => Ok(GameOutcome::PlayerBust),
+            "Dealer Bust" => Ok(GameOutcome::DealerBust),
+            "Surrender" => Ok(GameOutcome::Surrender),
+            other => Err(format!("unknown outcome \"{other}\"")),
         }
     }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
 pub struct GameHistory {
     pub rounds: Vec<GameRound>,
     pub session_start: DateTime<Local>,
@@ -81,7 +148,7 @@ impl GameHistory {
                     self.biggest_win = round.money_change;
                 }
             }
-            GameOutcome::DealerWin | GameOutcome::PlayerBust => {
+            GameOutcome::DealerWin | GameOutcome::PlayerBust | GameOutcome::Surrender => {
                 self.total_losses += 1;
                 self.total_money_lost += round.money_change.abs();
                 if round.money_change.abs() > self.biggest_loss {
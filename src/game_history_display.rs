@@ -1,5 +1,17 @@
+use crate::expected_value::LuckReport;
 use crate::formatting::{format_money, format_percentage, BoxFormatter};
 use crate::game_history_core::GameHistory;
+use crate::game_history_report::{
+    starting_total_label, tally_by_action, tally_by_starting_total, tally_by_upcard, upcard_label,
+    HandStats, OutcomeTally,
+};
+use crate::streaks::{Streak, StreakKind, StreakStats};
+
+/// Luck, as a share of the coins wagered, at either end of the luck meter
+const LUCK_METER_RANGE: f64 = 10.0;
+
+/// Cells on each side of the middle of the luck meter
+const LUCK_METER_HALF_WIDTH: usize = 14;
 
 impl GameHistory {
     pub fn display_summary(&self) {
@@ -21,6 +33,147 @@ impl GameHistory {
         println!("\n{}\n", formatter.build());
     }
 
+    /// Tables of results by dealer upcard, starting total and action, with
+    /// bust rates and hand sizes
+    pub fn display_advanced_stats(&self) {
+        println!(
+            "\n{}",
+            tally_table(
+                "RESULTS BY DEALER UPCARD",
+                "Upcard",
+                tally_by_upcard(&self.rounds)
+                    .into_iter()
+                    .map(|(upcard, tally)| (upcard_label(upcard), tally)),
+            )
+        );
+        println!(
+            "{}",
+            tally_table(
+                "RESULTS BY STARTING TOTAL",
+                "Start",
+                tally_by_starting_total(&self.rounds)
+                    .into_iter()
+                    .map(|((soft, total), tally)| (starting_total_label(total, soft), tally)),
+            )
+        );
+        println!(
+            "{}",
+            tally_table(
+                "RESULTS BY ACTION",
+                "Action",
+                tally_by_action(&self.rounds)
+                    .into_iter()
+                    .map(|(action, tally)| (action.to_string(), tally)),
+            )
+        );
+
+        let stats = HandStats::from_rounds(&self.rounds);
+        let mut formatter = BoxFormatter::new(40, "BUSTS AND HAND SIZE");
+        formatter.add_field_aligned(
+            "Player Bust Rate",
+            &format_percentage(stats.player_bust_rate()),
+        );
+        formatter.add_field_aligned(
+            "Dealer Bust Rate",
+            &format_percentage(stats.dealer_bust_rate()),
+        );
+        formatter.add_field_aligned(
+            "Avg Player Cards",
+            &format!("{:.2}", stats.average_player_cards()),
+        );
+        formatter.add_field_aligned(
+            "Avg Dealer Cards",
+            &format!("{:.2}", stats.average_dealer_cards()),
+        );
+        println!("{}\n", formatter.build());
+    }
+
+    /// Longest and current win and loss streaks, and how often streaks of
+    /// each length came up. Ties neither extend nor break a streak.
+    pub fn display_streaks(&self) {
+        let stats = StreakStats::from_rounds(&self.rounds);
+        let mut formatter = BoxFormatter::new(48, "STREAKS");
+        formatter.add_field_aligned(
+            "Longest Win Streak",
+            &streak_label(stats.longest(StreakKind::Win)),
+        );
+        formatter.add_field_aligned(
+            "Longest Loss Streak",
+            &streak_label(stats.longest(StreakKind::Loss)),
+        );
+        formatter.add_field_aligned(
+            "Current Streak",
+            &stats.current().map_or("-".to_string(), |streak| {
+                format!(
+                    "{} {}",
+                    streak.length,
+                    kind_label(streak.kind, streak.length)
+                )
+            }),
+        );
+
+        let wins = stats.distribution(StreakKind::Win);
+        let losses = stats.distribution(StreakKind::Loss);
+        let longest = wins.keys().chain(losses.keys()).copied().max();
+        if let Some(longest) = longest {
+            formatter.add_separator();
+            formatter.add_line(&format!(
+                "{:<8} {:>12} {:>13}",
+                "Length", "Win Streaks", "Loss Streaks"
+            ));
+            for length in 1..=longest {
+                let win_count = wins.get(&length).copied().unwrap_or(0);
+                let loss_count = losses.get(&length).copied().unwrap_or(0);
+                if win_count + loss_count > 0 {
+                    formatter.add_line(&format!("{length:<8} {win_count:>12} {loss_count:>13}"));
+                }
+            }
+        }
+        println!("\n{}", formatter.build());
+    }
+
+    /// Compare what was won with what the decisions made were expected to
+    /// win, to tell bad cards apart from bad play
+    pub fn display_luck(&self) {
+        let report = LuckReport::from_rounds(&self.rounds);
+        let luck = report.luck();
+        let luck_share = report.share_of_wagered(luck);
+        let cost_share = report.share_of_wagered(report.play_cost);
+
+        let mut formatter = BoxFormatter::new(48, "LUCK METER");
+        formatter.add_field_aligned("Hands Judged", &report.hands);
+        formatter.add_field_aligned("Coins Wagered", &report.wagered);
+        formatter.add_field_aligned("Actual Result", &format_money(report.actual));
+        formatter.add_field_aligned("Expected Result", &format!("{:+.1}", report.expected));
+        formatter.add_field_aligned("Luck", &format!("{luck:+.1} ({luck_share:+.1}%)"));
+        formatter.add_field_aligned(
+            "Cost of Mistakes",
+            &format!("{:.1} ({cost_share:.1}%)", report.play_cost),
+        );
+        formatter.add_empty_line();
+        formatter.add_line(&luck_meter(luck_share));
+        formatter.add_empty_line();
+        formatter.add_line(if luck_share <= -2.0 {
+            "The cards ran cold."
+        } else if luck_share >= 2.0 {
+            "The cards ran hot."
+        } else {
+            "The cards were about as kind as expected."
+        });
+        formatter.add_line(if cost_share >= 1.0 {
+            "Your decisions gave away a noticeable edge."
+        } else if report.play_cost > 0.0 {
+            "Your decisions were close to perfect."
+        } else {
+            "Your decisions matched the best play."
+        });
+        formatter.add_separator();
+        formatter.add_line("Expected results assume an infinite shoe and");
+        formatter.add_line("that the dealer hits soft 17. Only hands from");
+        formatter.add_line("Classic tables are judged.");
+        println!("{}\n", formatter.build());
+    }
+
     pub fn display_recent_games(&self, count: usize) {
         let recent_count = count.min(self.rounds.len());
         if recent_count == 0 {
@@ -32,15 +185,18 @@ impl GameHistory {
 
         let start_index = self.rounds.len().saturating_sub(recent_count);
         for (i, round) in self.rounds[start_index..].iter().enumerate() {
-            let display_number = start_index + i + 1;
-
             // Game header
             formatter.add_field_aligned(
-                &format!("Game #{display_number}"),
+                &format!("Game #{}", round.round_number),
                 &format!(
-                    "{} | Bet: {} | {}",
+                    "{} | Bet: {}{} | {}",
                     round.outcome,
                     round.bet_amount,
+                    if round.free_bet_amount > 0 {
+                        format!(" ({} free)", round.free_bet_amount)
+                    } else {
+                        String::new()
+                    },
                     round.timestamp.format("%H:%M:%S")
                 ),
             );
@@ -77,26 +233,83 @@ impl GameHistory {
         println!("\n{}\n", formatter.build());
     }
 
-    pub fn display_detailed_game(&self, round_number: usize) {
-        if round_number == 0 || round_number > self.rounds.len() {
-            println!(
-                "Invalid game number. Please choose a number between 1 and {}.",
-                self.rounds.len()
-            );
+    /// Show one page of the games at `indices`, one line per game, where
+    /// `page` counts from 0. Games show their round number so they can be
+    /// opened with `display_detailed_game`.
+    pub fn display_game_page(&self, indices: &[usize], page: usize, page_size: usize) {
+        let start = page * page_size;
+        let end = (start + page_size).min(indices.len());
+        if start >= end {
+            println!("No games to show.\n");
             return;
         }
 
-        let round = &self.rounds[round_number - 1];
+        let mut formatter = BoxFormatter::new(
+            80,
+            &format!("GAMES {}-{end} OF {}", start + 1, indices.len()),
+        );
+        formatter.add_line(&format!(
+            "{:<6} {:<11} {:>4} {:<11} {:>6}  {}",
+            "Game", "Time", "Bet", "Outcome", "Change", "Player v Dealer"
+        ));
+        formatter.add_separator();
+        for &i in &indices[start..end] {
+            let round = &self.rounds[i];
+            let mut flags = String::new();
+            if round.was_double_down {
+                flags.push_str(" DD");
+            }
+            if round.was_split {
+                flags.push_str(" SP");
+            }
+            formatter.add_line(&format!(
+                "#{:<5} {:<11} {:>4} {:<11} {:>6}  {} ({}) v {} ({}){flags}",
+                round.round_number,
+                round.timestamp.format("%m-%d %H:%M"),
+                round.bet_amount,
+                round.outcome.to_string(),
+                format_money(round.money_change),
+                format_cards_short(&round.player_cards),
+                round.player_total,
+                format_cards_short(&round.dealer_cards),
+                round.dealer_total
+            ));
+        }
+
+        println!("\n{}\n", formatter.build());
+    }
+
+    /// Show every detail of the game numbered `round_number`
+    pub fn display_detailed_game(&self, round_number: u32) {
+        let Some(round) = self
+            .rounds
+            .iter()
+            .find(|round| round.round_number == round_number)
+        else {
+            println!("There is no game #{round_number} in your history.");
+            return;
+        };
 
         let mut formatter = BoxFormatter::new(70, &format!("GAME #{round_number} DETAILS"));
 
         formatter.add_field_aligned("Time", &round.timestamp.format("%Y-%m-%d %H:%M:%S"));
         formatter.add_field_aligned("Bet Amount", &round.bet_amount);
 
+        if round.free_split_amount() > 0 {
+            formatter.add_field_aligned("Free Split Stake", &round.free_split_amount());
+        }
+        if round.free_double_amount > 0 {
+            formatter.add_field_aligned("Free Double Stake", &round.free_double_amount);
+        }
+
         if round.was_double_down {
             formatter.add_field_aligned("Double Down", &"Yes");
         }
 
+        if round.was_split {
+            formatter.add_field_aligned("Split Hand", &"Yes");
+        }
+
         formatter.add_empty_line();
         formatter.add_field_aligned("Player Cards", &format_cards_long(&round.player_cards));
         formatter.add_field_aligned("Player Total", &round.player_total);
@@ -123,6 +336,77 @@ impl GameHistory {
     }
 }
 
+fn streak_label(streak: Option<Streak>) -> String {
+    streak.map_or("-".to_string(), |streak| {
+        format!("{} (from game {})", streak.length, streak.start + 1)
+    })
+}
+
+fn kind_label(kind: StreakKind, length: usize) -> &'static str {
+    match (kind, length) {
+        (StreakKind::Win, 1) => "win",
+        (StreakKind::Win, _) => "wins",
+        (StreakKind::Loss, 1) => "loss",
+        (StreakKind::Loss, _) => "losses",
+    }
+}
+
+/// A bar from unlucky to lucky with a marker at `luck_share`, the luck as a
+/// percentage of the coins wagered
+#[allow(
+    clippy::cast_possible_truncation,
+    clippy::cast_sign_loss,
+    clippy::cast_precision_loss
+)]
+fn luck_meter(luck_share: f64) -> String {
+    let half = LUCK_METER_HALF_WIDTH as f64;
+    let offset =
+        (luck_share.clamp(-LUCK_METER_RANGE, LUCK_METER_RANGE) / LUCK_METER_RANGE * half).round();
+    let marker = (half + offset) as usize;
+    let bar: String = (0..=2 * LUCK_METER_HALF_WIDTH)
+        .map(|cell| {
+            if cell == marker {
+                '*'
+            } else if cell == LUCK_METER_HALF_WIDTH {
+                '|'
+            } else {
+                '-'
+            }
+        })
+        .collect();
+    format!("Unlucky {bar} Lucky")
+}
+
+fn tally_table(
+    title: &str,
+    group: &str,
+    tallies: impl Iterator<Item = (String, OutcomeTally)>,
+) -> String {
+    let mut formatter = BoxFormatter::new(59, title);
+    formatter.add_line(&format!(
+        "{group:<12} {:>6} {:>5} {:>6} {:>5} {:>8} {:>7}",
+        "Hands", "Wins", "Losses", "Ties", "Win Rate", "Net"
+    ));
+    formatter.add_separator();
+    let mut any = false;
+    for (label, tally) in tallies {
+        any = true;
+        formatter.add_line(&format!(
+            "{label:<12} {:>6} {:>5} {:>6} {:>5} {:>8} {:>7}",
+            tally.hands,
+            tally.wins,
+            tally.losses,
+            tally.ties,
+            format_percentage(tally.win_rate()),
+            format_money(tally.net)
+        ));
+    }
+    if !any {
+        formatter.add_line("No hands yet");
+    }
+    formatter.build()
+}
+
 fn format_cards_short(cards: &[String]) -> String {
     if cards.len() <= 3 {
         cards
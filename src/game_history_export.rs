@@ -1,32 +1,46 @@
-use crate::game_history_core::{GameHistory, GameOutcome, GameRound};
-use chrono::{DateTime, Local};
+use crate::csv_io::{self, CsvHeader, CsvRecord, RowError};
+use crate::game_history_core::{GameHistory, GameRound};
+use crate::table_rules::GameVariant;
+use chrono::{Local, NaiveDateTime, TimeZone};
 use std::fs;
 use std::path::Path;
+use std::str::FromStr;
+
+const TIMESTAMP_FORMAT: &str = "%Y-%m-%d %H:%M:%S";
+
+/// Column names of exported CSV files and the history log
+pub const CSV_HEADER: &str = "Round,Timestamp,Bet,PlayerCards,DealerCards,PlayerTotal,DealerTotal,Outcome,MoneyChange,MoneyAfter,DoubleDown,PlayerBust,DealerBust,FreeBet,Split,Session,Actions,Variant,FreeDouble,Switched";
+
+/// Columns a history CSV file must have; the others have defaults
+const REQUIRED_COLUMNS: [&str; 9] = [
+    "Timestamp",
+    "Bet",
+    "PlayerCards",
+    "DealerCards",
+    "PlayerTotal",
+    "DealerTotal",
+    "Outcome",
+    "MoneyChange",
+    "MoneyAfter",
+];
+
+/// A history read from CSV or JSON Lines, with the rows that could not be read
+#[derive(Debug, Default)]
+pub struct HistoryImport {
+    pub history: GameHistory,
+    pub errors: Vec<RowError>,
+}
 
 impl GameHistory {
     #[must_use]
-    #[allow(clippy::format_push_string)]
     pub fn export_to_csv(&self) -> String {
         let mut csv = String::new();
-        csv.push_str("Round,Timestamp,Bet,PlayerCards,DealerCards,PlayerTotal,DealerTotal,Outcome,MoneyChange,MoneyAfter,DoubleDown,PlayerBust,DealerBust\n");
-
-        for (i, round) in self.rounds.iter().enumerate() {
-            csv.push_str(&format!(
-                "{},{},{},\"{}\",\"{}\",{},{},{},{},{},{},{},{}\n",
-                i + 1,
-                round.timestamp.format("%Y-%m-%d %H:%M:%S"),
-                round.bet_amount,
-                round.player_cards.join(" | "),
-                round.dealer_cards.join(" | "),
-                round.player_total,
-                round.dealer_total,
-                round.outcome,
-                round.money_change,
-                round.money_after,
-                round.was_double_down,
-                round.player_busted,
-                round.dealer_busted
-            ));
+        csv.push_str(CSV_HEADER);
+        csv.push('\n');
+
+        for round in &self.rounds {
+            csv.push_str(&round.to_csv_row());
+            csv.push('\n');
         }
 
         csv
@@ -36,9 +50,62 @@ impl GameHistory {
     ///
     /// # Errors
     ///
-    /// Returns an error indicating that JSON export is not implemented.
+    /// Returns an error if the history cannot be serialized.
     pub fn export_to_json(&self) -> Result<String, String> {
-        Err("JSON export not implemented - serde_json dependency not available".to_string())
+        serde_json::to_string_pretty(self).map_err(|e| format!("Failed to export JSON: {e}"))
+    }
+
+    /// Export game history as JSON Lines, one round per line.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a round cannot be serialized.
+    pub fn export_to_jsonl(&self) -> Result<String, String> {
+        let mut jsonl = String::new();
+        for round in &self.rounds {
+            let line =
+                serde_json::to_string(round).map_err(|e| format!("Failed to export JSON: {e}"))?;
+            jsonl.push_str(&line);
+            jsonl.push('\n');
+        }
+        Ok(jsonl)
+    }
+
+    /// Read a history written by `export_to_json`. The totals are worked
+    /// out again from the rounds rather than trusted from the file.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the text is not a valid history.
+    pub fn from_json(content: &str) -> Result<Self, String> {
+        let parsed: GameHistory =
+            serde_json::from_str(content).map_err(|e| format!("Invalid history JSON: {e}"))?;
+        let mut history = GameHistory::new();
+        history.session_start = parsed.session_start;
+        for round in parsed.rounds {
+            history.add_round(round);
+        }
+        Ok(history)
+    }
+
+    /// Read a history written by `export_to_jsonl`, reporting lines that are
+    /// not a valid round.
+    #[must_use]
+    pub fn parse_jsonl(content: &str) -> HistoryImport {
+        let mut import = HistoryImport::default();
+        for (i, line) in content.lines().enumerate() {
+            if line.trim().is_empty() {
+                continue;
+            }
+            match serde_json::from_str::<GameRound>(line) {
+                Ok(round) => import.history.add_round(round),
+                Err(e) => import.errors.push(RowError {
+                    line: i + 1,
+                    message: e.to_string(),
+                }),
+            }
+        }
+        import
     }
 
     #[must_use]
@@ -67,11 +134,10 @@ impl GameHistory {
             let recent_count = 10.min(self.rounds.len());
             let start_index = self.rounds.len().saturating_sub(recent_count);
 
-            for (i, round) in self.rounds[start_index..].iter().enumerate() {
-                let game_number = start_index + i + 1;
+            for round in &self.rounds[start_index..] {
                 summary.push_str(&format!(
                     "Game #{}: {} - Bet: {} - {}\n",
-                    game_number,
+                    round.round_number,
                     round.outcome,
                     round.bet_amount,
                     round.timestamp.format("%H:%M:%S")
@@ -86,99 +152,303 @@ impl GameHistory {
     ///
     /// # Errors
     ///
-    /// Returns an error if the file cannot be read or parsed.
-    pub fn load_from_csv(file_path: &str) -> Result<Self, String> {
-        if !Path::new(file_path).exists() {
-            return Ok(GameHistory::new());
+    /// Returns an error if the file cannot be read. Rows that cannot be
+    /// parsed are listed in the returned import instead.
+    pub fn load_from_csv<P: AsRef<Path>>(file_path: P) -> Result<HistoryImport, String> {
+        let file_path = file_path.as_ref();
+        if !file_path.exists() {
+            return Ok(HistoryImport::default());
         }
 
         let content =
             fs::read_to_string(file_path).map_err(|e| format!("Failed to read CSV file: {e}"))?;
 
-        let mut history = GameHistory::new();
-        let lines: Vec<&str> = content.lines().collect();
+        Ok(Self::parse_csv(&content))
+    }
 
-        // Skip header line
-        for line in lines.iter().skip(1) {
-            if line.trim().is_empty() {
-                continue;
+    /// Read a history from CSV text with a header row. Columns are matched
+    /// by name, so they may come in any order and unknown ones are ignored.
+    #[must_use]
+    pub fn parse_csv(content: &str) -> HistoryImport {
+        let mut import = HistoryImport::default();
+        let mut records = csv_io::parse(content).into_iter();
+
+        let header = match records.next() {
+            None => return import,
+            Some(Err(e)) => {
+                import.errors.push(e);
+                return import;
             }
+            Some(Ok(record)) => CsvHeader::new(&record),
+        };
+        let missing: Vec<&str> = REQUIRED_COLUMNS
+            .into_iter()
+            .filter(|name| !header.has_column(name))
+            .collect();
+        if !missing.is_empty() {
+            import.errors.push(RowError {
+                line: 1,
+                message: format!("header is missing column(s) {}", missing.join(", ")),
+            });
+            return import;
+        }
 
-            let parts: Vec<&str> = line.split(',').collect();
-            if parts.len() < 13 {
-                continue; // Skip malformed lines
+        for record in records {
+            match record.and_then(|record| GameRound::from_csv_record(&header, &record)) {
+                Ok(round) => import.history.add_round(round),
+                Err(e) => import.errors.push(e),
             }
+        }
+        import
+    }
+}
 
-            // Parse the fields
-            let round_number = parts[0].parse::<u32>().unwrap_or(0);
-            let timestamp_str = parts[1].trim();
-            let bet_amount = parts[2].parse::<i32>().unwrap_or(0);
-            let player_cards_str = parts[3].trim_matches('"');
-            let dealer_cards_str = parts[4].trim_matches('"');
-            let player_total = parts[5].parse::<i32>().unwrap_or(0);
-            let dealer_total = parts[6].parse::<i32>().unwrap_or(0);
-            let outcome_str = parts[7].trim();
-            let money_change = parts[8].parse::<i32>().unwrap_or(0);
-            let money_after = parts[9].parse::<i32>().unwrap_or(0);
-            let was_double_down = parts[10].trim().eq_ignore_ascii_case("true");
-            let player_busted = parts[11].trim().eq_ignore_ascii_case("true");
-            let dealer_busted = parts[12].trim().eq_ignore_ascii_case("true");
-
-            // Parse timestamp
-            let timestamp = DateTime::parse_from_str(timestamp_str, "%Y-%m-%d %H:%M:%S")
-                .map_or_else(|_| Local::now(), |dt| dt.with_timezone(&Local));
-
-            // Parse outcome
-            let outcome = match outcome_str {
-                "Player Win" => GameOutcome::PlayerWin,
-                "Dealer Win" => GameOutcome::DealerWin,
-                "Tie" => GameOutcome::Tie,
-                "Player Bust" => GameOutcome::PlayerBust,
-                "Dealer Bust" => GameOutcome::DealerBust,
-                _ => {
-                    eprintln!("Warning: Unknown outcome '{outcome_str}', defaulting to Dealer Win");
-                    GameOutcome::DealerWin
-                }
-            };
+impl GameRound {
+    /// One CSV record for this round, without a line break
+    #[must_use]
+    pub fn to_csv_row(&self) -> String {
+        csv_io::write_row(&[
+            self.round_number.to_string(),
+            self.timestamp.format(TIMESTAMP_FORMAT).to_string(),
+            self.bet_amount.to_string(),
+            self.player_cards.join(" | "),
+            self.dealer_cards.join(" | "),
+            self.player_total.to_string(),
+            self.dealer_total.to_string(),
+            self.outcome.to_string(),
+            self.money_change.to_string(),
+            self.money_after.to_string(),
+            self.was_double_down.to_string(),
+            self.player_busted.to_string(),
+            self.dealer_busted.to_string(),
+            self.free_bet_amount.to_string(),
+            self.was_split.to_string(),
+            self.session.to_string(),
+            self.actions.clone(),
+            self.variant.to_string(),
+            self.free_double_amount.to_string(),
+            self.was_switched.to_string(),
+        ])
+    }
 
-            // Parse cards
-            let player_cards: Vec<String> = if player_cards_str.is_empty() {
-                Vec::new()
-            } else {
-                player_cards_str
-                    .split(" | ")
-                    .map(std::string::ToString::to_string)
-                    .collect()
-            };
+    /// Read a round from a record of a file with the given header.
+    ///
+    /// # Errors
+    ///
+    /// Returns the record's line and the first value that is missing or
+    /// cannot be parsed.
+    pub fn from_csv_record(header: &CsvHeader, record: &CsvRecord) -> Result<Self, RowError> {
+        let fields = RecordFields { header, record };
+        let row_error = |message| RowError {
+            line: record.line,
+            message,
+        };
 
-            let dealer_cards: Vec<String> = if dealer_cards_str.is_empty() {
-                Vec::new()
-            } else {
-                dealer_cards_str
-                    .split(" | ")
-                    .map(std::string::ToString::to_string)
-                    .collect()
-            };
+        let timestamp = fields.required("Timestamp").map_err(row_error)?;
+        let timestamp = NaiveDateTime::parse_from_str(timestamp.trim(), TIMESTAMP_FORMAT)
+            .ok()
+            .and_then(|naive| Local.from_local_datetime(&naive).earliest())
+            .ok_or_else(|| row_error(format!("Timestamp \"{timestamp}\" is not a valid time")))?;
+        let player_total = fields.parse("PlayerTotal").map_err(row_error)?;
+        let dealer_total = fields.parse("DealerTotal").map_err(row_error)?;
 
-            let round = GameRound {
-                round_number,
-                timestamp,
-                bet_amount,
-                player_cards,
-                dealer_cards,
-                player_total,
-                dealer_total,
-                outcome,
-                money_change,
-                money_after,
-                was_double_down,
-                player_busted,
-                dealer_busted,
-            };
+        Ok(GameRound {
+            round_number: fields.parse_or("Round", 0).map_err(row_error)?,
+            session: fields.parse_or("Session", 0).map_err(row_error)?,
+            timestamp,
+            variant: fields
+                .parse_or("Variant", GameVariant::Classic)
+                .map_err(row_error)?,
+            bet_amount: fields.parse("Bet").map_err(row_error)?,
+            // Older files were written before free bets were tracked
+            free_bet_amount: fields.parse_or("FreeBet", 0).map_err(row_error)?,
+            free_double_amount: fields.parse_or("FreeDouble", 0).map_err(row_error)?,
+            player_cards: parse_cards(fields.required("PlayerCards").map_err(row_error)?),
+            dealer_cards: parse_cards(fields.required("DealerCards").map_err(row_error)?),
+            player_total,
+            dealer_total,
+            outcome: fields.parse("Outcome").map_err(row_error)?,
+            money_change: fields.parse("MoneyChange").map_err(row_error)?,
+            money_after: fields.parse("MoneyAfter").map_err(row_error)?,
+            was_double_down: fields
+                .parse_bool_or("DoubleDown", false)
+                .map_err(row_error)?,
+            was_split: fields.parse_bool_or("Split", false).map_err(row_error)?,
+            was_switched: fields.parse_bool_or("Switched", false).map_err(row_error)?,
+            actions: fields.optional("Actions").trim().to_string(),
+            player_busted: fields
+                .parse_bool_or("PlayerBust", player_total > 21)
+                .map_err(row_error)?,
+            dealer_busted: fields
+                .parse_bool_or("DealerBust", dealer_total > 21)
+                .map_err(row_error)?,
+        })
+    }
+}
 
-            history.add_round(round);
+fn parse_cards(cards: &str) -> Vec<String> {
+    cards
+        .split('|')
+        .map(str::trim)
+        .filter(|card| !card.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+/// Looks up the values of one record by column name
+struct RecordFields<'a> {
+    header: &'a CsvHeader,
+    record: &'a CsvRecord,
+}
+
+impl<'a> RecordFields<'a> {
+    fn required(&self, column: &str) -> Result<&'a str, String> {
+        self.header
+            .get(self.record, column)
+            .ok_or_else(|| format!("no value for {column}"))
+    }
+
+    /// The value of an optional column, empty when it is absent
+    fn optional(&self, column: &str) -> &'a str {
+        self.header.get(self.record, column).unwrap_or_default()
+    }
+
+    fn parse<T: FromStr>(&self, column: &str) -> Result<T, String> {
+        let value = self.required(column)?;
+        value
+            .trim()
+            .parse()
+            .map_err(|_| format!("{column} \"{value}\" is not valid"))
+    }
+
+    /// Parse an optional column, using `default` when it is absent or empty
+    fn parse_or<T: FromStr>(&self, column: &str, default: T) -> Result<T, String> {
+        match self.header.get(self.record, column) {
+            Some(value) if !value.trim().is_empty() => self.parse(column),
+            _ => Ok(default),
         }
+    }
 
-        Ok(history)
+    fn parse_bool_or(&self, column: &str, default: bool) -> Result<bool, String> {
+        match self.header.get(self.record, column).map(str::trim) {
+            None | Some("") => Ok(default),
+            Some(value) if value.eq_ignore_ascii_case("true") => Ok(true),
+            Some(value) if value.eq_ignore_ascii_case("false") => Ok(false),
+            Some(value) => Err(format!("{column} \"{value}\" is not true or false")),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game_history_core::GameOutcome;
+
+    fn cards(names: &[&str]) -> Vec<String> {
+        names.iter().map(ToString::to_string).collect()
+    }
+
+    /// A history with one hand of every outcome. Timestamps are whole
+    /// seconds, the precision CSV files keep.
+    fn sample_history() -> GameHistory {
+        let mut history = GameHistory::new();
+        let start = Local.with_ymd_and_hms(2026, 6, 15, 12, 30, 5).unwrap();
+        let outcomes = [
+            GameOutcome::PlayerWin,
+            GameOutcome::DealerWin,
+            GameOutcome::Tie,
+            GameOutcome::PlayerBust,
+            GameOutcome::DealerBust,
+            GameOutcome::Surrender,
+        ];
+        let mut money = 100;
+        for (i, (outcome, variant)) in outcomes.into_iter().zip(GameVariant::ALL).enumerate() {
+            let n = u32::try_from(i).unwrap();
+            let change = match outcome {
+                GameOutcome::PlayerWin | GameOutcome::DealerBust => 20,
+                GameOutcome::Tie => 0,
+                GameOutcome::Surrender => -5,
+                _ => -20,
+            };
+            money += change;
+            history.add_round(GameRound {
+                round_number: n + 1,
+                session: 3,
+                timestamp: start + chrono::TimeDelta::seconds(i64::from(n) * 61),
+                variant,
+                bet_amount: 20,
+                free_bet_amount: if i == 0 { 10 } else { 0 },
+                free_double_amount: if i == 0 { 10 } else { 0 },
+                player_cards: cards(&["6 Hearts", "4 Clubs", "K Spades"]),
+                dealer_cards: cards(&["10 Diamonds", "9 Hearts"]),
+                player_total: if outcome == GameOutcome::PlayerBust {
+                    24
+                } else {
+                    20
+                },
+                dealer_total: if outcome == GameOutcome::DealerBust {
+                    25
+                } else {
+                    19
+                },
+                outcome,
+                money_change: change,
+                money_after: money,
+                was_double_down: i == 0,
+                was_split: i == 1,
+                was_switched: i == 2,
+                actions: if i == 0 {
+                    "D".to_string()
+                } else {
+                    "HS".to_string()
+                },
+                player_busted: outcome == GameOutcome::PlayerBust,
+                dealer_busted: outcome == GameOutcome::DealerBust,
+            });
+        }
+        history
+    }
+
+    fn assert_same_history(read: &GameHistory, written: &GameHistory) {
+        assert_eq!(read.rounds, written.rounds);
+        assert_eq!(read.total_games_played, written.total_games_played);
+        assert_eq!(read.total_wins, written.total_wins);
+        assert_eq!(read.total_losses, written.total_losses);
+        assert_eq!(read.total_ties, written.total_ties);
+    }
+
+    #[test]
+    fn csv_export_reads_back_the_same_rounds() {
+        let history = sample_history();
+        let import = GameHistory::parse_csv(&history.export_to_csv());
+        assert!(import.errors.is_empty(), "{:?}", import.errors);
+        assert_same_history(&import.history, &history);
+    }
+
+    #[test]
+    fn json_export_reads_back_the_same_rounds() {
+        let history = sample_history();
+        let read = GameHistory::from_json(&history.export_to_json().unwrap()).unwrap();
+        assert_same_history(&read, &history);
+        assert_eq!(read.session_start, history.session_start);
+    }
+
+    #[test]
+    fn jsonl_export_reads_back_the_same_rounds() {
+        let history = sample_history();
+        let import = GameHistory::parse_jsonl(&history.export_to_jsonl().unwrap());
+        assert!(import.errors.is_empty(), "{:?}", import.errors);
+        assert_same_history(&import.history, &history);
+    }
+
+    #[test]
+    fn csv_import_reports_bad_rows_and_keeps_the_rest() {
+        let csv = sample_history().export_to_csv();
+        let mut lines: Vec<&str> = csv.lines().collect();
+        lines[2] = "2,yesterday,20";
+        let import = GameHistory::parse_csv(&lines.join("\n"));
+        assert_eq!(import.history.rounds.len(), 5);
+        assert_eq!(import.errors.len(), 1);
+        assert_eq!(import.errors[0].line, 3);
     }
 }
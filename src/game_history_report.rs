@@ -0,0 +1,483 @@
+use crate::basic_strategy::upcard_value;
+use crate::formatting::format_percentage;
+use crate::game_history_core::{GameHistory, GameOutcome, GameRound};
+use crate::player_handler::{hand_value, is_soft_hand};
+use chrono::Local;
+use std::collections::BTreeMap;
+use std::fmt::Write;
+
+const CHART_WIDTH: usize = 60;
+const CHART_HEIGHT: usize = 12;
+const SVG_WIDTH: f64 = 720.0;
+const SVG_HEIGHT: f64 = 240.0;
+
+/// Wins, losses and ties of a group of hands, with their net result
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct OutcomeTally {
+    pub hands: u32,
+    pub wins: u32,
+    pub losses: u32,
+    pub ties: u32,
+    pub net: i32,
+}
+
+impl OutcomeTally {
+    fn add(&mut self, round: &GameRound) {
+        self.hands += 1;
+        match round.outcome {
+            GameOutcome::PlayerWin | GameOutcome::DealerBust => self.wins += 1,
+            GameOutcome::Tie => self.ties += 1,
+            GameOutcome::DealerWin | GameOutcome::PlayerBust | GameOutcome::Surrender => {
+                self.losses += 1;
+            }
+        }
+        self.net += round.money_change;
+    }
+
+    #[must_use]
+    pub fn win_rate(&self) -> f64 {
+        if self.hands == 0 {
+            0.0
+        } else {
+            f64::from(self.wins) / f64::from(self.hands) * 100.0
+        }
+    }
+}
+
+/// The dealer's face-up card as shown in reports: 2-10 or A
+#[must_use]
+pub fn upcard_label(value: i32) -> String {
+    if value == 11 {
+        "A".to_string()
+    } else {
+        value.to_string()
+    }
+}
+
+/// A player's first two cards as a total, e.g. "16" or "Soft 18"
+#[must_use]
+pub fn starting_total_label(total: i32, soft: bool) -> String {
+    if soft {
+        format!("Soft {total}")
+    } else {
+        total.to_string()
+    }
+}
+
+/// Outcomes grouped by the value of the dealer's upcard, 2 through 11 (ace)
+#[must_use]
+pub fn tally_by_upcard(rounds: &[GameRound]) -> BTreeMap<i32, OutcomeTally> {
+    let mut tallies = BTreeMap::<i32, OutcomeTally>::new();
+    for round in rounds {
+        if let Some(upcard) = round.dealer_cards.first() {
+            tallies.entry(upcard_value(upcard)).or_default().add(round);
+        }
+    }
+    tallies
+}
+
+/// Outcomes grouped by the total of the player's first two cards and
+/// whether it was soft
+#[must_use]
+pub fn tally_by_starting_total(rounds: &[GameRound]) -> BTreeMap<(bool, i32), OutcomeTally> {
+    let mut tallies = BTreeMap::<(bool, i32), OutcomeTally>::new();
+    for round in rounds {
+        if let Some(start) = round.player_cards.get(..2) {
+            let key = (is_soft_hand(start), hand_value(start));
+            tallies.entry(key).or_default().add(round);
+        }
+    }
+    tallies
+}
+
+/// Outcomes of the hands where the player doubled down, split, or hit
+/// until busting, in that order
+#[must_use]
+pub fn tally_by_action(rounds: &[GameRound]) -> [(&'static str, OutcomeTally); 3] {
+    let mut doubled = OutcomeTally::default();
+    let mut split = OutcomeTally::default();
+    let mut hit_to_bust = OutcomeTally::default();
+    for round in rounds {
+        if round.was_double_down {
+            doubled.add(round);
+        }
+        if round.was_split {
+            split.add(round);
+        }
+        if round.player_busted && !round.was_double_down {
+            hit_to_bust.add(round);
+        }
+    }
+    [
+        ("Doubled down", doubled),
+        ("Split hands", split),
+        ("Hit to bust", hit_to_bust),
+    ]
+}
+
+/// How often each side busts and how many cards their hands end with
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct HandStats {
+    pub hands: u32,
+    pub player_busts: u32,
+    pub dealer_busts: u32,
+    pub player_cards: u32,
+    pub dealer_cards: u32,
+}
+
+impl HandStats {
+    #[must_use]
+    #[allow(clippy::cast_possible_truncation)]
+    pub fn from_rounds(rounds: &[GameRound]) -> Self {
+        let mut stats = HandStats::default();
+        for round in rounds {
+            stats.hands += 1;
+            stats.player_busts += u32::from(round.player_busted);
+            stats.dealer_busts += u32::from(round.dealer_busted);
+            stats.player_cards += round.player_cards.len() as u32;
+            stats.dealer_cards += round.dealer_cards.len() as u32;
+        }
+        stats
+    }
+
+    #[must_use]
+    pub fn player_bust_rate(&self) -> f64 {
+        self.per_hand(self.player_busts) * 100.0
+    }
+
+    #[must_use]
+    pub fn dealer_bust_rate(&self) -> f64 {
+        self.per_hand(self.dealer_busts) * 100.0
+    }
+
+    #[must_use]
+    pub fn average_player_cards(&self) -> f64 {
+        self.per_hand(self.player_cards)
+    }
+
+    #[must_use]
+    pub fn average_dealer_cards(&self) -> f64 {
+        self.per_hand(self.dealer_cards)
+    }
+
+    fn per_hand(&self, count: u32) -> f64 {
+        if self.hands == 0 {
+            0.0
+        } else {
+            f64::from(count) / f64::from(self.hands)
+        }
+    }
+}
+
+/// A text chart of `values`, `height` rows of at most `width` columns,
+/// highest row first. Longer series are sampled to fit.
+#[must_use]
+#[allow(clippy::cast_possible_wrap)]
+pub fn text_chart(values: &[i32], width: usize, height: usize) -> Vec<String> {
+    if values.is_empty() || width == 0 || height == 0 {
+        return Vec::new();
+    }
+    let columns: Vec<i32> = if values.len() <= width {
+        values.to_vec()
+    } else {
+        (1..=width)
+            .map(|col| values[col * values.len() / width - 1])
+            .collect()
+    };
+    let min = columns.iter().copied().min().unwrap_or(0);
+    let max = columns.iter().copied().max().unwrap_or(0);
+    let span = i64::from(max - min).max(1);
+    let label_width = max.to_string().len().max(min.to_string().len());
+
+    (0..height)
+        .map(|row| {
+            let level = height - 1 - row;
+            let label = if row == 0 {
+                max.to_string()
+            } else if level == 0 {
+                min.to_string()
+            } else {
+                String::new()
+            };
+            let bars: String = columns
+                .iter()
+                .map(|&value| {
+                    let filled = i64::from(value - min) * (height as i64 - 1) / span;
+                    if filled >= level as i64 {
+                        '█'
+                    } else {
+                        ' '
+                    }
+                })
+                .collect();
+            format!("{label:>label_width$} │{bars}")
+        })
+        .collect()
+}
+
+impl GameHistory {
+    /// A Markdown report with the summary, a bankroll chart, outcome
+    /// breakdowns and every hand played
+    #[must_use]
+    pub fn export_to_markdown(&self) -> String {
+        let mut md = String::new();
+        writeln!(md, "# Blackjack Report\n").ok();
+        writeln!(
+            md,
+            "Generated {}.\n",
+            Local::now().format("%Y-%m-%d %H:%M:%S")
+        )
+        .ok();
+
+        writeln!(md, "## Summary\n").ok();
+        writeln!(md, "| Statistic | Value |\n|---|---:|").ok();
+        for (label, value) in self.summary_rows() {
+            writeln!(md, "| {label} | {value} |").ok();
+        }
+
+        if !self.rounds.is_empty() {
+            writeln!(md, "\n## Bankroll\n\n```").ok();
+            let bankroll: Vec<i32> = self.rounds.iter().map(|r| r.money_after).collect();
+            for line in text_chart(&bankroll, CHART_WIDTH, CHART_HEIGHT) {
+                writeln!(md, "{line}").ok();
+            }
+            writeln!(md, "```").ok();
+        }
+
+        writeln!(md, "\n## By dealer upcard\n").ok();
+        write_markdown_tallies(
+            &mut md,
+            "Upcard",
+            tally_by_upcard(&self.rounds)
+                .into_iter()
+                .map(|(upcard, tally)| (upcard_label(upcard), tally)),
+        );
+
+        writeln!(md, "\n## By starting total\n").ok();
+        write_markdown_tallies(
+            &mut md,
+            "Start",
+            tally_by_starting_total(&self.rounds)
+                .into_iter()
+                .map(|((soft, total), tally)| (starting_total_label(total, soft), tally)),
+        );
+
+        writeln!(md, "\n## Hands\n").ok();
+        writeln!(
+            md,
+            "| # | Time | Bet | Player | Dealer | Outcome | Change | Bankroll |\n|---:|---|---:|---|---|---|---:|---:|"
+        ).ok();
+        for round in &self.rounds {
+            writeln!(
+                md,
+                "| {} | {} | {} | {} ({}) | {} ({}) | {} | {:+} | {} |",
+                round.round_number,
+                round.timestamp.format("%Y-%m-%d %H:%M"),
+                round.bet_amount,
+                round.player_cards.join(", "),
+                round.player_total,
+                round.dealer_cards.join(", "),
+                round.dealer_total,
+                round.outcome,
+                round.money_change,
+                round.money_after
+            )
+            .ok();
+        }
+        md
+    }
+
+    /// A self-contained HTML report with the same sections as the Markdown
+    /// one; the bankroll chart is an inline SVG
+    #[must_use]
+    pub fn export_to_html(&self) -> String {
+        let mut html = String::new();
+        writeln!(
+            html,
+            "<!DOCTYPE html>\n<html lang=\"en\">\n<head>\n<meta charset=\"utf-8\">\n<title>Blackjack Report</title>\n<style>\n\
+             body {{ font-family: sans-serif; margin: 2em; color: #222; }}\n\
+             table {{ border-collapse: collapse; margin-bottom: 1.5em; }}\n\
+             th, td {{ border: 1px solid #ccc; padding: 4px 10px; }}\n\
+             th {{ background: #f0f0f0; }}\n\
+             td.num {{ text-align: right; }}\n\
+             .win {{ color: #1a7f37; }} .loss {{ color: #c62828; }}\n\
+             </style>\n</head>\n<body>"
+        ).ok();
+        writeln!(html, "<h1>Blackjack Report</h1>").ok();
+        writeln!(
+            html,
+            "<p>Generated {}.</p>",
+            Local::now().format("%Y-%m-%d %H:%M:%S")
+        )
+        .ok();
+
+        writeln!(html, "<h2>Summary</h2>\n<table>").ok();
+        for (label, value) in self.summary_rows() {
+            writeln!(
+                html,
+                "<tr><th>{}</th><td class=\"num\">{}</td></tr>",
+                escape_html(label),
+                escape_html(&value)
+            )
+            .ok();
+        }
+        writeln!(html, "</table>").ok();
+
+        if !self.rounds.is_empty() {
+            let bankroll: Vec<i32> = self.rounds.iter().map(|r| r.money_after).collect();
+            writeln!(html, "<h2>Bankroll</h2>\n{}", svg_chart(&bankroll)).ok();
+        }
+
+        writeln!(html, "<h2>By dealer upcard</h2>").ok();
+        write_html_tallies(
+            &mut html,
+            "Upcard",
+            tally_by_upcard(&self.rounds)
+                .into_iter()
+                .map(|(upcard, tally)| (upcard_label(upcard), tally)),
+        );
+
+        writeln!(html, "<h2>By starting total</h2>").ok();
+        write_html_tallies(
+            &mut html,
+            "Start",
+            tally_by_starting_total(&self.rounds)
+                .into_iter()
+                .map(|((soft, total), tally)| (starting_total_label(total, soft), tally)),
+        );
+
+        writeln!(
+            html,
+            "<h2>Hands</h2>\n<table>\n<tr><th>#</th><th>Time</th><th>Bet</th><th>Player</th><th>Dealer</th><th>Outcome</th><th>Change</th><th>Bankroll</th></tr>"
+        ).ok();
+        for round in &self.rounds {
+            let class = match round.money_change {
+                change if change > 0 => " win",
+                change if change < 0 => " loss",
+                _ => "",
+            };
+            writeln!(
+                html,
+                "<tr><td class=\"num\">{}</td><td>{}</td><td class=\"num\">{}</td><td>{} ({})</td><td>{} ({})</td><td>{}</td><td class=\"num{class}\">{:+}</td><td class=\"num\">{}</td></tr>",
+                round.round_number,
+                round.timestamp.format("%Y-%m-%d %H:%M"),
+                round.bet_amount,
+                escape_html(&round.player_cards.join(", ")),
+                round.player_total,
+                escape_html(&round.dealer_cards.join(", ")),
+                round.dealer_total,
+                round.outcome,
+                round.money_change,
+                round.money_after
+            ).ok();
+        }
+        writeln!(html, "</table>\n</body>\n</html>").ok();
+        html
+    }
+
+    fn summary_rows(&self) -> Vec<(&'static str, String)> {
+        vec![
+            ("Games", self.total_games_played.to_string()),
+            ("Wins", self.total_wins.to_string()),
+            ("Losses", self.total_losses.to_string()),
+            ("Ties", self.total_ties.to_string()),
+            ("Win rate", format_percentage(self.get_win_rate())),
+            ("Net profit", format!("{:+}", self.get_net_profit())),
+            ("Biggest win", self.biggest_win.to_string()),
+            ("Biggest loss", self.biggest_loss.to_string()),
+        ]
+    }
+}
+
+fn write_markdown_tallies(
+    md: &mut String,
+    group: &str,
+    tallies: impl Iterator<Item = (String, OutcomeTally)>,
+) {
+    writeln!(
+        md,
+        "| {group} | Hands | Wins | Losses | Ties | Win rate | Net |\n|---|---:|---:|---:|---:|---:|---:|"
+    ).ok();
+    for (label, tally) in tallies {
+        writeln!(
+            md,
+            "| {label} | {} | {} | {} | {} | {} | {:+} |",
+            tally.hands,
+            tally.wins,
+            tally.losses,
+            tally.ties,
+            format_percentage(tally.win_rate()),
+            tally.net
+        )
+        .ok();
+    }
+}
+
+fn write_html_tallies(
+    html: &mut String,
+    group: &str,
+    tallies: impl Iterator<Item = (String, OutcomeTally)>,
+) {
+    writeln!(
+        html,
+        "<table>\n<tr><th>{group}</th><th>Hands</th><th>Wins</th><th>Losses</th><th>Ties</th><th>Win rate</th><th>Net</th></tr>"
+    ).ok();
+    for (label, tally) in tallies {
+        writeln!(
+            html,
+            "<tr><td>{}</td><td class=\"num\">{}</td><td class=\"num\">{}</td><td class=\"num\">{}</td><td class=\"num\">{}</td><td class=\"num\">{}</td><td class=\"num\">{:+}</td></tr>",
+            escape_html(&label),
+            tally.hands,
+            tally.wins,
+            tally.losses,
+            tally.ties,
+            format_percentage(tally.win_rate()),
+            tally.net
+        ).ok();
+    }
+    writeln!(html, "</table>").ok();
+}
+
+/// An SVG line chart of `values` with the lowest and highest value marked
+#[allow(clippy::cast_precision_loss)]
+fn svg_chart(values: &[i32]) -> String {
+    let min = values.iter().copied().min().unwrap_or(0);
+    let max = values.iter().copied().max().unwrap_or(0);
+    let span = f64::from((max - min).max(1));
+    let step = if values.len() > 1 {
+        SVG_WIDTH / (values.len() - 1) as f64
+    } else {
+        0.0
+    };
+    let points: Vec<String> = values
+        .iter()
+        .enumerate()
+        .map(|(i, &value)| {
+            let x = i as f64 * step;
+            let y = SVG_HEIGHT - f64::from(value - min) / span * SVG_HEIGHT;
+            format!("{x:.1},{y:.1}")
+        })
+        .collect();
+
+    format!(
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{w}\" height=\"{h}\" viewBox=\"-50 -10 {vw} {vh}\">\n\
+         <line x1=\"0\" y1=\"0\" x2=\"0\" y2=\"{SVG_HEIGHT}\" stroke=\"#999\"/>\n\
+         <line x1=\"0\" y1=\"{SVG_HEIGHT}\" x2=\"{SVG_WIDTH}\" y2=\"{SVG_HEIGHT}\" stroke=\"#999\"/>\n\
+         <text x=\"-8\" y=\"10\" text-anchor=\"end\" font-size=\"12\">{max}</text>\n\
+         <text x=\"-8\" y=\"{SVG_HEIGHT}\" text-anchor=\"end\" font-size=\"12\">{min}</text>\n\
+         <polyline fill=\"none\" stroke=\"#1565c0\" stroke-width=\"2\" points=\"{}\"/>\n\
+         </svg>",
+        points.join(" "),
+        w = SVG_WIDTH + 60.0,
+        h = SVG_HEIGHT + 30.0,
+        vw = SVG_WIDTH + 60.0,
+        vh = SVG_HEIGHT + 30.0,
+    )
+}
+
+fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
@@ -1,81 +1,273 @@
 use crate::art_handler::{get_message, get_splash_screen, print_game_status};
-use crate::deck_manager::create_and_shuffle_deck;
+use crate::deck_manager::create_stacked_deck_for;
 use crate::enemy_ai_handler;
-use crate::game_state::GameState;
-use crate::player_handler::{player_turn, print_initial_game_state};
-use crate::win_checker::determine_winner;
+use crate::game_state::{GameState, PlayedHand};
+use crate::leaderboard;
+use crate::player_handler::{
+    hand_value, player_turn, pontoon_turn, print_initial_game_state, switch_phase,
+};
+use crate::round_recovery::RoundPhase;
+use crate::side_bets::{PlacedSideBet, SideBetKind, SideBetTiming};
+use crate::table_rules::GameVariant;
+use crate::win_checker::{determine_winner, resolve_side_bets};
+use rand::{thread_rng, Rng};
 use std::io::{self, Write};
 use std::thread;
 use std::time::Duration;
 
 pub fn start_blackjack() {
-    let mut state = GameState::new();
-    start_blackjack_with_state(&mut state);
+    let profile = match crate::profiles::startup_profile() {
+        Ok(profile) => profile,
+        Err(e) => {
+            eprintln!("✗ Could not open your profile: {e}");
+            return;
+        }
+    };
+    let _lock = match profile.lock() {
+        Ok(lock) => lock,
+        Err(e) => {
+            eprintln!("✗ Could not open your profile: {e}");
+            return;
+        }
+    };
+    let resume = crate::round_recovery::offer_resume(&profile);
+    match GameState::try_new(&profile) {
+        Ok(mut state) => {
+            if let Some(snapshot) = resume {
+                snapshot.restore(&mut state);
+            }
+            start_blackjack_with_state(&mut state);
+        }
+        Err(e) => print_save_error(&e),
+    }
+}
+
+/// Explain why the game cannot start without touching the save file
+pub fn print_save_error(error: &crate::save_system::SaveError) {
+    eprintln!("✗ Could not load your saved game: {error}");
+    eprintln!("  Your save file has been left untouched. Fix or move it to start over.");
 }
 
 pub fn start_blackjack_with_state(state: &mut GameState) {
     print_splash_screen();
     delay();
+    println!("Table: {}", state.rules.variant);
+    if state.has_round_in_progress() {
+        println!("Resuming your unfinished round.");
+    }
     loop {
         // Check if player has any money before starting a new round
-        if state.money <= 0 {
-            println!("\x1b[1;31m{}\x1b[0m", get_message("Game Over", None));
-            print!("{} ", get_message("Do you want to (t)ry again", None));
-            io::stdout().flush().ok();
-            let c = read_char();
-            if c == 't' {
-                state.money = crate::save_system::STARTING_MONEY;
-                state.games_won = 0;
-                state.games_lost = 0;
-                // Save the reset state
-                state.save_to_disk();
-                // Don't reset history - keep the game history across restarts
+        if state.money <= 0 && !state.has_round_in_progress() {
+            if game_over(state) {
                 continue;
             }
             break;
         }
 
-        setup_new_round(state);
-        print_game_status(state);
-        state.current_round_start_money = state.money;
-        state.bet = get_bet(state);
-        if state.bet == -1 {
+        if !state.has_round_in_progress() && !start_round(state) {
             break;
         }
-        state.money -= state.bet;
-        state.was_double_down = false;
-        print_initial_game_state(state);
+        play_current_round(state);
+    }
+}
+
+/// The bankroll has run out: close the run for the leaderboard and offer a
+/// new one. Returns `true` if the player starts again with a fresh bankroll.
+fn game_over(state: &mut GameState) -> bool {
+    println!("\x1b[1;31m{}\x1b[0m", get_message("Game Over", Some(state)));
+    if let Some(profile) = &state.profile {
+        leaderboard::end_run(profile, &state.history.rounds);
+    }
+    print!(
+        "{} ",
+        get_message("Do you want to (t)ry again", Some(state))
+    );
+    io::stdout().flush().ok();
+    if read_char() != 't' {
+        return false;
+    }
+    state.money = crate::save_system::STARTING_MONEY;
+    state.games_won = 0;
+    state.games_lost = 0;
+    // Save the reset state
+    state.save_to_disk();
+    // Don't reset history - keep the game history across restarts
+    true
+}
 
-        let player_busted = !player_turn(state);
-        if player_busted {
-            // Player busted, record the result without dealer turn
-            determine_winner(state);
+/// Deal a new round and take the player's bet. Returns `false` if the
+/// player went back to the main menu instead.
+pub fn start_round(state: &mut GameState) -> bool {
+    setup_new_round(state);
+    print_game_status(state);
+    state.current_round_start_money = state.money;
+    let hands = state.rules.variant.hands_per_round();
+    state.bet = get_bet(state, hands);
+    if state.bet == -1 {
+        return false;
+    }
+    state.money -= state.bet * hands;
+    state.free_bet = 0;
+    state.free_double_bet = 0;
+    state.was_double_down = false;
+    state.was_split = false;
+    state.was_switched = false;
+    state.surrendered = false;
+    state.split_hands.clear();
+    state.finished_hands.clear();
+    state.side_bets.clear();
+    state.actions.clear();
+    state.round_phase = RoundPhase::SideBets;
+    state.save_round();
+    true
+}
+
+/// Play the round in `state` from its current phase until it is settled.
+/// The round is saved after every decision so it can be resumed.
+pub fn play_current_round(state: &mut GameState) {
+    if state.round_phase == RoundPhase::SideBets {
+        place_side_bets(state);
+        state.round_phase = if state.rules.variant == GameVariant::BlackjackSwitch {
+            RoundPhase::Switch
         } else {
+            RoundPhase::Playing(0)
+        };
+        state.save_round();
+    }
+
+    if state.rules.variant == GameVariant::BlackjackSwitch {
+        play_switch_round(state);
+    } else {
+        play_round(state);
+    }
+
+    if state.round_phase == RoundPhase::Dealer {
+        let player_alive = !state.surrendered
+            && state
+                .finished_hands
+                .iter()
+                .any(|hand| hand_value(&hand.cards) <= 21);
+        if player_alive || has_pending_side_bets(state, SideBetTiming::AfterDealerTurn) {
             // Player didn't bust, continue with dealer turn
             enemy_ai_handler::dealer_turn(state);
-            determine_winner(state);
         }
+        state.round_phase = RoundPhase::Settling(0);
+        state.save_round();
+    }
 
-        // Check if player ran out of money after this round
-        if state.money <= 0 {
-            println!("\x1b[1;31m{}\x1b[0m", get_message("Game Over", None));
-            print!("{} ", get_message("Do you want to (t)ry again", None));
-            io::stdout().flush().ok();
-            let c = read_char();
-            if c == 't' {
-                state.money = crate::save_system::STARTING_MONEY;
-                state.games_won = 0;
-                state.games_lost = 0;
-                // Save the reset state
-                state.save_to_disk();
-                // Don't reset history - keep the game history across restarts
-                continue;
-            }
-            break;
+    settle_hands(state);
+    state.finished_hands.clear();
+    state.finish_round();
+}
+
+fn play_round(state: &mut GameState) {
+    if state.round_phase != RoundPhase::Playing(0) {
+        return;
+    }
+    print_initial_game_state(state);
+    resolve_side_bets(state, SideBetTiming::AfterDeal);
+
+    if state.rules.variant == GameVariant::Pontoon {
+        pontoon_turn(state);
+    } else {
+        player_turn(state);
+    }
+    let hands = take_played_hands(state);
+    state.finished_hands.extend(hands);
+    state.round_phase = RoundPhase::Dealer;
+    state.save_round();
+}
+
+/// Blackjack Switch: after the switch, the two hands are played one after
+/// the other with the same bet on each.
+#[allow(clippy::cast_possible_truncation, clippy::cast_possible_wrap)]
+fn play_switch_round(state: &mut GameState) {
+    if state.round_phase == RoundPhase::Switch {
+        resolve_side_bets(state, SideBetTiming::AfterDeal);
+        switch_phase(state);
+        state.round_phase = RoundPhase::Playing(0);
+        state.save_round();
+    }
+
+    while let RoundPhase::Playing(hand) = state.round_phase {
+        println!("\n--- Playing Hand {} ---", hand + 1);
+        print_initial_game_state(state);
+        player_turn(state);
+        let played = take_played_hands(state);
+        // Doubling is the only way to change the bet on a Switch hand
+        let bet_per_hand = if played[0].was_double_down {
+            played[0].bet / 2
+        } else {
+            played[0].bet
+        };
+        state.finished_hands.extend(played);
+
+        if hand == 0 {
+            state.player_cards = std::mem::take(&mut state.second_hand);
+            state.player_card_count = state.player_cards.len() as i32;
+            state.bet = bet_per_hand;
+            state.free_bet = 0;
+            state.free_double_bet = 0;
+            state.was_double_down = false;
+            state.round_phase = RoundPhase::Playing(1);
+        } else {
+            state.round_phase = RoundPhase::Dealer;
         }
+        state.save_round();
+    }
+}
+
+/// The hands the player finished with: both halves of a split, otherwise
+/// the hand currently in play.
+pub fn take_played_hands(state: &mut GameState) -> Vec<PlayedHand> {
+    if state.split_hands.is_empty() {
+        vec![PlayedHand::current(state)]
+    } else {
+        std::mem::take(&mut state.split_hands)
     }
 }
 
+/// Settle the finished hands the round has not settled yet, one by one
+fn settle_hands(state: &mut GameState) {
+    while settle_next_hand(state) {}
+}
+
+/// Settle the next unsettled finished hand against the dealer and record
+/// it in the history. Side bets settle with the first hand, so its money
+/// change covers everything except the other hands' own stakes and
+/// payouts. The round is saved once the hand is recorded, so a resumed
+/// round never settles a hand twice. Returns `false` if every hand is
+/// already settled.
+#[allow(clippy::cast_possible_truncation, clippy::cast_possible_wrap)]
+fn settle_next_hand(state: &mut GameState) -> bool {
+    let RoundPhase::Settling(i) = state.round_phase else {
+        return false;
+    };
+    let Some(hand) = state.finished_hands.get(i).cloned() else {
+        return false;
+    };
+    if state.finished_hands.len() > 1 {
+        println!("\n--- Settling Hand {} ---", i + 1);
+    }
+    state.unsettled_stakes = state.finished_hands[i + 1..]
+        .iter()
+        .map(PlayedHand::money_staked)
+        .sum();
+    state.player_cards = hand.cards;
+    state.player_card_count = state.player_cards.len() as i32;
+    state.bet = hand.bet;
+    state.free_bet = hand.free_bet;
+    state.free_double_bet = hand.free_double_bet;
+    state.was_double_down = hand.was_double_down;
+    state.was_split = hand.was_split;
+    state.was_switched = hand.was_switched;
+    determine_winner(state);
+    state.current_round_start_money = state.money + state.unsettled_stakes;
+    state.round_phase = RoundPhase::Settling(i + 1);
+    state.save_round();
+    true
+}
+
 fn print_splash_screen() {
     println!("{}", get_splash_screen());
     delay();
@@ -88,7 +280,10 @@ fn delay() {
 
 #[allow(clippy::cast_possible_truncation, clippy::cast_possible_wrap)]
 fn setup_new_round(state: &mut GameState) {
-    state.card_deck = create_and_shuffle_deck();
+    state.deck_top = state.stacked_rounds.pop_front().unwrap_or_default();
+    state.deck_seed = thread_rng().gen();
+    state.card_deck =
+        create_stacked_deck_for(state.rules.variant, &state.deck_top, state.deck_seed);
     state.player_cards.clear();
     state.dealer_cards.clear();
     state.player_cards.push(state.card_deck[0].clone());
@@ -96,13 +291,26 @@ fn setup_new_round(state: &mut GameState) {
     state.dealer_cards.push(state.card_deck[2].clone());
     state.dealer_cards.push(state.card_deck[3].clone());
     state.deck_index = 4;
+    state.second_hand.clear();
+    if state.rules.variant == GameVariant::BlackjackSwitch {
+        state.second_hand.push(state.card_deck[4].clone());
+        state.second_hand.push(state.card_deck[5].clone());
+        state.deck_index = 6;
+    }
     state.player_card_count = state.player_cards.len() as i32;
     state.dealer_card_count = state.dealer_cards.len() as i32;
 }
 
-fn get_bet(state: &GameState) -> i32 {
+/// Ask for the bet placed on each of the round's `hands`; every hand carries
+/// the same bet, so the total stake is `hands` times the returned amount.
+fn get_bet(state: &GameState, hands: i32) -> i32 {
+    let max_bet = state.money / hands;
     loop {
-        print!("How many coins do you want to bet? (m to return to main menu): ");
+        if hands == 1 {
+            print!("How many coins do you want to bet? (m to return to main menu): ");
+        } else {
+            print!("How many coins do you want to bet on each of your {hands} hands? (m to return to main menu): ");
+        }
         io::stdout().flush().ok();
         let mut line = String::new();
         io::stdin().read_line(&mut line).ok();
@@ -113,17 +321,123 @@ fn get_bet(state: &GameState) -> i32 {
         }
 
         if let Ok(n) = trimmed.parse::<i32>() {
-            if n > 0 && n <= state.money {
+            if n > 0 && n <= max_bet {
                 return n;
             }
         }
-        println!("Please bet between 1 and {} coins.", state.money);
+        if max_bet < 1 {
+            println!(
+                "You need at least {hands} coins to play {hands} hands. (m to return to main menu)"
+            );
+        } else {
+            println!("Please bet between 1 and {max_bet} coins.");
+        }
+    }
+}
+
+fn place_side_bets(state: &mut GameState) {
+    for kind in SideBetKind::ALL {
+        if !state.rules.is_side_bet_enabled(kind) {
+            continue;
+        }
+        if state.money <= 0 {
+            break;
+        }
+        loop {
+            print!(
+                "Side bet on {kind}? (0 to skip, up to {} coins): ",
+                state.money
+            );
+            io::stdout().flush().ok();
+            let mut line = String::new();
+            io::stdin().read_line(&mut line).ok();
+
+            match line.trim().parse::<i32>() {
+                Ok(0) => break,
+                Ok(n) if n > 0 && n <= state.money => {
+                    state.money -= n;
+                    state.side_bets.push(PlacedSideBet { kind, amount: n });
+                    break;
+                }
+                _ => println!("Please bet between 0 and {} coins.", state.money),
+            }
+        }
     }
 }
 
+fn has_pending_side_bets(state: &GameState, timing: SideBetTiming) -> bool {
+    state
+        .side_bets
+        .iter()
+        .any(|bet| bet.kind.side_bet().timing() == timing)
+}
+
 #[must_use]
 pub fn read_char() -> char {
     let mut line = String::new();
     io::stdin().read_line(&mut line).ok();
     line.trim().chars().next().unwrap_or('\n')
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::round_recovery::RoundSnapshot;
+
+    fn cards(names: &[&str]) -> Vec<String> {
+        names.iter().map(ToString::to_string).collect()
+    }
+
+    fn split_hand(names: &[&str]) -> PlayedHand {
+        PlayedHand {
+            cards: cards(names),
+            bet: 10,
+            free_bet: 0,
+            free_double_bet: 0,
+            was_double_down: false,
+            was_split: true,
+            was_switched: false,
+        }
+    }
+
+    /// A split round of 10 coins a hand from a 100 coin bankroll, played out
+    /// and waiting to be settled: the first hand wins, the second pushes
+    fn split_round_to_settle() -> GameState {
+        let mut state = GameState::with_money(80);
+        state.current_round_start_money = 100;
+        state.dealer_cards = cards(&["10 Spades", "8 Clubs"]);
+        state.finished_hands = vec![
+            split_hand(&["10 Hearts", "9 Clubs"]),
+            split_hand(&["10 Clubs", "8 Hearts"]),
+        ];
+        state.round_phase = RoundPhase::Settling(0);
+        state
+    }
+
+    #[test]
+    fn resumed_settlement_skips_hands_already_recorded() {
+        let mut whole = split_round_to_settle();
+        play_current_round(&mut whole);
+        assert_eq!(whole.money, 110);
+        assert_eq!(whole.history.rounds.len(), 2);
+
+        let mut interrupted = split_round_to_settle();
+        assert!(settle_next_hand(&mut interrupted));
+        let snapshot = RoundSnapshot::capture(&interrupted);
+        assert_eq!(snapshot.phase, RoundPhase::Settling(1));
+        // Cancelling now would hand back only the unsettled hand's stake
+        assert_eq!(snapshot.stake(), 10);
+
+        let mut resumed = GameState::with_money(0);
+        snapshot.restore(&mut resumed);
+        play_current_round(&mut resumed);
+        assert_eq!(resumed.money, whole.money);
+        assert!(!resumed.has_round_in_progress());
+        assert_eq!(resumed.history.rounds.len(), 1);
+        let (resumed_round, whole_round) = (&resumed.history.rounds[0], &whole.history.rounds[1]);
+        assert_eq!(resumed_round.player_cards, whole_round.player_cards);
+        assert_eq!(resumed_round.outcome, whole_round.outcome);
+        assert_eq!(resumed_round.money_change, whole_round.money_change);
+        assert_eq!(resumed_round.money_after, whole_round.money_after);
+    }
+}
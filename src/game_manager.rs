@@ -1,27 +1,70 @@
+use crate::achievements::show_achievements;
+use crate::daily_challenge_menu::show_daily_challenge_menu;
+use crate::duplicate::show_duplicate_mode;
 use crate::game_history::GameHistory;
-use crate::game_loop::start_blackjack_with_state;
+use crate::game_loop::{print_save_error, start_blackjack_with_state};
 use crate::game_state::GameState;
+use crate::history_log;
 use crate::history_menu::show_history_menu;
+use crate::leaderboard::show_leaderboard;
 use crate::menu_handling::MenuAction;
+use crate::profile_menu::choose_profile;
+use crate::profiles::{self, Profile};
+use crate::round_recovery::{self, RoundSnapshot};
+use crate::rules_menu::show_rules_menu;
+use crate::safe_file::{self, FileLock};
+use crate::save_system;
+use crate::scenario_menu::show_scenario_menu;
+use crate::sessions::{self, Session};
+use crate::table_rules::TableRules;
 use crate::text_handler;
+use crate::tournament_menu::show_tournament_menu;
+use std::path::PathBuf;
 
 pub struct GameManager {
     pub game_state: Option<GameState>,
     pub history: GameHistory,
+    pub rules: TableRules,
+    pub profile: Profile,
+    /// Held while the profile is open so a second running game can't write to it
+    profile_lock: Option<FileLock>,
+    /// Session of the open profile
+    session: Option<Session>,
 }
 
 impl GameManager {
+    /// Create a manager for the profile used last time
     #[must_use]
     pub fn new() -> Self {
-        // Try to load existing game history from CSV file
-        let history = GameHistory::load_from_csv("stats.csv").unwrap_or_else(|e| {
-            eprintln!("Warning: Could not load game history: {e}");
-            GameHistory::new()
+        let profile = profiles::startup_profile().unwrap_or_else(|e| {
+            eprintln!("⚠ Warning: Could not open the profiles directory ({e}). Saving in the current directory.");
+            Profile {
+                name: "default".to_string(),
+                dir: PathBuf::from("."),
+            }
         });
+        Self::for_profile(profile)
+    }
+
+    /// Create a manager holding the history and settings of `profile`
+    #[must_use]
+    pub fn for_profile(profile: Profile) -> Self {
+        let history = history_log::load_history(&profile);
+        let rules = match save_system::read_save_data(&profile.save_path()) {
+            Ok(save_data) => save_data.map(|data| data.settings).unwrap_or_default(),
+            Err(e) => {
+                eprintln!("⚠ Warning: Could not load your settings: {e}");
+                TableRules::new()
+            }
+        };
 
         GameManager {
             game_state: None,
             history,
+            rules,
+            profile,
+            profile_lock: None,
+            session: None,
         }
     }
 
@@ -33,6 +76,9 @@ impl GameManager {
     pub fn run(&mut self) -> Result<(), Box<dyn std::error::Error>> {
         let show_splash = text_handler::ShowSplash;
         show_splash.doit();
+        if !self.switch_profile() {
+            return Ok(());
+        }
 
         loop {
             text_handler::print_menu();
@@ -53,12 +99,49 @@ impl GameManager {
                     help.show_instructions();
                 }
                 MenuAction::NewGame => {
-                    self.start_new_game();
+                    self.start_game(None);
                 }
                 MenuAction::History => {
-                    show_history_menu(&self.history);
+                    let sessions: Vec<Session> =
+                        safe_file::read_json(&self.profile.sessions_path())
+                            .unwrap_or_else(|e| {
+                                eprintln!("⚠ Warning: Could not read your sessions: {e}");
+                                None
+                            })
+                            .unwrap_or_default();
+                    show_history_menu(&self.history, &sessions);
+                }
+                MenuAction::Rules => {
+                    show_rules_menu(&mut self.rules);
+                    self.save_settings();
+                }
+                MenuAction::Tournament => {
+                    show_tournament_menu(&self.profile);
+                }
+                MenuAction::Duplicate => {
+                    show_duplicate_mode();
+                }
+                MenuAction::DailyChallenge => {
+                    show_daily_challenge_menu(&self.profile);
+                }
+                MenuAction::Scenarios => {
+                    show_scenario_menu();
+                }
+                MenuAction::Profile => {
+                    if !self.switch_profile() {
+                        break;
+                    }
+                }
+                MenuAction::Achievements => {
+                    show_achievements(&self.profile);
+                }
+                MenuAction::Leaderboard => {
+                    show_leaderboard(&self.profile, &self.history.rounds);
                 }
                 MenuAction::Quit => {
+                    if let Some(session) = self.end_session() {
+                        println!("\n{}", session.summary_card());
+                    }
                     println!("Thanks for playing! Goodbye!");
                     break;
                 }
@@ -67,29 +150,51 @@ impl GameManager {
                 }
             }
         }
+        self.end_session();
         Ok(())
     }
 
-    fn start_new_game(&mut self) {
+    /// Play at the table, carrying on with `resume` first if it is given
+    fn start_game(&mut self, resume: Option<RoundSnapshot>) {
         // Create or reset the game state, but preserve history
         let mut state = if let Some(mut existing_state) = self.game_state.take() {
             // Reset game state but keep the history
             existing_state.player_cards.clear();
+            existing_state.second_hand.clear();
             existing_state.dealer_cards.clear();
             existing_state.bet = 0;
+            existing_state.free_bet = 0;
+            existing_state.free_double_bet = 0;
             existing_state.player_card_count = 0;
             existing_state.dealer_card_count = 0;
             existing_state.deck_index = 0;
             existing_state.was_double_down = false;
+            existing_state.surrendered = false;
+            existing_state.was_switched = false;
+            existing_state.split_hands.clear();
+            existing_state.side_bets.clear();
+            existing_state.actions.clear();
             existing_state.history = self.history.clone();
             existing_state
         } else {
-            let mut new_state = GameState::new();
+            let mut new_state = match GameState::try_new(&self.profile) {
+                Ok(state) => state,
+                Err(e) => {
+                    print_save_error(&e);
+                    return;
+                }
+            };
             new_state.history = self.history.clone();
             new_state
         };
 
+        state.rules = self.rules.clone();
+        state.session = self.session.take();
+        if let Some(snapshot) = resume {
+            snapshot.restore(&mut state);
+        }
         start_blackjack_with_state(&mut state);
+        self.session = state.session.take();
         self.history = state.history.clone();
         self.game_state = Some(state);
 
@@ -99,6 +204,60 @@ impl GameManager {
         }
     }
 
+    /// Let the player choose a profile, lock it and load its history and
+    /// settings, then offer to resume a round the profile left unfinished.
+    /// Returns `false` if the player gave up because every profile
+    /// they tried is open in another running game.
+    fn switch_profile(&mut self) -> bool {
+        // Release the current profile so it can be renamed or deleted
+        self.end_session();
+        self.profile_lock = None;
+        loop {
+            let profile = choose_profile(self.profile.clone());
+            match profile.lock() {
+                Ok(lock) => {
+                    *self = Self::for_profile(profile);
+                    self.profile_lock = Some(lock);
+                    let session = sessions::begin_session(&self.profile);
+                    self.history.session_start = session.start;
+                    self.session = Some(session);
+                    if let Some(snapshot) = round_recovery::offer_resume(&self.profile) {
+                        self.start_game(Some(snapshot));
+                    }
+                    return true;
+                }
+                Err(e) => {
+                    println!("✗ {e}");
+                    print!("(t)ry another profile or (q)uit: ");
+                    std::io::Write::flush(&mut std::io::stdout()).ok();
+                    let answer = text_handler::read_menu_input().unwrap_or_default();
+                    if !answer.eq_ignore_ascii_case("t") {
+                        return false;
+                    }
+                    self.profile = profile;
+                }
+            }
+        }
+    }
+
+    /// Close the open session with the current bankroll and store it.
+    /// Returns the closed session, or `None` if none was open.
+    fn end_session(&mut self) -> Option<Session> {
+        let mut session = self.session.take()?;
+        let money = self
+            .game_state
+            .as_ref()
+            .map_or(session.ending_money, |state| state.money);
+        sessions::end_session(&self.profile, &mut session, money);
+        Some(session)
+    }
+
+    fn save_settings(&self) {
+        if let Err(e) = save_system::save_settings(&self.rules, &self.profile.save_path()) {
+            eprintln!("⚠ Warning: Could not save your table rules: {e}");
+        }
+    }
+
     #[must_use]
     pub fn get_history(&self) -> &GameHistory {
         &self.history
@@ -1,11 +1,75 @@
+use crate::basic_strategy::StrategyAction;
 use crate::game_history::GameHistory;
+use crate::profiles::Profile;
+use crate::round_recovery::{self, RoundPhase, RoundSnapshot};
+use crate::save_system::SaveError;
+use crate::sessions::Session;
+use crate::side_bets::PlacedSideBet;
+use crate::table_rules::TableRules;
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
 
+/// A finished player hand waiting to be settled against the dealer. Rounds
+/// with several hands (splits, Blackjack Switch) settle them one at a time.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PlayedHand {
+    pub cards: Vec<String>,
+    pub bet: i32,
+    pub free_bet: i32,
+    /// Part of `free_bet` from a free double down; the rest is a free split
+    #[serde(default)]
+    pub free_double_bet: i32,
+    pub was_double_down: bool,
+    #[serde(default)]
+    pub was_split: bool,
+    /// Whether the hand's second card came from the other hand of a
+    /// Blackjack Switch round, so a two-card 21 is not a blackjack
+    #[serde(default)]
+    pub was_switched: bool,
+}
+
+impl PlayedHand {
+    /// Snapshot the hand currently being played in `state`
+    #[must_use]
+    pub fn current(state: &GameState) -> Self {
+        PlayedHand {
+            cards: state.player_cards.clone(),
+            bet: state.bet,
+            free_bet: state.free_bet,
+            free_double_bet: state.free_double_bet,
+            was_double_down: state.was_double_down,
+            was_split: state.was_split,
+            was_switched: state.was_switched,
+        }
+    }
+
+    /// The part of the wager paid for with real money
+    #[must_use]
+    pub fn money_staked(&self) -> i32 {
+        self.bet - self.free_bet
+    }
+}
+
+#[allow(clippy::struct_excessive_bools)]
 pub struct GameState {
     pub card_deck: Vec<String>,
+    /// Cards the current deck was stacked with, dealt before the shuffled
+    /// rest
+    pub deck_top: Vec<String>,
+    /// Seed the rest of the current deck was shuffled from
+    pub deck_seed: u64,
+    /// Card orders the coming rounds are dealt from, one per round, before
+    /// going back to shuffled decks
+    pub stacked_rounds: VecDeque<Vec<String>>,
     pub player_cards: Vec<String>,
+    pub second_hand: Vec<String>,
     pub dealer_cards: Vec<String>,
     pub money: i32,
     pub bet: i32,
+    /// Part of `bet` staked for free at a Free Bet table
+    pub free_bet: i32,
+    /// Part of `free_bet` from a free double down
+    pub free_double_bet: i32,
     pub games_won: i32,
     pub games_lost: i32,
     pub player_card_count: i32,
@@ -13,7 +77,29 @@ pub struct GameState {
     pub deck_index: i32,
     pub history: GameHistory,
     pub current_round_start_money: i32,
+    /// Real money staked on hands of this round that are not settled yet
+    pub unsettled_stakes: i32,
     pub was_double_down: bool,
+    /// Whether the hand being settled is one half of a split
+    pub was_split: bool,
+    /// Whether the second cards of a Blackjack Switch round were swapped
+    pub was_switched: bool,
+    pub surrendered: bool,
+    pub split_hands: Vec<PlayedHand>,
+    /// Index into `split_hands` of the hand being played after a split
+    pub active_split_hand: usize,
+    /// Hands already played this round, waiting for the dealer
+    pub finished_hands: Vec<PlayedHand>,
+    /// How far the current round has got
+    pub round_phase: RoundPhase,
+    pub rules: TableRules,
+    pub side_bets: Vec<PlacedSideBet>,
+    /// Decisions the player made this round, in order
+    pub actions: Vec<StrategyAction>,
+    /// Profile the state saves to; `None` for tables that never touch disk
+    pub profile: Option<Profile>,
+    /// Session each finished hand is counted in
+    pub session: Option<Session>,
 }
 
 impl Default for GameState {
@@ -23,31 +109,108 @@ impl Default for GameState {
 }
 
 impl GameState {
+    /// A state with the starting bankroll that is never saved
     #[must_use]
     pub fn new() -> Self {
-        let save_data = crate::save_system::load_save_data();
+        Self::with_money(crate::save_system::STARTING_MONEY)
+    }
 
+    /// Create a state from the save file of `profile`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the save file exists but cannot be loaded.
+    pub fn try_new(profile: &Profile) -> Result<Self, SaveError> {
+        let save_data = crate::save_system::load_save_data(&profile.save_path())?;
+
+        let mut state = Self::with_money(save_data.money);
+        state.games_won = save_data.games_won;
+        state.games_lost = save_data.games_lost;
+        state.rules = save_data.settings;
+        state.profile = Some(profile.clone());
+        Ok(state)
+    }
+
+    /// A fresh state holding `money`, without touching the save file. Used
+    /// for tables whose chips are kept apart from the player's bankroll.
+    #[must_use]
+    pub fn with_money(money: i32) -> Self {
         GameState {
             card_deck: Vec::new(),
+            deck_top: Vec::new(),
+            deck_seed: 0,
+            stacked_rounds: VecDeque::new(),
             player_cards: Vec::new(),
+            second_hand: Vec::new(),
             dealer_cards: Vec::new(),
-            money: save_data.money,
+            money,
             bet: 0,
-            games_won: save_data.games_won,
-            games_lost: save_data.games_lost,
+            free_bet: 0,
+            free_double_bet: 0,
+            games_won: 0,
+            games_lost: 0,
             player_card_count: 0,
             dealer_card_count: 0,
             deck_index: 0,
             history: GameHistory::new(),
-            current_round_start_money: save_data.money,
+            current_round_start_money: money,
+            unsettled_stakes: 0,
             was_double_down: false,
+            was_split: false,
+            was_switched: false,
+            surrendered: false,
+            split_hands: Vec::new(),
+            active_split_hand: 0,
+            finished_hands: Vec::new(),
+            round_phase: RoundPhase::Betting,
+            rules: TableRules::new(),
+            side_bets: Vec::new(),
+            actions: Vec::new(),
+            profile: None,
+            session: None,
         }
     }
 
-    /// Save the current game state to disk
+    /// Save the current game state to the profile's save file
     pub fn save_to_disk(&self) {
-        let save_data =
-            crate::save_system::create_save_data(self.money, self.games_won, self.games_lost);
-        crate::save_system::auto_save(&save_data);
+        let Some(profile) = &self.profile else {
+            return;
+        };
+        let save_data = crate::save_system::create_save_data(
+            self.money,
+            self.games_won,
+            self.games_lost,
+            self.rules.clone(),
+        );
+        crate::save_system::auto_save(&save_data, &profile.save_path());
+    }
+
+    /// Whether a bet is on the table for a round that has not been settled
+    #[must_use]
+    pub fn has_round_in_progress(&self) -> bool {
+        self.round_phase != RoundPhase::Betting
+    }
+
+    /// Write the round in progress to the profile, so it can be resumed if
+    /// the game is closed before it is settled
+    pub fn save_round(&self) {
+        let Some(profile) = &self.profile else {
+            return;
+        };
+        let snapshot = RoundSnapshot::capture(self);
+        if let Err(e) = round_recovery::save_snapshot(&snapshot, &profile.round_path()) {
+            eprintln!("⚠ Warning: Could not save the round in progress: {e}");
+        }
+    }
+
+    /// Mark the round as settled and forget its snapshot
+    pub fn finish_round(&mut self) {
+        self.round_phase = RoundPhase::Betting;
+        let Some(profile) = &self.profile else {
+            return;
+        };
+        if let Err(e) = round_recovery::clear_snapshot(&profile.round_path()) {
+            eprintln!("⚠ Warning: Could not remove the finished round: {e}");
+        }
     }
 }
@@ -0,0 +1,317 @@
+use crate::basic_strategy::upcard_value;
+use crate::game_history_core::{GameHistory, GameOutcome, GameRound};
+use crate::game_history_report::{starting_total_label, upcard_label};
+use crate::player_handler::{hand_value, is_soft_hand};
+use chrono::NaiveDate;
+use std::fmt;
+
+/// Date format used when asking for and showing a date range
+pub const DATE_FORMAT: &str = "%Y-%m-%d";
+
+/// Which outcomes a filter keeps
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OutcomeFilter {
+    Wins,
+    Losses,
+    Ties,
+    Exactly(GameOutcome),
+}
+
+impl OutcomeFilter {
+    #[must_use]
+    pub fn matches(self, outcome: GameOutcome) -> bool {
+        match self {
+            OutcomeFilter::Wins => {
+                matches!(outcome, GameOutcome::PlayerWin | GameOutcome::DealerBust)
+            }
+            OutcomeFilter::Losses => matches!(
+                outcome,
+                GameOutcome::DealerWin | GameOutcome::PlayerBust | GameOutcome::Surrender
+            ),
+            OutcomeFilter::Ties => outcome == GameOutcome::Tie,
+            OutcomeFilter::Exactly(wanted) => outcome == wanted,
+        }
+    }
+
+    /// Parse "win", "loss", "tie" or an outcome name such as "player bust"
+    #[must_use]
+    pub fn parse(input: &str) -> Option<Self> {
+        match input.trim().to_lowercase().as_str() {
+            "w" | "win" | "wins" => Some(OutcomeFilter::Wins),
+            "l" | "loss" | "losses" => Some(OutcomeFilter::Losses),
+            "t" | "tie" | "ties" | "push" => Some(OutcomeFilter::Ties),
+            other => [
+                GameOutcome::PlayerWin,
+                GameOutcome::DealerWin,
+                GameOutcome::PlayerBust,
+                GameOutcome::DealerBust,
+                GameOutcome::Surrender,
+            ]
+            .into_iter()
+            .find(|outcome| outcome.to_string().to_lowercase() == other)
+            .map(OutcomeFilter::Exactly),
+        }
+    }
+}
+
+impl fmt::Display for OutcomeFilter {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            OutcomeFilter::Wins => write!(f, "Wins"),
+            OutcomeFilter::Losses => write!(f, "Losses"),
+            OutcomeFilter::Ties => write!(f, "Ties"),
+            OutcomeFilter::Exactly(outcome) => write!(f, "{outcome}"),
+        }
+    }
+}
+
+/// The player's first two cards, as a hard or soft total or a pair
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum StartingHand {
+    Hard(i32),
+    Soft(i32),
+    /// Two cards of the given rank, such as "8" or "A"
+    Pair(String),
+}
+
+impl StartingHand {
+    #[must_use]
+    pub fn matches(&self, cards: &[String]) -> bool {
+        let Some(start) = cards.get(..2) else {
+            return false;
+        };
+        match self {
+            StartingHand::Hard(total) => !is_soft_hand(start) && hand_value(start) == *total,
+            StartingHand::Soft(total) => is_soft_hand(start) && hand_value(start) == *total,
+            StartingHand::Pair(rank) => start
+                .iter()
+                .all(|card| card.split_whitespace().next() == Some(rank.as_str())),
+        }
+    }
+
+    /// Parse a hard total ("16"), a soft total ("s18") or a pair ("p8", "pA")
+    #[must_use]
+    pub fn parse(input: &str) -> Option<Self> {
+        let input = input.trim().to_uppercase();
+        if let Some(rank) = input.strip_prefix('P') {
+            let rank = rank.trim();
+            let valid = matches!(rank, "A" | "K" | "Q" | "J")
+                || rank.parse::<i32>().is_ok_and(|n| (2..=10).contains(&n));
+            return valid.then(|| StartingHand::Pair(rank.to_string()));
+        }
+        let (soft, total) = match input.strip_prefix('S') {
+            Some(total) => (true, total),
+            None => (false, input.strip_prefix('H').unwrap_or(&input)),
+        };
+        let total = total.trim().parse::<i32>().ok()?;
+        match (soft, total) {
+            (true, 12..=21) => Some(StartingHand::Soft(total)),
+            (false, 4..=21) => Some(StartingHand::Hard(total)),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for StartingHand {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            StartingHand::Hard(total) => write!(f, "{}", starting_total_label(*total, false)),
+            StartingHand::Soft(total) => write!(f, "{}", starting_total_label(*total, true)),
+            StartingHand::Pair(rank) => write!(f, "Pair of {rank}s"),
+        }
+    }
+}
+
+/// What the games of a filtered history are ordered by
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SortKey {
+    #[default]
+    Game,
+    Bet,
+    MoneyChange,
+}
+
+impl fmt::Display for SortKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SortKey::Game => write!(f, "Game number"),
+            SortKey::Bet => write!(f, "Bet"),
+            SortKey::MoneyChange => write!(f, "Money change"),
+        }
+    }
+}
+
+/// Conditions a game must meet to be shown, and the order to show them in.
+/// Conditions left as `None` match every game.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct HistoryFilter {
+    pub outcome: Option<OutcomeFilter>,
+    /// First day to include
+    pub from: Option<NaiveDate>,
+    /// Last day to include
+    pub to: Option<NaiveDate>,
+    pub min_bet: Option<i32>,
+    pub max_bet: Option<i32>,
+    pub double_down: Option<bool>,
+    pub split: Option<bool>,
+    /// Dealer upcard value, 2 through 11 (ace)
+    pub upcard: Option<i32>,
+    pub starting_hand: Option<StartingHand>,
+    pub sort_by: SortKey,
+    pub descending: bool,
+}
+
+impl HistoryFilter {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Whether any condition is set, so that some games may be left out
+    #[must_use]
+    pub fn is_active(&self) -> bool {
+        let unsorted = HistoryFilter {
+            sort_by: SortKey::Game,
+            descending: false,
+            ..self.clone()
+        };
+        unsorted != HistoryFilter::new()
+    }
+
+    #[must_use]
+    pub fn matches(&self, round: &GameRound) -> bool {
+        let day = round.timestamp.date_naive();
+        self.outcome.is_none_or(|o| o.matches(round.outcome))
+            && self.from.is_none_or(|from| day >= from)
+            && self.to.is_none_or(|to| day <= to)
+            && self.min_bet.is_none_or(|min| round.bet_amount >= min)
+            && self.max_bet.is_none_or(|max| round.bet_amount <= max)
+            && self.double_down.is_none_or(|d| round.was_double_down == d)
+            && self.split.is_none_or(|s| round.was_split == s)
+            && self.upcard.is_none_or(|upcard| {
+                round
+                    .dealer_cards
+                    .first()
+                    .is_some_and(|card| upcard_value(card) == upcard)
+            })
+            && self
+                .starting_hand
+                .as_ref()
+                .is_none_or(|hand| hand.matches(&round.player_cards))
+    }
+
+    /// Positions in `history.rounds` of the games that match, in the
+    /// filter's sort order. Games that compare equal stay in play order.
+    #[must_use]
+    pub fn apply(&self, history: &GameHistory) -> Vec<usize> {
+        let mut indices: Vec<usize> = history
+            .rounds
+            .iter()
+            .enumerate()
+            .filter(|(_, round)| self.matches(round))
+            .map(|(i, _)| i)
+            .collect();
+
+        let key = |i: usize| {
+            let round = &history.rounds[i];
+            match self.sort_by {
+                SortKey::Game => i64::try_from(i).unwrap_or(i64::MAX),
+                SortKey::Bet => i64::from(round.bet_amount),
+                SortKey::MoneyChange => i64::from(round.money_change),
+            }
+        };
+        indices.sort_by(|&a, &b| {
+            let order = key(a).cmp(&key(b));
+            if self.descending {
+                order.reverse().then(a.cmp(&b))
+            } else {
+                order.then(a.cmp(&b))
+            }
+        });
+        indices
+    }
+
+    /// One line per condition that is set, for showing the filter
+    #[must_use]
+    pub fn describe(&self) -> Vec<String> {
+        let mut lines = Vec::new();
+        if let Some(outcome) = self.outcome {
+            lines.push(format!("Outcome: {outcome}"));
+        }
+        match (self.from, self.to) {
+            (None, None) => {}
+            (from, to) => lines.push(format!(
+                "Dates: {} to {}",
+                from.map_or("any".to_string(), |d| d.format(DATE_FORMAT).to_string()),
+                to.map_or("any".to_string(), |d| d.format(DATE_FORMAT).to_string())
+            )),
+        }
+        match (self.min_bet, self.max_bet) {
+            (None, None) => {}
+            (min, max) => lines.push(format!(
+                "Bet: {} to {}",
+                min.map_or("any".to_string(), |b| b.to_string()),
+                max.map_or("any".to_string(), |b| b.to_string())
+            )),
+        }
+        if let Some(double_down) = self.double_down {
+            lines.push(format!("Doubled down: {}", yes_no(double_down)));
+        }
+        if let Some(split) = self.split {
+            lines.push(format!("Split hand: {}", yes_no(split)));
+        }
+        if let Some(upcard) = self.upcard {
+            lines.push(format!("Dealer upcard: {}", upcard_label(upcard)));
+        }
+        if let Some(hand) = &self.starting_hand {
+            lines.push(format!("Starting hand: {hand}"));
+        }
+        if self.sort_by != SortKey::Game || self.descending {
+            lines.push(format!(
+                "Sorted by: {}, {}",
+                self.sort_by,
+                if self.descending {
+                    "highest first"
+                } else {
+                    "lowest first"
+                }
+            ));
+        }
+        lines
+    }
+}
+
+fn yes_no(value: bool) -> &'static str {
+    if value {
+        "Yes"
+    } else {
+        "No"
+    }
+}
+
+/// Parse a dealer upcard: 2 through 10, a face card or an ace
+#[must_use]
+pub fn parse_upcard(input: &str) -> Option<i32> {
+    let value = upcard_value(&input.trim().to_uppercase());
+    (2..=11).contains(&value).then_some(value)
+}
+
+impl GameHistory {
+    /// A history of just the games at `indices`, in play order, with its
+    /// totals counted from those games
+    #[must_use]
+    pub fn subset(&self, indices: &[usize]) -> GameHistory {
+        let mut indices = indices.to_vec();
+        indices.sort_unstable();
+        let mut subset = GameHistory {
+            session_start: self.session_start,
+            ..GameHistory::new()
+        };
+        for i in indices {
+            if let Some(round) = self.rounds.get(i) {
+                subset.add_round(round.clone());
+            }
+        }
+        subset
+    }
+}
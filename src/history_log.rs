@@ -0,0 +1,194 @@
+use crate::csv_io::RowError;
+use crate::game_history::{GameHistory, GameRound};
+use crate::game_history_export::{HistoryImport, CSV_HEADER};
+use crate::profiles::Profile;
+use crate::safe_file;
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, BufRead, BufReader, Read, Seek, SeekFrom, Write};
+use std::path::Path;
+
+/// The log is compacted every time the history reaches a multiple of this
+/// many rounds
+pub const COMPACT_EVERY: usize = 100;
+
+/// Read the history log at `path`, or `None` if there is no log yet. Rows
+/// that cannot be read, such as one cut short by a crash, are listed in the
+/// import rather than loaded.
+///
+/// # Errors
+///
+/// Returns an error if the file exists but cannot be read.
+pub fn read_log(path: &Path) -> io::Result<Option<HistoryImport>> {
+    let bytes = match fs::read(path) {
+        Ok(bytes) => bytes,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(None),
+        Err(e) => return Err(e),
+    };
+    Ok(Some(GameHistory::parse_csv(&String::from_utf8_lossy(
+        &bytes,
+    ))))
+}
+
+/// Add one round to the end of the log, creating it if needed. Nothing
+/// already in the file is rewritten.
+///
+/// # Errors
+///
+/// Returns an error if the log cannot be opened or written.
+pub fn append_round(path: &Path, round: &GameRound) -> io::Result<()> {
+    let mut file = OpenOptions::new()
+        .create(true)
+        .read(true)
+        .append(true)
+        .open(path)?;
+
+    let mut entry = String::new();
+    if file.metadata()?.len() == 0 {
+        entry.push_str(CSV_HEADER);
+        entry.push('\n');
+    } else if !ends_with_newline(&mut file)? {
+        // Start a fresh line after a write that was cut short
+        entry.push('\n');
+    }
+    entry.push_str(&round.to_csv_row());
+    entry.push('\n');
+
+    file.write_all(entry.as_bytes())?;
+    file.sync_data()
+}
+
+fn ends_with_newline(file: &mut File) -> io::Result<bool> {
+    let mut last = [0];
+    file.seek(SeekFrom::End(-1))?;
+    file.read_exact(&mut last)?;
+    Ok(last[0] == b'\n')
+}
+
+/// Rewrite the log from `history` in one atomic write, dropping damaged
+/// lines and refreshing the rotating backups that appends do not touch.
+///
+/// # Errors
+///
+/// Returns an error if the log cannot be written.
+pub fn compact(path: &Path, history: &GameHistory) -> io::Result<()> {
+    safe_file::write_with_backups(path, &history.export_to_csv())
+}
+
+/// Append `round`, which must already be the last round of `history`, and
+/// compact the log when the history reaches a multiple of `COMPACT_EVERY`.
+///
+/// # Errors
+///
+/// Returns an error if the log cannot be written.
+pub fn record_round(path: &Path, history: &GameHistory, round: &GameRound) -> io::Result<()> {
+    append_round(path, round)?;
+    if history.rounds.len().is_multiple_of(COMPACT_EVERY) {
+        compact(path, history)?;
+    }
+    Ok(())
+}
+
+/// Load the history of `profile`. A `stats.csv` written by older versions
+/// is copied into a new log, a log with damaged rows is reported and
+/// compacted, and a log with an older set of columns is rewritten with the
+/// current ones.
+#[must_use]
+pub fn load_history(profile: &Profile) -> GameHistory {
+    let path = profile.history_log_path();
+    let loaded = match read_log(&path) {
+        Ok(Some(loaded)) => loaded,
+        Ok(None) => return import_stats_csv(profile),
+        Err(e) => {
+            eprintln!("Warning: Could not load game history: {e}");
+            return GameHistory::new();
+        }
+    };
+
+    if !loaded.errors.is_empty() {
+        report_row_errors(&path, &loaded.errors);
+        eprintln!(
+            "  Rewriting the log without them; the previous file is kept as {}.",
+            safe_file::backup_path(&path, 1).display()
+        );
+        if let Err(e) = compact(&path, &loaded.history) {
+            eprintln!("⚠ Warning: Could not compact the game history: {e}");
+        }
+    } else if !has_current_header(&path) {
+        // Rows appended under an old header would lose the newer columns
+        if let Err(e) = compact(&path, &loaded.history) {
+            eprintln!("⚠ Warning: Could not update the game history log: {e}");
+        }
+    }
+    loaded.history
+}
+
+fn has_current_header(path: &Path) -> bool {
+    let mut header = String::new();
+    File::open(path)
+        .and_then(|file| BufReader::new(file).read_line(&mut header))
+        .is_ok_and(|_| header.trim_end() == CSV_HEADER)
+}
+
+/// Start the log from the CSV file that older versions rewrote every round.
+/// A file with rows that cannot be read is replaced by the newest backup
+/// that reads cleanly; without one, the rows that can be read are copied.
+/// The CSV file itself is left in place.
+fn import_stats_csv(profile: &Profile) -> GameHistory {
+    let path = profile.stats_path();
+    let import = match safe_file::read_with_recovery(&path, parse_clean_csv, |e| {
+        e.kind() == io::ErrorKind::InvalidData
+    }) {
+        Ok(import) => import,
+        Err(e) if e.kind() == io::ErrorKind::InvalidData => read_log(&path).unwrap_or_else(|e| {
+            eprintln!("Warning: Could not load game history: {e}");
+            None
+        }),
+        Err(e) => {
+            eprintln!("Warning: Could not load game history: {e}");
+            None
+        }
+    };
+    let Some(HistoryImport { history, errors }) = import else {
+        return GameHistory::new();
+    };
+    if !errors.is_empty() {
+        report_row_errors(&profile.stats_path(), &errors);
+    }
+
+    if let Err(e) = compact(&profile.history_log_path(), &history) {
+        eprintln!("⚠ Warning: Could not create the game history log: {e}");
+    } else {
+        println!(
+            "✓ Copied {} rounds from {} into the history log",
+            history.rounds.len(),
+            profile.stats_path().display()
+        );
+    }
+    history
+}
+
+/// Parse a history CSV file, treating any row that cannot be read as damage
+fn parse_clean_csv(content: &str) -> io::Result<HistoryImport> {
+    let import = GameHistory::parse_csv(content);
+    match import.errors.first() {
+        None => Ok(import),
+        Some(error) => Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!(
+                "{} row(s) could not be read, first {error}",
+                import.errors.len()
+            ),
+        )),
+    }
+}
+
+fn report_row_errors(path: &Path, errors: &[RowError]) {
+    eprintln!(
+        "⚠ {} row(s) of {} could not be read:",
+        errors.len(),
+        path.display()
+    );
+    for error in errors {
+        eprintln!("  {error}");
+    }
+}
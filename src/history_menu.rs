@@ -1,29 +1,60 @@
+use crate::bankroll_chart;
 use crate::formatting::BoxFormatter;
-use crate::game_history::GameHistory;
+use crate::game_history::{GameHistory, GameRound};
+use crate::game_history_report::upcard_label;
+use crate::history_filter::{
+    parse_upcard, HistoryFilter, OutcomeFilter, SortKey, StartingHand, DATE_FORMAT,
+};
+use crate::sessions::{self, Session};
 use crate::text_handler;
+use chrono::NaiveDate;
 use std::io::{self, Write};
 
+/// Games shown per page when browsing
+const PAGE_SIZE: usize = 15;
+
 pub struct HistoryMenu<'a> {
     history: &'a GameHistory,
+    sessions: &'a [Session],
+    filter: HistoryFilter,
+    /// Positions of the games that pass the filter, in its sort order
+    matches: Vec<usize>,
+    /// The games that pass the filter in play order, used for summaries
+    /// and exports
+    view: GameHistory,
 }
 
 impl<'a> HistoryMenu<'a> {
     #[must_use]
-    pub fn new(history: &'a GameHistory) -> Self {
-        HistoryMenu { history }
+    pub fn new(history: &'a GameHistory, sessions: &'a [Session]) -> Self {
+        let filter = HistoryFilter::new();
+        let matches = filter.apply(history);
+        let view = history.subset(&matches);
+        HistoryMenu {
+            history,
+            sessions,
+            filter,
+            matches,
+            view,
+        }
     }
 
-    pub fn show_menu(&self) {
+    pub fn show_menu(&mut self) {
         loop {
-            Self::print_history_menu();
+            self.print_history_menu();
             let Ok(input) = text_handler::read_menu_input() else {
                 break;
             };
 
             match input.trim().to_lowercase().as_str() {
                 "s" => self.show_history_summary(),
-                "r" => self.show_recent_games(),
+                "a" => self.show_advanced_stats(),
+                "l" => self.show_streaks_and_luck(),
+                "c" => self.show_bankroll_chart(),
+                "p" => self.show_sessions(),
+                "r" => self.browse_games(),
                 "d" => self.show_detailed_game(),
+                "f" => self.edit_filter(),
                 "e" => self.export_history(),
                 "b" | "back" => break,
                 _ => {
@@ -33,55 +64,177 @@ impl<'a> HistoryMenu<'a> {
         }
     }
 
-    fn print_history_menu() {
-        let mut formatter = BoxFormatter::new(40, "GAME RECORDS");
+    fn print_history_menu(&self) {
+        let mut formatter = BoxFormatter::new(48, "GAME RECORDS");
         formatter.add_line("(s) Show summary statistics");
-        formatter.add_line("(r) Show recent games");
+        formatter.add_line("(a) Show advanced statistics");
+        formatter.add_line("(l) Show streaks and luck");
+        formatter.add_line("(c) Show bankroll chart");
+        formatter.add_line("(p) Show play sessions");
+        formatter.add_line("(r) Browse games");
         formatter.add_line("(d) Show detailed game");
-        formatter.add_line("(e) Export to CSV");
+        formatter.add_line("(f) Filter and sort games");
+        formatter.add_line("(e) Export data or a report");
         formatter.add_line("(b) Back to main menu");
+        if self.filter.is_active() {
+            formatter.add_separator();
+            formatter.add_line(&format!(
+                "Showing {} of {} games",
+                self.matches.len(),
+                self.history.rounds.len()
+            ));
+            for line in self.filter.describe() {
+                formatter.add_line(&line);
+            }
+        }
 
         println!("\n{}", formatter.build());
         print!("Choose an option: ");
         io::stdout().flush().ok();
     }
 
-    fn show_history_summary(&self) {
+    /// Say why there is nothing to show, if the history or the filtered
+    /// part of it is empty
+    fn report_no_games(&self) -> bool {
         if self.history.rounds.is_empty() {
             println!("No game history available. Play some games first!");
+            true
+        } else if self.matches.is_empty() {
+            println!("No games match the current filter.");
+            true
         } else {
-            self.history.display_summary();
+            false
+        }
+    }
+
+    fn show_history_summary(&self) {
+        if !self.report_no_games() {
+            self.view.display_summary();
         }
         Self::wait_for_enter();
     }
 
-    fn show_recent_games(&self) {
-        if self.history.rounds.is_empty() {
-            println!("No game history available. Play some games first!");
-        } else {
-            print!("How many recent games to show (default 5): ");
-            io::stdout().flush().ok();
+    fn show_advanced_stats(&self) {
+        if !self.report_no_games() {
+            self.view.display_advanced_stats();
+        }
+        Self::wait_for_enter();
+    }
 
-            let mut input = String::new();
-            io::stdin().read_line(&mut input).ok();
+    fn show_streaks_and_luck(&self) {
+        if !self.report_no_games() {
+            self.view.display_streaks();
+            self.view.display_luck();
+        }
+        Self::wait_for_enter();
+    }
 
-            let count = input.trim().parse::<usize>().unwrap_or(5);
-            self.history.display_recent_games(count);
+    fn show_bankroll_chart(&self) {
+        if self.report_no_games() {
+            Self::wait_for_enter();
+            return;
+        }
+
+        let rounds = &self.view.rounds;
+        let (title, shown) = match prompt(
+            "Chart (a)ll shown games, the (l)ast N hands or a (d)ate range? (default all): ",
+        )
+        .to_lowercase()
+        .as_str()
+        {
+            "l" => {
+                let count = prompt("How many hands (default 50): ")
+                    .parse::<usize>()
+                    .unwrap_or(50)
+                    .clamp(1, rounds.len());
+                (
+                    format!("BANKROLL OVER THE LAST {count} HANDS"),
+                    &rounds[rounds.len() - count..],
+                )
+            }
+            "d" => {
+                let parse_date = |input: &str| NaiveDate::parse_from_str(input, DATE_FORMAT).ok();
+                let from = parse_date(&prompt(
+                    "From date (YYYY-MM-DD, blank for the first game): ",
+                ));
+                let to = parse_date(&prompt("To date (YYYY-MM-DD, blank for the last game): "));
+                let in_range = |round: &GameRound| {
+                    let day = round.timestamp.date_naive();
+                    from.is_none_or(|from| day >= from) && to.is_none_or(|to| day <= to)
+                };
+                let start = rounds.iter().position(in_range).unwrap_or(rounds.len());
+                let end = rounds.iter().rposition(in_range).map_or(start, |i| i + 1);
+                (
+                    "BANKROLL FOR THE CHOSEN DATES".to_string(),
+                    &rounds[start..end],
+                )
+            }
+            _ => ("BANKROLL".to_string(), &rounds[..]),
+        };
+
+        if shown.is_empty() {
+            println!("No games were played in that range.");
+        } else {
+            println!("\n{title} ({} hands)\n", shown.len());
+            for line in bankroll_chart::render(shown, bankroll_chart::terminal_width()) {
+                println!("{line}");
+            }
         }
         Self::wait_for_enter();
     }
 
+    fn show_sessions(&self) {
+        sessions::display_sessions(self.sessions);
+        Self::wait_for_enter();
+    }
+
+    fn browse_games(&self) {
+        if self.report_no_games() {
+            Self::wait_for_enter();
+            return;
+        }
+
+        let last_page = (self.matches.len() - 1) / PAGE_SIZE;
+        let mut page = 0;
+        loop {
+            self.history
+                .display_game_page(&self.matches, page, PAGE_SIZE);
+            let input = prompt(&format!(
+                "Page {} of {}: (n)ext, (p)revious, (f)irst, (l)ast, a game number for details, or (b)ack: ",
+                page + 1,
+                last_page + 1
+            ));
+            match input.to_lowercase().as_str() {
+                "n" | "" => page = (page + 1).min(last_page),
+                "p" => page = page.saturating_sub(1),
+                "f" => page = 0,
+                "l" => page = last_page,
+                "b" | "back" => break,
+                number => {
+                    if let Ok(game_num) = number.parse::<u32>() {
+                        self.history.display_detailed_game(game_num);
+                        Self::wait_for_enter();
+                    } else {
+                        println!("Invalid option. Please try again.");
+                    }
+                }
+            }
+        }
+    }
+
     fn show_detailed_game(&self) {
         if self.history.rounds.is_empty() {
             println!("No games played yet!");
         } else {
-            print!("Enter game number (1-{}): ", self.history.rounds.len());
+            let first = self.history.rounds.first().map_or(1, |r| r.round_number);
+            let last = self.history.rounds.last().map_or(1, |r| r.round_number);
+            print!("Enter game number ({first}-{last}): ");
             io::stdout().flush().ok();
 
             let mut input = String::new();
             io::stdin().read_line(&mut input).ok();
 
-            if let Ok(game_num) = input.trim().parse::<usize>() {
+            if let Ok(game_num) = input.trim().parse::<u32>() {
                 self.history.display_detailed_game(game_num);
             } else {
                 println!("Invalid game number!");
@@ -90,11 +243,171 @@ impl<'a> HistoryMenu<'a> {
         Self::wait_for_enter();
     }
 
+    fn edit_filter(&mut self) {
+        loop {
+            self.print_filter_menu();
+            let Ok(input) = text_handler::read_menu_input() else {
+                break;
+            };
+
+            match input.trim().to_lowercase().as_str() {
+                "o" => {
+                    ask(
+                        "Outcome (win, loss, tie, player win, dealer win, player bust, dealer bust or surrender)",
+                        OutcomeFilter::parse,
+                        &mut self.filter.outcome,
+                    );
+                }
+                "t" => {
+                    let parse_date =
+                        |input: &str| NaiveDate::parse_from_str(input, DATE_FORMAT).ok();
+                    ask("From date (YYYY-MM-DD)", parse_date, &mut self.filter.from);
+                    ask("To date (YYYY-MM-DD)", parse_date, &mut self.filter.to);
+                }
+                "m" => {
+                    let parse_bet = |input: &str| input.parse::<i32>().ok();
+                    ask("Minimum bet", parse_bet, &mut self.filter.min_bet);
+                    ask("Maximum bet", parse_bet, &mut self.filter.max_bet);
+                }
+                "x" => {
+                    ask(
+                        "Doubled down? (y/n)",
+                        parse_yes_no,
+                        &mut self.filter.double_down,
+                    );
+                }
+                "p" => {
+                    ask("Split hand? (y/n)", parse_yes_no, &mut self.filter.split);
+                }
+                "u" => {
+                    ask(
+                        "Dealer upcard (2-10 or A)",
+                        parse_upcard,
+                        &mut self.filter.upcard,
+                    );
+                }
+                "h" => {
+                    ask(
+                        "Starting hand (16 for hard 16, s18 for soft 18, p8 for a pair of 8s)",
+                        StartingHand::parse,
+                        &mut self.filter.starting_hand,
+                    );
+                }
+                "s" => self.choose_sort_order(),
+                "c" => self.filter = HistoryFilter::new(),
+                "b" | "back" => break,
+                _ => {
+                    println!("Invalid option. Please try again.");
+                }
+            }
+            self.apply_filter();
+        }
+    }
+
+    fn print_filter_menu(&self) {
+        let any = |value: Option<String>| value.unwrap_or_else(|| "any".to_string());
+        let yes_no =
+            |value: Option<bool>| any(value.map(|v| if v { "yes" } else { "no" }.to_string()));
+        let range = |from: Option<String>, to: Option<String>| match (from, to) {
+            (None, None) => "any".to_string(),
+            (from, to) => format!("{} to {}", any(from), any(to)),
+        };
+        let filter = &self.filter;
+
+        let mut formatter = BoxFormatter::new(56, "FILTER AND SORT GAMES");
+        formatter.add_field("(o) Outcome", &any(filter.outcome.map(|o| o.to_string())));
+        formatter.add_field(
+            "(t) Dates",
+            &range(
+                filter.from.map(|d| d.format(DATE_FORMAT).to_string()),
+                filter.to.map(|d| d.format(DATE_FORMAT).to_string()),
+            ),
+        );
+        formatter.add_field(
+            "(m) Bet",
+            &range(
+                filter.min_bet.map(|b| b.to_string()),
+                filter.max_bet.map(|b| b.to_string()),
+            ),
+        );
+        formatter.add_field("(x) Doubled down", &yes_no(filter.double_down));
+        formatter.add_field("(p) Split hand", &yes_no(filter.split));
+        formatter.add_field("(u) Dealer upcard", &any(filter.upcard.map(upcard_label)));
+        formatter.add_field(
+            "(h) Starting hand",
+            &any(filter.starting_hand.as_ref().map(ToString::to_string)),
+        );
+        formatter.add_field(
+            "(s) Sort by",
+            &format!(
+                "{}, {}",
+                filter.sort_by,
+                if filter.descending {
+                    "highest first"
+                } else {
+                    "lowest first"
+                }
+            ),
+        );
+        formatter.add_line("(c) Clear all");
+        formatter.add_line("(b) Back to game records");
+        formatter.add_separator();
+        formatter.add_line(&format!(
+            "{} of {} games match",
+            self.matches.len(),
+            self.history.rounds.len()
+        ));
+
+        println!("\n{}", formatter.build());
+        print!("Choose an option: ");
+        io::stdout().flush().ok();
+    }
+
+    fn choose_sort_order(&mut self) {
+        match prompt("Sort by (g)ame number, (b)et or (m)oney change: ")
+            .to_lowercase()
+            .as_str()
+        {
+            "g" => self.filter.sort_by = SortKey::Game,
+            "b" => self.filter.sort_by = SortKey::Bet,
+            "m" => self.filter.sort_by = SortKey::MoneyChange,
+            _ => {
+                println!("Invalid option. Sort order unchanged.");
+                return;
+            }
+        }
+        self.filter.descending = prompt("(l)owest or (h)ighest first? ").eq_ignore_ascii_case("h");
+    }
+
+    fn apply_filter(&mut self) {
+        self.matches = self.filter.apply(self.history);
+        self.view = self.history.subset(&self.matches);
+    }
+
     fn export_history(&self) {
-        if self.history.rounds.is_empty() {
-            println!("No game history to export!");
+        if self.view.rounds.is_empty() {
+            println!("No games to export!");
         } else {
-            let csv_content = self.history.export_to_csv();
+            print!("Export format: (c)sv, (j)son, json (l)ines, (m)arkdown report or (h)tml report (default csv): ");
+            io::stdout().flush().ok();
+
+            let mut format = String::new();
+            io::stdin().read_line(&mut format).ok();
+            let (extension, content) = match format.trim().to_lowercase().as_str() {
+                "j" | "json" => ("json", self.view.export_to_json()),
+                "l" | "jsonl" => ("jsonl", self.view.export_to_jsonl()),
+                "m" | "md" | "markdown" => ("md", Ok(self.view.export_to_markdown())),
+                "h" | "html" => ("html", Ok(self.view.export_to_html())),
+                _ => ("csv", Ok(self.view.export_to_csv())),
+            };
+            let content = match content {
+                Ok(content) => content,
+                Err(e) => {
+                    println!("✗ Failed to export history: {e}");
+                    Self::wait_for_enter();
+                    return;
+                }
+            };
 
             print!("Enter filename (without extension): ");
             io::stdout().flush().ok();
@@ -105,17 +418,17 @@ impl<'a> HistoryMenu<'a> {
 
             let full_filename = if filename.is_empty() {
                 format!(
-                    "blackjack_history_{}.csv",
+                    "blackjack_history_{}.{extension}",
                     chrono::Local::now().format("%Y%m%d_%H%M%S")
                 )
             } else {
-                format!("{filename}.csv")
+                format!("{filename}.{extension}")
             };
 
-            match std::fs::write(&full_filename, csv_content) {
+            match std::fs::write(&full_filename, content) {
                 Ok(()) => {
                     println!("✓ History exported successfully to: {full_filename}");
-                    println!("  Total games exported: {}", self.history.rounds.len());
+                    println!("  Total games exported: {}", self.view.rounds.len());
                 }
                 Err(e) => {
                     println!("✗ Failed to export history: {e}");
@@ -132,7 +445,37 @@ impl<'a> HistoryMenu<'a> {
     }
 }
 
-pub fn show_history_menu(history: &GameHistory) {
-    let menu = HistoryMenu::new(history);
+/// Read one trimmed line after showing `message`
+fn prompt(message: &str) -> String {
+    print!("{message}");
+    io::stdout().flush().ok();
+    let mut input = String::new();
+    io::stdin().read_line(&mut input).ok();
+    input.trim().to_string()
+}
+
+/// Ask for a new value of a filter condition. A blank answer clears it so
+/// it matches anything; one that cannot be understood leaves it as it was.
+fn ask<T>(message: &str, parse: impl Fn(&str) -> Option<T>, condition: &mut Option<T>) {
+    let input = prompt(&format!("{message} (blank for any): "));
+    if input.is_empty() {
+        *condition = None;
+    } else if let Some(value) = parse(&input) {
+        *condition = Some(value);
+    } else {
+        println!("\"{input}\" was not understood. That filter is unchanged.");
+    }
+}
+
+fn parse_yes_no(input: &str) -> Option<bool> {
+    match input.to_lowercase().as_str() {
+        "y" | "yes" => Some(true),
+        "n" | "no" => Some(false),
+        _ => None,
+    }
+}
+
+pub fn show_history_menu(history: &GameHistory, sessions: &[Session]) {
+    let mut menu = HistoryMenu::new(history, sessions);
     menu.show_menu();
 }
@@ -0,0 +1,288 @@
+use crate::formatting::{format_money, format_percentage, BoxFormatter};
+use crate::game_history::{GameOutcome, GameRound};
+use crate::game_state::GameState;
+use crate::profiles::Profile;
+use crate::safe_file;
+use chrono::{DateTime, Local};
+use serde::{Deserialize, Serialize};
+use std::cmp::Ordering;
+
+/// Runs shown on each board
+const BOARD_SIZE: usize = 5;
+
+/// Hands a run needs before its win rate can make the board
+pub const MIN_HANDS_FOR_WIN_RATE: u32 = 100;
+
+/// Play from a fresh bankroll until it runs out at "Game Over"
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Run {
+    pub start: DateTime<Local>,
+    /// When the last hand of the run was played
+    pub end: DateTime<Local>,
+    pub hands_played: u32,
+    pub wins: u32,
+    pub losses: u32,
+    pub ties: u32,
+    pub peak_money: i32,
+    pub biggest_win: i32,
+}
+
+impl Run {
+    /// A run whose first hand is `round`
+    #[must_use]
+    pub fn starting_with(round: &GameRound) -> Self {
+        let mut run = Run {
+            start: round.timestamp,
+            end: round.timestamp,
+            hands_played: 0,
+            wins: 0,
+            losses: 0,
+            ties: 0,
+            peak_money: round.money_after - round.money_change,
+            biggest_win: 0,
+        };
+        run.record_hand(round);
+        run
+    }
+
+    pub fn record_hand(&mut self, round: &GameRound) {
+        self.hands_played += 1;
+        match round.outcome {
+            GameOutcome::PlayerWin | GameOutcome::DealerBust => self.wins += 1,
+            GameOutcome::Tie => self.ties += 1,
+            _ => self.losses += 1,
+        }
+        self.peak_money = self.peak_money.max(round.money_after);
+        self.biggest_win = self.biggest_win.max(round.money_change);
+        self.end = round.timestamp;
+    }
+
+    #[must_use]
+    pub fn win_rate(&self) -> f64 {
+        if self.hands_played == 0 {
+            0.0
+        } else {
+            f64::from(self.wins) / f64::from(self.hands_played) * 100.0
+        }
+    }
+}
+
+/// The finished runs of a profile and the one being played
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Leaderboard {
+    pub runs: Vec<Run>,
+    pub current: Option<Run>,
+}
+
+/// What a board ranks runs by
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Category {
+    PeakBankroll,
+    Survival,
+    BiggestWin,
+    WinRate,
+}
+
+impl Category {
+    pub const ALL: [Category; 4] = [
+        Category::PeakBankroll,
+        Category::Survival,
+        Category::BiggestWin,
+        Category::WinRate,
+    ];
+
+    #[must_use]
+    pub fn title(self) -> &'static str {
+        match self {
+            Category::PeakBankroll => "PEAK BANKROLL",
+            Category::Survival => "LONGEST SURVIVAL",
+            Category::BiggestWin => "BIGGEST SINGLE-HAND WIN",
+            Category::WinRate => "BEST WIN RATE",
+        }
+    }
+
+    /// Whether `run` can be ranked in this category
+    #[must_use]
+    pub fn qualifies(self, run: &Run) -> bool {
+        match self {
+            Category::WinRate => run.hands_played >= MIN_HANDS_FOR_WIN_RATE,
+            _ => run.hands_played > 0,
+        }
+    }
+
+    /// Order with the better run first
+    #[must_use]
+    pub fn compare(self, a: &Run, b: &Run) -> Ordering {
+        match self {
+            Category::PeakBankroll => b.peak_money.cmp(&a.peak_money),
+            Category::Survival => b.hands_played.cmp(&a.hands_played),
+            Category::BiggestWin => b.biggest_win.cmp(&a.biggest_win),
+            Category::WinRate => b.win_rate().total_cmp(&a.win_rate()),
+        }
+    }
+
+    /// The run's score in this category, for showing on the board
+    #[must_use]
+    pub fn score(self, run: &Run) -> String {
+        match self {
+            Category::PeakBankroll => run.peak_money.to_string(),
+            Category::Survival => format!("{} hands", run.hands_played),
+            Category::BiggestWin => format_money(run.biggest_win),
+            Category::WinRate => format_percentage(run.win_rate()),
+        }
+    }
+}
+
+impl Leaderboard {
+    /// Rebuild the runs from a profile's history, for profiles that played
+    /// before runs were kept. A run ends with a hand that left no coins.
+    #[must_use]
+    pub fn from_rounds(rounds: &[GameRound]) -> Self {
+        let mut leaderboard = Leaderboard::default();
+        for round in rounds {
+            leaderboard.record_hand(round);
+            if round.money_after <= 0 {
+                leaderboard.end_run();
+            }
+        }
+        leaderboard
+    }
+
+    pub fn record_hand(&mut self, round: &GameRound) {
+        match &mut self.current {
+            Some(run) => run.record_hand(round),
+            None => self.current = Some(Run::starting_with(round)),
+        }
+    }
+
+    /// Close the run being played. Returns it, or `None` if no hand has
+    /// been played since the last run ended.
+    pub fn end_run(&mut self) -> Option<Run> {
+        let run = self.current.take()?;
+        self.runs.push(run.clone());
+        Some(run)
+    }
+
+    /// Every run that qualifies for `category`, best first, paired with
+    /// whether it is the run still being played
+    #[must_use]
+    pub fn ranked(&self, category: Category) -> Vec<(&Run, bool)> {
+        let mut ranked: Vec<(&Run, bool)> = self
+            .runs
+            .iter()
+            .map(|run| (run, false))
+            .chain(self.current.iter().map(|run| (run, true)))
+            .filter(|(run, _)| category.qualifies(run))
+            .collect();
+        ranked.sort_by(|(a, _), (b, _)| category.compare(a, b));
+        ranked
+    }
+
+    /// Position of `run` on the board for `category`, counting from 1
+    #[must_use]
+    pub fn rank_of(&self, run: &Run, category: Category) -> Option<usize> {
+        self.ranked(category)
+            .iter()
+            .position(|(ranked, _)| *ranked == run)
+            .map(|i| i + 1)
+    }
+}
+
+/// The leaderboard of `profile`, rebuilt from `rounds` if it has none yet
+fn open(profile: &Profile, rounds: &[GameRound]) -> Option<Leaderboard> {
+    match safe_file::read_json(&profile.leaderboard_path()) {
+        Ok(leaderboard) => Some(leaderboard.unwrap_or_else(|| Leaderboard::from_rounds(rounds))),
+        Err(e) => {
+            eprintln!("⚠ Warning: Could not read your leaderboard: {e}");
+            None
+        }
+    }
+}
+
+fn store(profile: &Profile, leaderboard: &Leaderboard) {
+    if let Err(e) = safe_file::write_json(&profile.leaderboard_path(), leaderboard) {
+        eprintln!("⚠ Warning: Could not save your leaderboard: {e}");
+    }
+}
+
+/// Count the hand `state` has just recorded towards the run being played
+pub fn record_hand(state: &GameState) {
+    let (Some(profile), Some((round, earlier))) =
+        (&state.profile, state.history.rounds.split_last())
+    else {
+        return;
+    };
+    // A leaderboard rebuilt from the earlier hands counts this one below
+    if let Some(mut leaderboard) = open(profile, earlier) {
+        leaderboard.record_hand(round);
+        store(profile, &leaderboard);
+    }
+}
+
+/// Close the run of `profile` at "Game Over" and show how it ranks
+pub fn end_run(profile: &Profile, rounds: &[GameRound]) {
+    let Some(mut leaderboard) = open(profile, rounds) else {
+        return;
+    };
+    let Some(run) = leaderboard.end_run() else {
+        return;
+    };
+    store(profile, &leaderboard);
+
+    let mut formatter = BoxFormatter::new(44, "RUN OVER");
+    formatter.add_field_aligned("Hands Survived", &run.hands_played);
+    formatter.add_field_aligned("Peak Bankroll", &run.peak_money);
+    formatter.add_field_aligned("Biggest Win", &format_money(run.biggest_win));
+    formatter.add_field_aligned("Win Rate", &format_percentage(run.win_rate()));
+    let placings: Vec<String> = Category::ALL
+        .into_iter()
+        .filter_map(|category| {
+            let rank = leaderboard.rank_of(&run, category)?;
+            (rank <= BOARD_SIZE).then(|| format!("#{rank} for {}", category.title().to_lowercase()))
+        })
+        .collect();
+    if !placings.is_empty() {
+        formatter.add_separator();
+        formatter.add_line("New leaderboard entry:");
+        for placing in placings {
+            formatter.add_line(&placing);
+        }
+    }
+    println!("\n{}\n", formatter.build());
+}
+
+/// Show the best runs of `profile` in each category
+pub fn show_leaderboard(profile: &Profile, rounds: &[GameRound]) {
+    let leaderboard = open(profile, rounds).unwrap_or_default();
+    let mut formatter = BoxFormatter::new(52, "LEADERBOARD");
+    for (i, category) in Category::ALL.into_iter().enumerate() {
+        if i > 0 {
+            formatter.add_empty_line();
+        }
+        formatter.add_line(category.title());
+        formatter.add_separator();
+        let ranked = leaderboard.ranked(category);
+        if ranked.is_empty() {
+            formatter.add_line(&if category == Category::WinRate {
+                format!("No run has lasted {MIN_HANDS_FOR_WIN_RATE} hands yet")
+            } else {
+                "No runs yet".to_string()
+            });
+        }
+        for (rank, (run, current)) in ranked.into_iter().take(BOARD_SIZE).enumerate() {
+            formatter.add_line(&format!(
+                "{:>2}. {:>12}   {} to {}{}",
+                rank + 1,
+                category.score(run),
+                run.start.format("%Y-%m-%d"),
+                run.end.format("%Y-%m-%d"),
+                if current { " *" } else { "" }
+            ));
+        }
+    }
+    if leaderboard.current.is_some() {
+        formatter.add_separator();
+        formatter.add_line("* run still being played");
+    }
+    println!("\n{}\n", formatter.build());
+}
@@ -1,22 +1,47 @@
+pub mod achievements;
 pub mod art_handler;
+pub mod bankroll_chart;
+pub mod basic_strategy;
 pub mod card_handler;
+pub mod csv_io;
+pub mod daily_challenge;
+pub mod daily_challenge_menu;
 pub mod deck_manager;
+pub mod duplicate;
 pub mod enemy_ai_handler;
+pub mod expected_value;
 pub mod formatting;
 pub mod game_data;
 pub mod game_history;
 pub mod game_history_core;
 pub mod game_history_display;
 pub mod game_history_export;
+pub mod game_history_report;
 pub mod game_loop;
 pub mod game_manager;
 pub mod game_state;
+pub mod history_filter;
+pub mod history_log;
 pub mod history_menu;
+pub mod leaderboard;
 pub mod main_menu;
 pub mod menu_handling;
 pub mod player_handler;
+pub mod profile_menu;
+pub mod profiles;
+pub mod round_recovery;
+pub mod rules_menu;
+pub mod safe_file;
 pub mod save_system;
+pub mod scenario;
+pub mod scenario_menu;
+pub mod sessions;
+pub mod side_bets;
+pub mod streaks;
+pub mod table_rules;
 pub mod text_handler;
+pub mod tournament;
+pub mod tournament_menu;
 pub mod win_checker;
 
 use game_manager::GameManager;
@@ -1,6 +1,11 @@
+use crate::daily_challenge_menu;
+use crate::duplicate;
 use crate::game_loop;
 use crate::main_menu;
+use crate::profiles;
+use crate::scenario_menu;
 use crate::text_handler;
+use crate::tournament_menu;
 
 #[derive(Copy, Clone, Debug, PartialEq)]
 pub enum MenuAction {
@@ -9,6 +14,14 @@ pub enum MenuAction {
     Guide,
     NewGame,
     History,
+    Rules,
+    Tournament,
+    Duplicate,
+    DailyChallenge,
+    Scenarios,
+    Profile,
+    Achievements,
+    Leaderboard,
     Quit,
     Invalid,
 }
@@ -64,6 +77,14 @@ impl MenuAction {
             "g" => MenuAction::Guide,
             "n" => MenuAction::NewGame,
             "r" => MenuAction::History,
+            "t" => MenuAction::Rules,
+            "o" => MenuAction::Tournament,
+            "d" => MenuAction::Duplicate,
+            "c" => MenuAction::DailyChallenge,
+            "s" => MenuAction::Scenarios,
+            "p" => MenuAction::Profile,
+            "v" => MenuAction::Achievements,
+            "l" => MenuAction::Leaderboard,
             "q" => MenuAction::Quit,
             _ => MenuAction::Invalid,
         }
@@ -89,6 +110,32 @@ impl MenuAction {
             MenuAction::History => {
                 println!("History functionality is handled by GameManager");
             }
+            MenuAction::Rules => {
+                println!("Table rules are handled by GameManager");
+            }
+            MenuAction::Tournament => match profiles::startup_profile() {
+                Ok(profile) => tournament_menu::show_tournament_menu(&profile),
+                Err(e) => eprintln!("✗ Could not open your profile: {e}"),
+            },
+            MenuAction::Duplicate => {
+                duplicate::show_duplicate_mode();
+            }
+            MenuAction::DailyChallenge => match profiles::startup_profile() {
+                Ok(profile) => daily_challenge_menu::show_daily_challenge_menu(&profile),
+                Err(e) => eprintln!("✗ Could not open your profile: {e}"),
+            },
+            MenuAction::Scenarios => {
+                scenario_menu::show_scenario_menu();
+            }
+            MenuAction::Profile => {
+                println!("Profiles are handled by GameManager");
+            }
+            MenuAction::Achievements => {
+                println!("Achievements are handled by GameManager");
+            }
+            MenuAction::Leaderboard => {
+                println!("The leaderboard is handled by GameManager");
+            }
             MenuAction::Quit => {
                 std::process::exit(0);
             }
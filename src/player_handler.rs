@@ -1,6 +1,8 @@
-use crate::art_handler::{get_card_art, get_error_message};
+use crate::art_handler::{get_card_art, get_error_message, get_message};
+use crate::basic_strategy::StrategyAction;
 use crate::game_loop::read_char;
-use crate::game_state::GameState;
+use crate::game_state::{GameState, PlayedHand};
+use crate::table_rules::GameVariant;
 use std::io::{self, Write};
 
 #[must_use]
@@ -28,6 +30,24 @@ pub fn hand_value(hand: &[String]) -> i32 {
     total
 }
 
+/// A hand is soft when one of its aces can count as 11 without busting
+#[must_use]
+pub fn is_soft_hand(hand: &[String]) -> bool {
+    let has_ace = hand
+        .iter()
+        .any(|card| card.split_whitespace().next() == Some("A"));
+    let hard_total: i32 = hand
+        .iter()
+        .map(|card| match card.split_whitespace().next() {
+            Some("A") => 1,
+            Some("K" | "Q" | "J") => 10,
+            Some(rank) => rank.parse::<i32>().unwrap_or(0),
+            None => 0,
+        })
+        .sum();
+    has_ace && hard_total + 10 <= 21
+}
+
 #[must_use]
 pub fn card_art_index(card: &str) -> usize {
     match card.split_whitespace().next() {
@@ -78,7 +98,7 @@ pub fn print_player_cards(state: &GameState) {
 }
 
 pub fn print_initial_game_state(state: &GameState) {
-    println!("Dealer shows: {} (hole card hidden)", state.dealer_cards[0]);
+    print_dealer_upcards(state);
     println!("Your total: {}", hand_value(&state.player_cards));
     let card_art = get_card_art();
     let card_arts: Vec<Vec<&str>> = state
@@ -97,6 +117,26 @@ pub fn print_initial_game_state(state: &GameState) {
     }
 }
 
+/// Print the dealer's cards the table rules allow the player to see
+fn print_dealer_upcards(state: &GameState) {
+    let face_up = state.rules.variant.dealer_cards_face_up();
+    if face_up == 0 {
+        println!(
+            "{}",
+            get_message("Dealer's cards are face down", Some(state))
+        );
+    } else if face_up >= 2 {
+        println!(
+            "Dealer shows: {} and {} (total: {})",
+            state.dealer_cards[0],
+            state.dealer_cards[1],
+            hand_value(&state.dealer_cards)
+        );
+    } else {
+        println!("Dealer shows: {} (hole card hidden)", state.dealer_cards[0]);
+    }
+}
+
 fn print_hand_cards(hand: &[String], hand_name: &str) {
     println!("{} total: {}", hand_name, hand_value(hand));
     let card_art = get_card_art();
@@ -115,8 +155,64 @@ fn print_hand_cards(hand: &[String], hand_name: &str) {
     }
 }
 
+/// Blackjack Switch: show both hands and let the player swap their second
+/// cards before either hand is played.
+pub fn switch_phase(state: &mut GameState) {
+    print_dealer_upcards(state);
+    print_hand_cards(&state.player_cards, "Hand 1");
+    print_hand_cards(&state.second_hand, "Hand 2");
+
+    loop {
+        print!("Do you want to s(w)itch the second cards or (k)eep your hands? ");
+        io::stdout().flush().ok();
+
+        match read_char() {
+            'w' => {
+                std::mem::swap(&mut state.player_cards[1], &mut state.second_hand[1]);
+                state.was_switched = true;
+                println!("Second cards switched!");
+                print_hand_cards(&state.player_cards, "Hand 1");
+                print_hand_cards(&state.second_hand, "Hand 2");
+                return;
+            }
+            'k' => return,
+            _ => println!("Invalid action, please choose again."),
+        }
+    }
+}
+
+fn can_surrender(state: &GameState) -> bool {
+    state.player_card_count == 2 && state.rules.variant != GameVariant::BlackjackSwitch
+}
+
+/// Free Bet Blackjack doubles hard 9, 10 and 11 for free
+fn is_free_double(state: &GameState) -> bool {
+    state.rules.variant == GameVariant::FreeBet
+        && state.player_card_count == 2
+        && !is_soft_hand(&state.player_cards)
+        && (9..=11).contains(&hand_value(&state.player_cards))
+}
+
+fn can_double(state: &GameState) -> bool {
+    state.player_card_count == 2 && (state.money >= state.bet || is_free_double(state))
+}
+
+/// Free Bet Blackjack splits every pair except ten-value cards for free
+fn is_free_split(state: &GameState) -> bool {
+    state.rules.variant == GameVariant::FreeBet
+        && is_pair(state)
+        && !matches!(
+            state.player_cards[0].split_whitespace().next(),
+            Some("10" | "J" | "Q" | "K")
+        )
+}
+
 fn can_split(state: &GameState) -> bool {
-    if state.player_card_count != 2 || state.money < state.bet {
+    is_pair(state) && (state.money >= state.bet || is_free_split(state))
+}
+
+fn is_pair(state: &GameState) -> bool {
+    if state.player_card_count != 2 {
         return false;
     }
 
@@ -139,19 +235,31 @@ fn can_split(state: &GameState) -> bool {
     is_ten_value(card1_rank) && is_ten_value(card2_rank)
 }
 
-fn play_split_hand(hand: &mut Vec<String>, hand_name: &str, state: &mut GameState) -> bool {
+/// Play split hand `index` of `state.split_hands`, drawing its second card
+/// first unless it already has one from before the round was interrupted.
+fn play_split_hand(state: &mut GameState, index: usize) {
+    let hand_name = format!("Hand {}", index + 1);
     println!("\n--- Playing {hand_name} ---");
 
     // Draw one card for this hand
-    let card = draw(state);
-    hand.push(card.clone());
-    println!("Drew: {card}");
-    print_hand_cards(hand, hand_name);
+    if state.split_hands[index].cards.len() == 1 {
+        let card = draw(state);
+        state.split_hands[index].cards.push(card.clone());
+        state.save_round();
+        println!("Drew: {card}");
+    }
+    print_hand_cards(&state.split_hands[index].cards, &hand_name);
 
     // Check for blackjack (21 with 2 cards)
+    let hand = &state.split_hands[index].cards;
     if hand.len() == 2 && hand_value(hand) == 21 {
         println!("Blackjack on {hand_name}!");
-        return false; // No bust, stand automatically
+        return; // No bust, stand automatically
+    }
+    // A resumed round may have been saved right after the card that busted
+    if hand_value(hand) > 21 {
+        println!("{hand_name} busted!");
+        return;
     }
 
     // Play this hand
@@ -162,17 +270,22 @@ fn play_split_hand(hand: &mut Vec<String>, hand_name: &str, state: &mut GameStat
         let action = read_char();
         match action {
             'h' => {
+                state.actions.push(StrategyAction::Hit);
                 let card = draw(state);
-                hand.push(card.clone());
+                state.split_hands[index].cards.push(card.clone());
+                state.save_round();
                 println!("You got: {card}");
-                print_hand_cards(hand, hand_name);
+                print_hand_cards(&state.split_hands[index].cards, &hand_name);
 
-                if hand_value(hand) > 21 {
+                if hand_value(&state.split_hands[index].cards) > 21 {
                     println!("{hand_name} busted!");
-                    return true; // Busted
+                    return;
                 }
             }
-            's' => return false, // Stand, no bust
+            's' => {
+                state.actions.push(StrategyAction::Stand);
+                return; // Stand, no bust
+            }
             _ => {
                 println!("Invalid action, please choose again.");
             }
@@ -180,21 +293,59 @@ fn play_split_hand(hand: &mut Vec<String>, hand_name: &str, state: &mut GameStat
     }
 }
 
+/// Play the hands of a split one after the other, from the first one not
+/// finished yet. Returns `false` if every hand busted.
+fn play_split_hands(state: &mut GameState) -> bool {
+    while state.active_split_hand < state.split_hands.len() {
+        play_split_hand(state, state.active_split_hand);
+        state.active_split_hand += 1;
+        state.save_round();
+    }
+
+    if state
+        .split_hands
+        .iter()
+        .all(|hand| hand_value(&hand.cards) > 21)
+    {
+        println!("Both hands busted!");
+        return false;
+    }
+    true
+}
+
 #[allow(
     clippy::too_many_lines,
     clippy::cast_possible_truncation,
     clippy::cast_possible_wrap
 )]
 pub fn player_turn(state: &mut GameState) -> bool {
+    // A round resumed after a split carries on with the split hands
+    if !state.split_hands.is_empty() {
+        return play_split_hands(state);
+    }
+    // Rounds are saved as soon as a card is drawn, so a resumed hand may
+    // already be over
+    if state.was_double_down {
+        return finish_double_down(state);
+    }
+    if hand_value(&state.player_cards) > 21 {
+        println!("You busted!");
+        return false;
+    }
+
     loop {
         print!("Choose an action: (h)it, (s)tand");
-        if state.player_card_count == 2 && state.money >= state.bet {
+        if is_free_double(state) {
+            print!(", (d)ouble down for free");
+        } else if can_double(state) {
             print!(", (d)ouble down");
         }
-        if can_split(state) {
+        if is_free_split(state) {
+            print!(", (p)split for free");
+        } else if can_split(state) {
             print!(", (p)split");
         }
-        if state.player_card_count == 2 {
+        if can_surrender(state) {
             print!(", s(u)rrender");
         }
         print!(": ");
@@ -203,9 +354,11 @@ pub fn player_turn(state: &mut GameState) -> bool {
         let action = read_char();
         match action {
             'h' => {
+                state.actions.push(StrategyAction::Hit);
                 let card = draw(state);
                 state.player_cards.push(card.clone());
                 state.player_card_count = state.player_cards.len() as i32;
+                state.save_round();
                 println!("You got: {card}");
                 print_player_cards(state);
 
@@ -214,100 +367,228 @@ pub fn player_turn(state: &mut GameState) -> bool {
                     return false;
                 }
             }
-            's' => return true,
-            'd' if state.player_card_count == 2 && state.money >= state.bet => {
-                state.money -= state.bet;
+            's' => {
+                state.actions.push(StrategyAction::Stand);
+                return true;
+            }
+            'd' if can_double(state) => {
+                state.actions.push(StrategyAction::Double);
+                let free_double = is_free_double(state);
+                if free_double {
+                    state.free_bet += state.bet;
+                    state.free_double_bet = state.bet;
+                } else {
+                    state.money -= state.bet;
+                }
                 state.bet *= 2;
                 state.was_double_down = true;
                 let card = draw(state);
                 state.player_cards.push(card.clone());
                 state.player_card_count = state.player_cards.len() as i32;
-                println!("You doubled down and drew: {card}");
-                print_player_cards(state);
-
-                if hand_value(&state.player_cards) > 21 {
-                    println!("You busted!");
-                    return false;
+                state.save_round();
+                if free_double {
+                    println!("You doubled down for free and drew: {card}");
+                } else {
+                    println!("You doubled down and drew: {card}");
                 }
-                return true;
+                print_player_cards(state);
+                return finish_double_down(state);
             }
-            'd' if state.money < state.bet => {
+            'd' if state.player_card_count == 2 => {
                 println!("Not enough money to double down!");
             }
             'p' if can_split(state) => {
-                // Deduct additional bet for second hand
-                state.money -= state.bet;
-
-                // Split the cards
-                let mut hand1 = vec![state.player_cards[0].clone()];
-                let mut hand2 = vec![state.player_cards[1].clone()];
-
-                println!("Splitting your hand!");
-
-                // Play first hand
-                let hand1_busted = play_split_hand(&mut hand1, "Hand 1", state);
-
-                // Play second hand
-                let hand2_busted = play_split_hand(&mut hand2, "Hand 2", state);
-
-                // Determine outcome
-                if hand1_busted && hand2_busted {
-                    println!("Both hands busted!");
-                    return false;
-                } else if hand1_busted {
-                    println!("Hand 1 busted, but Hand 2 is still alive.");
-                    // Use hand2 as the main hand for dealer comparison
-                    state.player_cards = hand2;
-                    state.player_card_count = state.player_cards.len() as i32;
-                    return true;
-                } else if hand2_busted {
-                    println!("Hand 2 busted, but Hand 1 is still alive.");
-                    // Use hand1 as the main hand for dealer comparison
-                    state.player_cards = hand1;
-                    state.player_card_count = state.player_cards.len() as i32;
-                    return true;
-                }
-                // Both hands alive - use the better one for comparison
-                let hand1_total = hand_value(&hand1);
-                let hand2_total = hand_value(&hand2);
-
-                if hand1_total >= hand2_total {
-                    println!("Using Hand 1 (total: {hand1_total}) for dealer comparison.");
-                    state.player_cards = hand1;
+                state.actions.push(StrategyAction::Split);
+                // Stake the second hand, which is free on Free Bet tables
+                let second_free_bet = if is_free_split(state) {
+                    println!("Free split! The second hand's bet is on the house.");
+                    state.bet
                 } else {
-                    println!("Using Hand 2 (total: {hand2_total}) for dealer comparison.");
-                    state.player_cards = hand2;
-                }
-                state.player_card_count = state.player_cards.len() as i32;
-                return true;
+                    state.money -= state.bet;
+                    println!("Splitting your hand!");
+                    0
+                };
+
+                // Split the cards; both hands are settled against the dealer separately
+                state.split_hands = vec![
+                    PlayedHand {
+                        cards: vec![state.player_cards[0].clone()],
+                        bet: state.bet,
+                        free_bet: state.free_bet,
+                        free_double_bet: 0,
+                        was_double_down: false,
+                        was_split: true,
+                        was_switched: state.was_switched,
+                    },
+                    PlayedHand {
+                        cards: vec![state.player_cards[1].clone()],
+                        bet: state.bet,
+                        free_bet: second_free_bet,
+                        free_double_bet: 0,
+                        was_double_down: false,
+                        was_split: true,
+                        was_switched: state.was_switched,
+                    },
+                ];
+                state.active_split_hand = 0;
+                return play_split_hands(state);
             }
             'p' if !can_split(state) => {
                 if state.player_card_count != 2 {
                     println!("Can only split with exactly 2 cards!");
-                } else if state.money < state.bet {
-                    println!("Not enough money to split!");
-                } else {
+                } else if !is_pair(state) {
                     println!("Cannot split - cards must be same rank!");
+                } else {
+                    println!("Not enough money to split!");
                 }
             }
-            'u' if state.player_card_count == 2 => {
+            'u' if can_surrender(state) => {
+                state.actions.push(StrategyAction::Surrender);
                 println!("You surrendered. Half your bet is returned.");
                 state.money += state.bet / 2;
+                state.surrendered = true;
                 return false;
             }
             'u' if state.player_card_count != 2 => {
                 println!("Can only surrender with your initial 2 cards!");
             }
+            'u' => {
+                println!("Surrender is not offered at this table!");
+            }
+            _ => {
+                println!("Invalid action, please choose again.");
+            }
+        }
+    }
+}
+
+/// Pontoon: the player twists (hits), sticks (stands) on 15 or more, or buys
+/// a card by raising the stake by the original bet. Buying is only allowed
+/// before the first twist. Five cards without busting make a five card trick.
+#[allow(clippy::cast_possible_truncation, clippy::cast_possible_wrap)]
+pub fn pontoon_turn(state: &mut GameState) -> bool {
+    // A resumed round may already have cards bought (each raising the
+    // stake by the original bet) and twisted (logged as hits)
+    let twists = state
+        .actions
+        .iter()
+        .filter(|action| **action == StrategyAction::Hit)
+        .count();
+    let mut has_twisted = twists > 0;
+    let bought = state.player_cards.len() - 2 - twists;
+    let original_bet = state.bet / (bought as i32 + 1);
+    // It may also have been saved right after the card that ended the hand
+    if hand_value(&state.player_cards) > 21 {
+        println!("{}", get_message("Bust", Some(state)));
+        return false;
+    }
+    if state.player_cards.len() == 5 {
+        println!("{}", get_message("Five Card Trick", Some(state)));
+        return true;
+    }
+
+    loop {
+        let can_stick = hand_value(&state.player_cards) >= 15;
+        let can_buy = !has_twisted && state.player_cards.len() < 5 && state.money >= original_bet;
+        let prompt = match (can_stick, can_buy) {
+            (true, true) => "Twist, stick or buy",
+            (true, false) => "Twist or stick",
+            (false, true) => "Twist or buy",
+            (false, false) => "Twist only",
+        };
+        print!("{} ", get_message(prompt, Some(state)));
+        io::stdout().flush().ok();
+
+        let bought = match read_char() {
+            't' => false,
+            'b' if can_buy => {
+                state.money -= original_bet;
+                state.bet += original_bet;
+                true
+            }
+            'b' => {
+                println!("{}", get_message("Cannot buy", Some(state)));
+                continue;
+            }
+            's' if can_stick => {
+                state.actions.push(StrategyAction::Stand);
+                return true;
+            }
+            's' => {
+                println!("{}", get_message("Must twist", Some(state)));
+                continue;
+            }
             _ => {
                 println!("Invalid action, please choose again.");
+                continue;
+            }
+        };
+
+        let card = draw(state);
+        state.player_cards.push(card.clone());
+        state.player_card_count = state.player_cards.len() as i32;
+        if !bought {
+            has_twisted = true;
+            state.actions.push(StrategyAction::Hit);
+        }
+        state.save_round();
+        if bought {
+            println!(
+                "{}",
+                get_message("You bought", Some(state)).replace("{{card}}", &card)
+            );
+        } else {
+            println!(
+                "{}",
+                get_message("You twisted", Some(state)).replace("{{card}}", &card)
+            );
+        }
+        print_player_cards(state);
+
+        if hand_value(&state.player_cards) > 21 {
+            println!("{}", get_message("Bust", Some(state)));
+            return false;
+        }
+        if state.player_cards.len() == 5 {
+            println!("{}", get_message("Five Card Trick", Some(state)));
+            return true;
+        }
+    }
+}
+
+/// Finish a hand once its double down card is dealt. Returns `false` if it
+/// busted or, at a Spanish 21 table, the player rescued the double down.
+fn finish_double_down(state: &mut GameState) -> bool {
+    if hand_value(&state.player_cards) > 21 {
+        println!("You busted!");
+        return false;
+    }
+    !(state.rules.variant == GameVariant::Spanish21 && offer_double_down_rescue(state))
+}
+
+/// Spanish 21 double-down rescue: after doubling the player may take back
+/// the doubled portion of the wager and forfeit the original bet.
+fn offer_double_down_rescue(state: &mut GameState) -> bool {
+    loop {
+        print!("(s)tand or (r)escue your double down? ");
+        io::stdout().flush().ok();
+
+        match read_char() {
+            's' => return false,
+            'r' => {
+                let original_bet = state.bet / 2;
+                state.money += state.bet - original_bet;
+                state.surrendered = true;
+                println!("You rescued your double down and forfeit {original_bet} coins.");
+                return true;
             }
+            _ => println!("Invalid action, please choose again."),
         }
     }
 }
 
 pub fn player_wins(state: &mut GameState) {
-    use crate::art_handler::get_message;
-    println!("\x1b[1;32m{}\x1b[0m", get_message("You Win!", None));
+    println!("\x1b[1;32m{}\x1b[0m", get_message("You Win!", Some(state)));
     state.money += state.bet * 2;
     state.games_won += 1;
 }
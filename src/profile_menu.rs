@@ -0,0 +1,145 @@
+use crate::formatting::BoxFormatter;
+use crate::profiles::{self, Profile};
+use crate::text_handler;
+use std::io::{self, Write};
+
+pub struct ProfileMenu {
+    current: Profile,
+}
+
+impl ProfileMenu {
+    #[must_use]
+    pub fn new(current: Profile) -> Self {
+        ProfileMenu { current }
+    }
+
+    /// Let the player pick a profile, creating, renaming or deleting
+    /// profiles along the way. Enter keeps the highlighted profile.
+    #[must_use]
+    pub fn show_menu(mut self) -> Profile {
+        loop {
+            let profiles = profiles::list_profiles().unwrap_or_else(|e| {
+                eprintln!("⚠ Warning: Could not list profiles: {e}");
+                Vec::new()
+            });
+            self.print_profile_menu(&profiles);
+            let Ok(input) = text_handler::read_menu_input() else {
+                break;
+            };
+
+            match input.trim().to_lowercase().as_str() {
+                "" => break,
+                "n" => self.create_profile(),
+                "r" => self.rename_profile(&profiles),
+                "d" => self.delete_profile(&profiles),
+                choice => match choice.parse::<usize>() {
+                    Ok(n) if (1..=profiles.len()).contains(&n) => {
+                        self.current = profiles[n - 1].clone();
+                        break;
+                    }
+                    _ => println!("Invalid option. Please try again."),
+                },
+            }
+        }
+
+        if let Err(e) = profiles::set_last_used(&self.current) {
+            eprintln!("⚠ Warning: Could not remember the chosen profile: {e}");
+        }
+        println!("Playing as {}.", self.current);
+        self.current
+    }
+
+    fn print_profile_menu(&self, profiles: &[Profile]) {
+        let mut formatter = BoxFormatter::new(50, "PROFILES");
+        for (i, profile) in profiles.iter().enumerate() {
+            let marker = if *profile == self.current { " *" } else { "" };
+            formatter.add_line(&format!("({}) {profile}{marker}", i + 1));
+        }
+        formatter.add_empty_line();
+        formatter.add_line("(n) New profile");
+        formatter.add_line("(r) Rename a profile");
+        formatter.add_line("(d) Delete a profile");
+        formatter.add_line(&format!("(Enter) Play as {}", self.current));
+
+        println!("\n{}", formatter.build());
+        print!("Choose a profile: ");
+        io::stdout().flush().ok();
+    }
+
+    fn create_profile(&mut self) {
+        let name = prompt("Name of the new profile: ");
+        match profiles::create_profile(&name) {
+            Ok(profile) => {
+                println!("✓ Created profile {profile}.");
+                self.current = profile;
+            }
+            Err(e) => println!("✗ Could not create profile: {e}"),
+        }
+    }
+
+    fn rename_profile(&mut self, profiles: &[Profile]) {
+        let Some(profile) = pick_profile(profiles, "Rename which profile? ") else {
+            return;
+        };
+        let new_name = prompt(&format!("New name for {profile}: "));
+        match profiles::rename_profile(profile, &new_name) {
+            Ok(renamed) => {
+                println!("✓ Renamed {profile} to {renamed}.");
+                if self.current == *profile {
+                    self.current = renamed;
+                }
+            }
+            Err(e) => println!("✗ Could not rename profile: {e}"),
+        }
+    }
+
+    fn delete_profile(&mut self, profiles: &[Profile]) {
+        if profiles.len() <= 1 {
+            println!("You need at least one profile.");
+            return;
+        }
+        let Some(profile) = pick_profile(profiles, "Delete which profile? ") else {
+            return;
+        };
+        let answer = prompt(&format!(
+            "Delete {profile} with its bankroll and history? This cannot be undone. (y/n): "
+        ));
+        if !answer.eq_ignore_ascii_case("y") {
+            println!("Kept {profile}.");
+            return;
+        }
+        match profiles::delete_profile(profile) {
+            Ok(()) => {
+                println!("✓ Deleted profile {profile}.");
+                if self.current == *profile {
+                    if let Some(other) = profiles.iter().find(|p| *p != profile) {
+                        self.current = other.clone();
+                    }
+                }
+            }
+            Err(e) => println!("✗ Could not delete profile: {e}"),
+        }
+    }
+}
+
+fn prompt(message: &str) -> String {
+    print!("{message}");
+    io::stdout().flush().ok();
+    text_handler::read_menu_input().unwrap_or_default()
+}
+
+fn pick_profile<'a>(profiles: &'a [Profile], message: &str) -> Option<&'a Profile> {
+    match prompt(message).parse::<usize>() {
+        Ok(n) if (1..=profiles.len()).contains(&n) => Some(&profiles[n - 1]),
+        _ => {
+            println!("Invalid profile number.");
+            None
+        }
+    }
+}
+
+/// Show the profile menu starting from `current` and return the chosen profile
+#[must_use]
+pub fn choose_profile(current: Profile) -> Profile {
+    ProfileMenu::new(current).show_menu()
+}
@@ -0,0 +1,345 @@
+use crate::safe_file::FileLock;
+use std::env;
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+const APP_DIR: &str = "blackjack2";
+const PROFILES_DIR: &str = "profiles";
+const SCENARIOS_DIR: &str = "scenarios";
+const LAST_PROFILE_FILE: &str = "last_profile";
+const LOCK_EXTENSION: &str = "lock";
+const DEFAULT_PROFILE: &str = "default";
+const MAX_NAME_LEN: usize = 24;
+
+pub const SAVE_FILE_NAME: &str = "blackjack_save.json";
+/// History file of older versions, rewritten in full after every round
+pub const STATS_FILE_NAME: &str = "stats.csv";
+pub const HISTORY_LOG_FILE_NAME: &str = "history.log";
+pub const TOURNAMENT_HISTORY_FILE_NAME: &str = "tournament_history.csv";
+pub const DAILY_HISTORY_FILE_NAME: &str = "daily_challenge.csv";
+pub const ROUND_FILE_NAME: &str = "round_in_progress.json";
+pub const SESSIONS_FILE_NAME: &str = "sessions.json";
+pub const ACHIEVEMENTS_FILE_NAME: &str = "achievements.json";
+pub const LEADERBOARD_FILE_NAME: &str = "leaderboard.json";
+
+/// A named player with their own bankroll, settings and history
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Profile {
+    pub name: String,
+    pub dir: PathBuf,
+}
+
+impl Profile {
+    #[must_use]
+    pub fn save_path(&self) -> PathBuf {
+        self.dir.join(SAVE_FILE_NAME)
+    }
+
+    #[must_use]
+    pub fn stats_path(&self) -> PathBuf {
+        self.dir.join(STATS_FILE_NAME)
+    }
+
+    /// Append-only log of every round played
+    #[must_use]
+    pub fn history_log_path(&self) -> PathBuf {
+        self.dir.join(HISTORY_LOG_FILE_NAME)
+    }
+
+    #[must_use]
+    pub fn tournament_history_path(&self) -> PathBuf {
+        self.dir.join(TOURNAMENT_HISTORY_FILE_NAME)
+    }
+
+    /// Score of each day's daily challenge
+    #[must_use]
+    pub fn daily_history_path(&self) -> PathBuf {
+        self.dir.join(DAILY_HISTORY_FILE_NAME)
+    }
+
+    /// Snapshot of a round that was interrupted before it was settled
+    #[must_use]
+    pub fn round_path(&self) -> PathBuf {
+        self.dir.join(ROUND_FILE_NAME)
+    }
+
+    /// Every session played with the profile
+    #[must_use]
+    pub fn sessions_path(&self) -> PathBuf {
+        self.dir.join(SESSIONS_FILE_NAME)
+    }
+
+    /// Achievements unlocked with the profile and progress towards others
+    #[must_use]
+    pub fn achievements_path(&self) -> PathBuf {
+        self.dir.join(ACHIEVEMENTS_FILE_NAME)
+    }
+
+    /// Every run from a fresh bankroll to "Game Over", for the leaderboard
+    #[must_use]
+    pub fn leaderboard_path(&self) -> PathBuf {
+        self.dir.join(LEADERBOARD_FILE_NAME)
+    }
+
+    /// Lock file of the profile. It sits next to the profile's directory
+    /// rather than in it, so the directory can be renamed or removed while
+    /// the lock is held.
+    #[must_use]
+    pub fn lock_path(&self) -> PathBuf {
+        self.dir.with_extension(LOCK_EXTENSION)
+    }
+
+    /// Lock the profile so no other running game can write to it.
+    ///
+    /// # Errors
+    ///
+    /// Returns `ProfileError::InUse` if another game holds the lock.
+    pub fn lock(&self) -> Result<FileLock, ProfileError> {
+        FileLock::try_acquire(&self.lock_path()).map_err(|e| {
+            if e.kind() == io::ErrorKind::WouldBlock {
+                ProfileError::InUse(self.name.clone())
+            } else {
+                ProfileError::Io(e)
+            }
+        })
+    }
+}
+
+impl fmt::Display for Profile {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.name)
+    }
+}
+
+/// Why a profile could not be opened, created, renamed or deleted
+#[derive(Debug)]
+pub enum ProfileError {
+    InvalidName(String),
+    AlreadyExists(String),
+    /// Another running game has the profile open
+    InUse(String),
+    Io(io::Error),
+}
+
+impl fmt::Display for ProfileError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ProfileError::InvalidName(name) => write!(
+                f,
+                "\"{name}\" is not a valid profile name (use up to {MAX_NAME_LEN} letters, digits, spaces, '-' or '_')"
+            ),
+            ProfileError::AlreadyExists(name) => write!(f, "a profile named \"{name}\" already exists"),
+            ProfileError::InUse(name) => {
+                write!(f, "profile \"{name}\" is open in another running game")
+            }
+            ProfileError::Io(e) => write!(f, "{e}"),
+        }
+    }
+}
+
+impl std::error::Error for ProfileError {}
+
+impl From<io::Error> for ProfileError {
+    fn from(e: io::Error) -> Self {
+        ProfileError::Io(e)
+    }
+}
+
+/// Per-user data directory: `$XDG_DATA_HOME/blackjack2`, falling back to
+/// `~/.local/share/blackjack2`, or the working directory without a home.
+#[must_use]
+pub fn data_dir() -> PathBuf {
+    let base = env::var_os("XDG_DATA_HOME")
+        .map(PathBuf::from)
+        .filter(|path| path.is_absolute())
+        .or_else(|| env::var_os("HOME").map(|home| PathBuf::from(home).join(".local/share")));
+    match base {
+        Some(base) => base.join(APP_DIR),
+        None => PathBuf::from("."),
+    }
+}
+
+#[must_use]
+pub fn profiles_dir() -> PathBuf {
+    data_dir().join(PROFILES_DIR)
+}
+
+/// Scenario files shared by every profile
+#[must_use]
+pub fn scenarios_dir() -> PathBuf {
+    data_dir().join(SCENARIOS_DIR)
+}
+
+fn validate_name(name: &str) -> Result<String, ProfileError> {
+    let name = name.trim();
+    let valid = !name.is_empty()
+        && name.len() <= MAX_NAME_LEN
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, ' ' | '-' | '_'));
+    if valid {
+        Ok(name.to_string())
+    } else {
+        Err(ProfileError::InvalidName(name.to_string()))
+    }
+}
+
+fn profile_named(name: &str) -> Profile {
+    Profile {
+        name: name.to_string(),
+        dir: profiles_dir().join(name),
+    }
+}
+
+/// All profiles, sorted by name.
+///
+/// # Errors
+///
+/// Returns an error if the profiles directory exists but cannot be read.
+pub fn list_profiles() -> io::Result<Vec<Profile>> {
+    let dir = profiles_dir();
+    if !dir.exists() {
+        return Ok(Vec::new());
+    }
+
+    let mut profiles = Vec::new();
+    for entry in fs::read_dir(dir)? {
+        let entry = entry?;
+        if entry.file_type()?.is_dir() {
+            if let Some(name) = entry.file_name().to_str() {
+                profiles.push(profile_named(name));
+            }
+        }
+    }
+    profiles.sort_by_key(|profile| profile.name.to_lowercase());
+    Ok(profiles)
+}
+
+/// Create a new, empty profile.
+///
+/// # Errors
+///
+/// Returns an error if the name is invalid or taken, or the directory
+/// cannot be created.
+pub fn create_profile(name: &str) -> Result<Profile, ProfileError> {
+    let name = validate_name(name)?;
+    let profile = profile_named(&name);
+    if profile.dir.exists() {
+        return Err(ProfileError::AlreadyExists(name));
+    }
+    fs::create_dir_all(&profile.dir)?;
+    Ok(profile)
+}
+
+/// Rename a profile, keeping all of its files.
+///
+/// # Errors
+///
+/// Returns an error if the new name is invalid or taken, or the directory
+/// cannot be moved.
+pub fn rename_profile(profile: &Profile, new_name: &str) -> Result<Profile, ProfileError> {
+    let new_name = validate_name(new_name)?;
+    let renamed = profile_named(&new_name);
+    if renamed.dir.exists() {
+        return Err(ProfileError::AlreadyExists(new_name));
+    }
+    let lock = profile.lock()?;
+    let new_lock = renamed.lock()?;
+    fs::rename(&profile.dir, &renamed.dir)?;
+    drop(new_lock);
+    drop(lock);
+    remove_lock_file(profile);
+    if last_used_profile().as_ref() == Some(profile) {
+        set_last_used(&renamed)?;
+    }
+    Ok(renamed)
+}
+
+/// Delete a profile and everything stored in it.
+///
+/// # Errors
+///
+/// Returns an error if another game has the profile open or the directory
+/// cannot be removed.
+pub fn delete_profile(profile: &Profile) -> Result<(), ProfileError> {
+    let lock = profile.lock()?;
+    fs::remove_dir_all(&profile.dir)?;
+    drop(lock);
+    remove_lock_file(profile);
+    Ok(())
+}
+
+/// Remove the lock file of a profile that no longer exists. Another game
+/// may have taken the lock in the meantime, which is harmless, so failures
+/// are ignored.
+fn remove_lock_file(profile: &Profile) {
+    fs::remove_file(profile.lock_path()).ok();
+}
+
+/// The profile chosen last time, if it still exists
+#[must_use]
+pub fn last_used_profile() -> Option<Profile> {
+    let name = fs::read_to_string(data_dir().join(LAST_PROFILE_FILE)).ok()?;
+    let profile = profile_named(name.trim());
+    profile.dir.is_dir().then_some(profile)
+}
+
+/// Remember `profile` as the one to offer first next time.
+///
+/// # Errors
+///
+/// Returns an error if the data directory cannot be written.
+pub fn set_last_used(profile: &Profile) -> io::Result<()> {
+    fs::create_dir_all(data_dir())?;
+    fs::write(data_dir().join(LAST_PROFILE_FILE), &profile.name)
+}
+
+/// Copy a save and history left in the working directory by older versions
+/// into `profile`. Returns whether anything was imported.
+///
+/// # Errors
+///
+/// Returns an error if a file exists but cannot be copied.
+pub fn import_legacy_files(profile: &Profile) -> io::Result<bool> {
+    let mut imported = false;
+    for name in [
+        SAVE_FILE_NAME,
+        STATS_FILE_NAME,
+        TOURNAMENT_HISTORY_FILE_NAME,
+    ] {
+        let legacy = Path::new(name);
+        let target = profile.dir.join(name);
+        if legacy.is_file() && !target.exists() {
+            fs::copy(legacy, target)?;
+            imported = true;
+        }
+    }
+    Ok(imported)
+}
+
+/// The profile to start with: the last one used, otherwise the first one,
+/// otherwise a new default profile holding any files from older versions.
+///
+/// # Errors
+///
+/// Returns an error if no profile exists and one cannot be created.
+pub fn startup_profile() -> Result<Profile, ProfileError> {
+    if let Some(profile) = last_used_profile() {
+        return Ok(profile);
+    }
+    if let Some(profile) = list_profiles()?.into_iter().next() {
+        return Ok(profile);
+    }
+
+    let profile = create_profile(DEFAULT_PROFILE)?;
+    if import_legacy_files(&profile)? {
+        println!(
+            "✓ Imported your existing save into profile \"{}\" ({})",
+            profile.name,
+            profile.dir.display()
+        );
+    }
+    Ok(profile)
+}
@@ -0,0 +1,341 @@
+use crate::basic_strategy::StrategyAction;
+use crate::deck_manager::create_stacked_deck_for;
+use crate::formatting::BoxFormatter;
+use crate::game_state::{GameState, PlayedHand};
+use crate::profiles::Profile;
+use crate::safe_file;
+use crate::save_system::{self, SaveError};
+use crate::side_bets::PlacedSideBet;
+use crate::table_rules::TableRules;
+use crate::text_handler;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::io::{self, Write};
+use std::path::Path;
+
+/// How far a round has got, and so where an interrupted round picks up
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum RoundPhase {
+    /// No bet is on the table
+    Betting,
+    /// The main bet is placed and side bets are being chosen
+    SideBets,
+    /// Blackjack Switch: deciding whether to swap the second cards
+    Switch,
+    /// The player is deciding on the given hand; only Blackjack Switch
+    /// rounds have a second one
+    Playing(usize),
+    /// The player is done and the dealer plays out before settling
+    Dealer,
+    /// The dealer is done and the given number of finished hands have been
+    /// settled and recorded
+    Settling(usize),
+}
+
+/// Everything needed to carry on with a round after the game was closed
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RoundSnapshot {
+    pub phase: RoundPhase,
+    pub rules: TableRules,
+    /// The deck is kept as what it was built from rather than as cards, so
+    /// the snapshot does not list the cards still to come
+    #[serde(default)]
+    pub deck_top: Vec<String>,
+    #[serde(default)]
+    pub deck_seed: u64,
+    /// Whole deck of snapshots written by older versions
+    #[serde(default, skip_serializing)]
+    pub card_deck: Vec<String>,
+    pub deck_index: i32,
+    pub player_cards: Vec<String>,
+    pub second_hand: Vec<String>,
+    pub dealer_cards: Vec<String>,
+    /// Bankroll with this round's stakes already taken out, and the payouts
+    /// of any hands already settled added back
+    pub money: i32,
+    /// Bankroll before the stakes still on the table were taken out
+    pub round_start_money: i32,
+    pub bet: i32,
+    pub free_bet: i32,
+    #[serde(default)]
+    pub free_double_bet: i32,
+    pub was_double_down: bool,
+    pub surrendered: bool,
+    #[serde(default)]
+    pub was_switched: bool,
+    pub split_hands: Vec<PlayedHand>,
+    pub active_split_hand: usize,
+    pub finished_hands: Vec<PlayedHand>,
+    pub side_bets: Vec<PlacedSideBet>,
+    pub actions: Vec<StrategyAction>,
+}
+
+impl RoundSnapshot {
+    #[must_use]
+    pub fn capture(state: &GameState) -> Self {
+        RoundSnapshot {
+            phase: state.round_phase,
+            rules: state.rules.clone(),
+            deck_top: state.deck_top.clone(),
+            deck_seed: state.deck_seed,
+            card_deck: Vec::new(),
+            deck_index: state.deck_index,
+            player_cards: state.player_cards.clone(),
+            second_hand: state.second_hand.clone(),
+            dealer_cards: state.dealer_cards.clone(),
+            money: state.money,
+            round_start_money: state.current_round_start_money,
+            bet: state.bet,
+            free_bet: state.free_bet,
+            free_double_bet: state.free_double_bet,
+            was_double_down: state.was_double_down,
+            surrendered: state.surrendered,
+            was_switched: state.was_switched,
+            split_hands: state.split_hands.clone(),
+            active_split_hand: state.active_split_hand,
+            finished_hands: state.finished_hands.clone(),
+            side_bets: state.side_bets.clone(),
+            actions: state.actions.clone(),
+        }
+    }
+
+    /// Put the round back on the table in `state`
+    #[allow(clippy::cast_possible_truncation, clippy::cast_possible_wrap)]
+    pub fn restore(self, state: &mut GameState) {
+        state.round_phase = self.phase;
+        state.rules = self.rules;
+        state.card_deck = if self.card_deck.is_empty() {
+            create_stacked_deck_for(state.rules.variant, &self.deck_top, self.deck_seed)
+        } else {
+            self.card_deck
+        };
+        state.deck_top = self.deck_top;
+        state.deck_seed = self.deck_seed;
+        state.deck_index = self.deck_index;
+        state.player_cards = self.player_cards;
+        state.second_hand = self.second_hand;
+        state.dealer_cards = self.dealer_cards;
+        state.player_card_count = state.player_cards.len() as i32;
+        state.dealer_card_count = state.dealer_cards.len() as i32;
+        state.money = self.money;
+        state.current_round_start_money = self.round_start_money;
+        state.bet = self.bet;
+        state.free_bet = self.free_bet;
+        state.free_double_bet = self.free_double_bet;
+        state.was_double_down = self.was_double_down;
+        state.surrendered = self.surrendered;
+        state.was_switched = self.was_switched;
+        state.split_hands = self.split_hands;
+        state.active_split_hand = self.active_split_hand;
+        state.finished_hands = self.finished_hands;
+        state.side_bets = self.side_bets;
+        state.actions = self.actions;
+    }
+
+    /// Coins on the table: the main bet and any side bets
+    #[must_use]
+    pub fn stake(&self) -> i32 {
+        self.round_start_money - self.money
+    }
+}
+
+/// Write `snapshot` to `path`, replacing the previous one.
+///
+/// # Errors
+///
+/// Returns an error if the snapshot cannot be serialized or written.
+pub fn save_snapshot(snapshot: &RoundSnapshot, path: &Path) -> io::Result<()> {
+    let json = serde_json::to_string_pretty(snapshot)?;
+    safe_file::write_atomic(path, &json)
+}
+
+/// The snapshot at `path`, or `None` if no round was interrupted.
+///
+/// # Errors
+///
+/// Returns an error if the file exists but cannot be read or parsed.
+pub fn load_snapshot(path: &Path) -> io::Result<Option<RoundSnapshot>> {
+    if !path.exists() {
+        return Ok(None);
+    }
+    let content = fs::read_to_string(path)?;
+    Ok(Some(serde_json::from_str(&content)?))
+}
+
+/// Remove the snapshot at `path`, if there is one.
+///
+/// # Errors
+///
+/// Returns an error if the file exists but cannot be removed.
+pub fn clear_snapshot(path: &Path) -> io::Result<()> {
+    match fs::remove_file(path) {
+        Err(e) if e.kind() != io::ErrorKind::NotFound => Err(e),
+        _ => Ok(()),
+    }
+}
+
+/// Call off an interrupted round: the stakes still on the table go back to
+/// the bankroll in the save and the snapshot is removed. Hands already
+/// settled keep their results.
+///
+/// # Errors
+///
+/// Returns an error if the save cannot be loaded or written, in which case
+/// the snapshot is kept so the round can still be resumed.
+pub fn cancel_round(profile: &Profile, snapshot: &RoundSnapshot) -> Result<(), SaveError> {
+    let path = profile.save_path();
+    let mut save_data = save_system::read_save_data(&path)?.unwrap_or_default();
+    save_data.money = snapshot.round_start_money;
+    save_system::save_game_data(&save_data, &path)?;
+    clear_snapshot(&profile.round_path())?;
+    Ok(())
+}
+
+/// If a round of `profile` was interrupted, show it and ask whether to
+/// resume it. Returns the snapshot to resume; a cancelled round has its
+/// stake returned.
+#[must_use]
+pub fn offer_resume(profile: &Profile) -> Option<RoundSnapshot> {
+    let snapshot = match load_snapshot(&profile.round_path()) {
+        Ok(snapshot) => snapshot?,
+        Err(e) => {
+            eprintln!("⚠ Warning: Could not read your unfinished round: {e}");
+            return None;
+        }
+    };
+
+    let mut formatter = BoxFormatter::new(50, "UNFINISHED ROUND");
+    formatter.add_field("Table", &snapshot.rules.variant);
+    formatter.add_field("Coins on the table", &snapshot.stake());
+    if !snapshot.player_cards.is_empty() {
+        formatter.add_field("Your cards", &snapshot.player_cards.join(", "));
+    }
+    println!("\n{}", formatter.build());
+
+    loop {
+        print!("(r)esume the round or (c)ancel it and get your stake back? ");
+        io::stdout().flush().ok();
+        let answer = text_handler::read_menu_input().unwrap_or_default();
+        match answer.trim().to_lowercase().as_str() {
+            "r" => return Some(snapshot),
+            "c" => {
+                match cancel_round(profile, &snapshot) {
+                    Ok(()) => println!(
+                        "✓ Round cancelled. {} coins were returned to you.",
+                        snapshot.stake()
+                    ),
+                    Err(e) => eprintln!("✗ Could not cancel the round: {e}"),
+                }
+                return None;
+            }
+            _ => println!("Invalid option. Please try again."),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::table_rules::GameVariant;
+
+    fn temp_profile(test: &str) -> Profile {
+        let dir =
+            std::env::temp_dir().join(format!("round_recovery_{test}_{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        Profile {
+            name: test.to_string(),
+            dir,
+        }
+    }
+
+    /// A Blackjack Switch round with 2 x 10 coins bet from 100, dealt from a
+    /// stacked deck, with a card drawn to the first hand
+    fn round_in(phase: RoundPhase) -> GameState {
+        let mut state = GameState::with_money(80);
+        state.rules.variant = GameVariant::BlackjackSwitch;
+        state.deck_top = vec!["5 Hearts".to_string(), "6 Clubs".to_string()];
+        state.deck_seed = 42;
+        state.card_deck =
+            create_stacked_deck_for(state.rules.variant, &state.deck_top, state.deck_seed);
+        state.player_cards = vec![
+            state.card_deck[0].clone(),
+            state.card_deck[1].clone(),
+            state.card_deck[6].clone(),
+        ];
+        state.dealer_cards = state.card_deck[2..4].to_vec();
+        state.second_hand = state.card_deck[4..6].to_vec();
+        state.deck_index = 7;
+        state.current_round_start_money = 100;
+        state.bet = 10;
+        state.round_phase = phase;
+        state
+    }
+
+    #[test]
+    fn restored_round_matches_the_captured_one_in_every_phase() {
+        let profile = temp_profile("phases");
+        for phase in [
+            RoundPhase::SideBets,
+            RoundPhase::Switch,
+            RoundPhase::Playing(0),
+            RoundPhase::Playing(1),
+            RoundPhase::Dealer,
+            RoundPhase::Settling(1),
+        ] {
+            let state = round_in(phase);
+            save_snapshot(&RoundSnapshot::capture(&state), &profile.round_path()).unwrap();
+            let snapshot = load_snapshot(&profile.round_path()).unwrap().unwrap();
+            assert!(snapshot.card_deck.is_empty());
+
+            let mut restored = GameState::with_money(0);
+            snapshot.restore(&mut restored);
+            assert_eq!(restored.round_phase, phase);
+            assert_eq!(restored.rules.variant, GameVariant::BlackjackSwitch);
+            assert_eq!(restored.card_deck, state.card_deck, "{phase:?}");
+            assert_eq!(restored.deck_index, state.deck_index);
+            assert_eq!(restored.player_cards, state.player_cards);
+            assert_eq!(restored.second_hand, state.second_hand);
+            assert_eq!(restored.dealer_cards, state.dealer_cards);
+            assert_eq!(restored.player_card_count, 3);
+            assert_eq!(restored.bet, state.bet);
+            assert_eq!(restored.money, state.money);
+            assert_eq!(restored.current_round_start_money, 100);
+        }
+        fs::remove_dir_all(&profile.dir).unwrap();
+    }
+
+    #[test]
+    fn snapshot_keeps_the_deck_as_its_seed() {
+        let state = round_in(RoundPhase::Playing(0));
+        let json = serde_json::to_string(&RoundSnapshot::capture(&state)).unwrap();
+        assert!(!json.contains("card_deck"));
+        // Only cards already on the table are named
+        assert!(!json.contains(&state.card_deck[7]));
+
+        // Older snapshots that listed the whole deck still restore it as is
+        let mut legacy: serde_json::Value = serde_json::from_str(&json).unwrap();
+        legacy["card_deck"] = serde_json::json!(["2 Hearts", "3 Hearts"]);
+        let snapshot: RoundSnapshot = serde_json::from_value(legacy).unwrap();
+        let mut restored = GameState::with_money(0);
+        snapshot.restore(&mut restored);
+        assert_eq!(restored.card_deck, ["2 Hearts", "3 Hearts"]);
+    }
+
+    #[test]
+    fn cancel_round_returns_the_stake_and_removes_the_snapshot() {
+        let profile = temp_profile("cancel");
+        let path = profile.save_path();
+        let save = save_system::create_save_data(80, 3, 4, TableRules::new());
+        save_system::save_game_data(&save, &path).unwrap();
+        let snapshot = RoundSnapshot::capture(&round_in(RoundPhase::Playing(0)));
+        save_snapshot(&snapshot, &profile.round_path()).unwrap();
+        assert_eq!(snapshot.stake(), 20);
+
+        cancel_round(&profile, &snapshot).unwrap();
+        let save = save_system::read_save_data(&path).unwrap().unwrap();
+        assert_eq!(save.money, 100);
+        assert_eq!((save.games_won, save.games_lost), (3, 4));
+        assert!(load_snapshot(&profile.round_path()).unwrap().is_none());
+        fs::remove_dir_all(&profile.dir).unwrap();
+    }
+}
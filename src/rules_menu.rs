@@ -0,0 +1,101 @@
+use crate::formatting::BoxFormatter;
+use crate::side_bets::SideBetKind;
+use crate::table_rules::{GameVariant, TableRules};
+use crate::text_handler;
+use std::io::{self, Write};
+
+pub struct RulesMenu<'a> {
+    rules: &'a mut TableRules,
+}
+
+impl<'a> RulesMenu<'a> {
+    #[must_use]
+    pub fn new(rules: &'a mut TableRules) -> Self {
+        RulesMenu { rules }
+    }
+
+    pub fn show_menu(&mut self) {
+        loop {
+            self.print_rules_menu();
+            let Ok(input) = text_handler::read_menu_input() else {
+                break;
+            };
+
+            match input.trim().to_lowercase().as_str() {
+                "v" => self.choose_variant(),
+                "p" => Self::show_pay_tables(),
+                "b" | "back" => break,
+                choice => match choice.parse::<usize>() {
+                    Ok(n) if (1..=SideBetKind::ALL.len()).contains(&n) => {
+                        self.rules.toggle_side_bet(SideBetKind::ALL[n - 1]);
+                    }
+                    _ => println!("Invalid option. Please try again."),
+                },
+            }
+        }
+    }
+
+    fn print_rules_menu(&self) {
+        let mut formatter = BoxFormatter::new(50, "TABLE RULES");
+        formatter.add_field_aligned("Game variant", &self.rules.variant);
+        formatter.add_empty_line();
+        formatter.add_line("Side bets:");
+        for (i, kind) in SideBetKind::ALL.iter().enumerate() {
+            let status = if self.rules.is_side_bet_enabled(*kind) {
+                "ON"
+            } else {
+                "OFF"
+            };
+            formatter.add_field_aligned(&format!("({}) {kind}", i + 1), &status);
+        }
+        formatter.add_empty_line();
+        formatter.add_line("(v) Change game variant");
+        formatter.add_line("(p) Show side bet pay tables");
+        formatter.add_line("(b) Back to main menu");
+
+        println!("\n{}", formatter.build());
+        print!("Choose an option: ");
+        io::stdout().flush().ok();
+    }
+
+    fn choose_variant(&mut self) {
+        let mut formatter = BoxFormatter::new(60, "GAME VARIANTS");
+        for (i, variant) in GameVariant::ALL.iter().enumerate() {
+            formatter.add_line(&format!("({}) {variant}", i + 1));
+            formatter.add_line(&format!("    {}", variant.description()));
+        }
+        println!("\n{}", formatter.build());
+        print!("Choose a variant: ");
+        io::stdout().flush().ok();
+
+        let Ok(input) = text_handler::read_menu_input() else {
+            return;
+        };
+        match input.parse::<usize>() {
+            Ok(n) if (1..=GameVariant::ALL.len()).contains(&n) => {
+                self.rules.variant = GameVariant::ALL[n - 1];
+                println!("✓ Table now deals {}.", self.rules.variant);
+            }
+            _ => println!("Invalid variant. Keeping {}.", self.rules.variant),
+        }
+    }
+
+    fn show_pay_tables() {
+        for kind in SideBetKind::ALL {
+            let side_bet = kind.side_bet();
+            let mut formatter = BoxFormatter::new(60, side_bet.name());
+            for line in side_bet.pay_table() {
+                formatter.add_field_aligned(
+                    line.label,
+                    &format!("{}:{}", line.numerator, line.denominator),
+                );
+            }
+            println!("\n{}", formatter.build());
+        }
+    }
+}
+
+pub fn show_rules_menu(rules: &mut TableRules) {
+    let mut menu = RulesMenu::new(rules);
+    menu.show_menu();
+}
@@ -0,0 +1,222 @@
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use std::fmt;
+use std::fs::{self, File, OpenOptions, TryLockError};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+
+/// Number of rotating backups kept next to each file
+pub const BACKUPS_KEPT: usize = 3;
+
+fn sibling(path: &Path, suffix: &str) -> PathBuf {
+    let mut name = path.file_name().unwrap_or_default().to_os_string();
+    name.push(suffix);
+    path.with_file_name(name)
+}
+
+/// Path of backup `n`, where backup 1 is the newest
+#[must_use]
+pub fn backup_path(path: &Path, n: usize) -> PathBuf {
+    sibling(path, &format!(".bak{n}"))
+}
+
+/// Replace `path` with `contents` so that a crash leaves either the old or
+/// the new file, never a partial one: write a temporary file, flush it to
+/// disk, then rename it over the original.
+///
+/// # Errors
+///
+/// Returns an error if the temporary file cannot be written or renamed.
+pub fn write_atomic(path: &Path, contents: &str) -> io::Result<()> {
+    let dir = path.parent().filter(|dir| !dir.as_os_str().is_empty());
+    if let Some(dir) = dir {
+        fs::create_dir_all(dir)?;
+    }
+
+    let tmp = sibling(path, ".tmp");
+    let mut file = File::create(&tmp)?;
+    file.write_all(contents.as_bytes())?;
+    file.sync_all()?;
+    drop(file);
+    fs::rename(&tmp, path)?;
+
+    // Persist the rename itself; not every platform can open a directory
+    if let Some(dir) = dir {
+        if let Ok(dir) = File::open(dir) {
+            dir.sync_all().ok();
+        }
+    }
+    Ok(())
+}
+
+/// Shift the existing backups of `path` down by one and copy the current
+/// file into backup 1, dropping the oldest.
+///
+/// # Errors
+///
+/// Returns an error if a backup cannot be moved or copied.
+pub fn rotate_backups(path: &Path) -> io::Result<()> {
+    if !path.is_file() {
+        return Ok(());
+    }
+    for n in (1..BACKUPS_KEPT).rev() {
+        let older = backup_path(path, n);
+        if older.exists() {
+            fs::rename(&older, backup_path(path, n + 1))?;
+        }
+    }
+    fs::copy(path, backup_path(path, 1))?;
+    Ok(())
+}
+
+/// Back up the current file, then atomically replace it with `contents`.
+///
+/// # Errors
+///
+/// Returns an error if the backups cannot be rotated or the file cannot be
+/// written.
+pub fn write_with_backups(path: &Path, contents: &str) -> io::Result<()> {
+    rotate_backups(path)?;
+    write_atomic(path, contents)
+}
+
+/// Read and parse `path`, or `None` if it does not exist. If the file is
+/// damaged (it fails to parse and `is_damage` agrees, or it is not valid
+/// text), the newest backup that parses is restored in its place and the
+/// damaged file is kept as `<name>.corrupt`.
+///
+/// # Errors
+///
+/// Returns the original error if the file cannot be read, or is damaged and
+/// no backup can be parsed either.
+pub fn read_with_recovery<T, E>(
+    path: &Path,
+    parse: impl Fn(&str) -> Result<T, E>,
+    is_damage: impl Fn(&E) -> bool,
+) -> Result<Option<T>, E>
+where
+    E: From<io::Error> + fmt::Display,
+{
+    if !path.exists() {
+        return Ok(None);
+    }
+
+    let error = match fs::read_to_string(path) {
+        Ok(content) => match parse(&content) {
+            Ok(value) => return Ok(Some(value)),
+            Err(e) if is_damage(&e) => e,
+            Err(e) => return Err(e),
+        },
+        Err(e) if e.kind() == io::ErrorKind::InvalidData => E::from(e),
+        Err(e) => return Err(E::from(e)),
+    };
+
+    for n in 1..=BACKUPS_KEPT {
+        let backup = backup_path(path, n);
+        let Ok(content) = fs::read_to_string(&backup) else {
+            continue;
+        };
+        let Ok(value) = parse(&content) else {
+            continue;
+        };
+
+        let corrupt = sibling(path, ".corrupt");
+        fs::rename(path, &corrupt)?;
+        write_atomic(path, &content)?;
+        eprintln!(
+            "⚠ {} was damaged ({error}). Restored it from {}; the damaged copy is kept as {}.",
+            path.display(),
+            backup.display(),
+            corrupt.display()
+        );
+        return Ok(Some(value));
+    }
+    Err(error)
+}
+
+/// Read the JSON value stored at `path`, or `None` if there is no file
+/// yet. A file that is not valid JSON for `T` is treated as damaged and
+/// restored from its newest good backup.
+///
+/// # Errors
+///
+/// Returns an error if the file cannot be read, or is damaged and no backup
+/// can be parsed either.
+pub fn read_json<T: DeserializeOwned>(path: &Path) -> io::Result<Option<T>> {
+    read_with_recovery(
+        path,
+        |content| serde_json::from_str(content).map_err(io::Error::from),
+        |e| {
+            matches!(
+                e.kind(),
+                io::ErrorKind::InvalidData | io::ErrorKind::UnexpectedEof
+            )
+        },
+    )
+}
+
+/// Store `value` at `path` as JSON, keeping backups for `read_json` to
+/// recover from.
+///
+/// # Errors
+///
+/// Returns an error if the value cannot be serialized or the file cannot be
+/// written.
+pub fn write_json<T: Serialize>(path: &Path, value: &T) -> io::Result<()> {
+    write_with_backups(path, &serde_json::to_string_pretty(value)?)
+}
+
+/// An exclusive lock on a file, held until dropped. Used so two running
+/// games cannot write the same profile.
+#[derive(Debug)]
+pub struct FileLock {
+    _file: File,
+}
+
+impl FileLock {
+    /// Take the lock at `path`, creating the file if needed.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error of kind `WouldBlock` if another process holds the
+    /// lock, or any error from creating the lock file.
+    pub fn try_acquire(path: &Path) -> io::Result<Self> {
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        let file = OpenOptions::new()
+            .create(true)
+            .truncate(false)
+            .write(true)
+            .open(path)?;
+        match file.try_lock() {
+            Ok(()) => Ok(FileLock { _file: file }),
+            Err(TryLockError::WouldBlock) => Err(io::Error::new(
+                io::ErrorKind::WouldBlock,
+                "it is in use by another running game",
+            )),
+            Err(TryLockError::Error(e)) => Err(e),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn read_json_restores_a_damaged_file_from_its_backup() {
+        let dir = std::env::temp_dir().join(format!("safe_file_test_{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("book.json");
+
+        assert_eq!(read_json::<Vec<u32>>(&path).unwrap(), None);
+        write_json(&path, &vec![1, 2]).unwrap();
+        write_json(&path, &vec![1, 2, 3]).unwrap();
+        fs::write(&path, "[1, 2,").unwrap();
+
+        assert_eq!(read_json::<Vec<u32>>(&path).unwrap(), Some(vec![1, 2]));
+        assert_eq!(read_json::<Vec<u32>>(&path).unwrap(), Some(vec![1, 2]));
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
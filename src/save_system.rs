@@ -1,80 +1,336 @@
+use crate::profiles::SAVE_FILE_NAME;
+use crate::safe_file;
+use crate::table_rules::TableRules;
 use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::fmt;
 use std::fs;
+use std::io;
 use std::path::Path;
 
-const SAVE_FILE: &str = "blackjack_save.json";
 const DEFAULT_STARTING_MONEY: i32 = 100;
 
 pub const STARTING_MONEY: i32 = DEFAULT_STARTING_MONEY;
 
+/// Version of the save format written by this build. Files without a
+/// `version` field are version 1.
+pub const CURRENT_SAVE_VERSION: u32 = 3;
+
+/// Upgrades applied in order; `MIGRATIONS[i]` turns version `i + 1` into `i + 2`
+const MIGRATIONS: [fn(&mut Map<String, Value>); 2] = [migrate_v1_to_v2, migrate_v2_to_v3];
+
 #[derive(Serialize, Deserialize, Clone, Debug)]
 pub struct SaveData {
+    pub version: u32,
     pub money: i32,
     pub games_won: i32,
     pub games_lost: i32,
+    /// Table rules the player last chose
+    pub settings: TableRules,
+    /// Fields written by other versions of the game, kept so saving does not drop them
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
 }
 
 impl Default for SaveData {
     fn default() -> Self {
         SaveData {
+            version: CURRENT_SAVE_VERSION,
             money: DEFAULT_STARTING_MONEY,
             games_won: 0,
             games_lost: 0,
+            settings: TableRules::new(),
+            extra: Map::new(),
         }
     }
 }
 
-#[must_use]
-pub fn load_save_data() -> SaveData {
-    if Path::new(SAVE_FILE).exists() {
-        match fs::read_to_string(SAVE_FILE) {
-            Ok(content) => match serde_json::from_str::<SaveData>(&content) {
-                Ok(save_data) => {
-                    println!(
-                        "✓ Loaded previous game data: {} coins, {} wins, {} losses",
-                        save_data.money, save_data.games_won, save_data.games_lost
-                    );
-                    save_data
-                }
-                Err(e) => {
-                    eprintln!("⚠ Warning: Could not parse save file ({e}). Starting fresh.");
-                    SaveData::default()
-                }
-            },
-            Err(e) => {
-                eprintln!("⚠ Warning: Could not read save file ({e}). Starting fresh.");
-                SaveData::default()
+/// Why a save file could not be loaded or written
+#[derive(Debug)]
+pub enum SaveError {
+    Io(io::Error),
+    Parse(serde_json::Error),
+    /// The file was written by a newer version of the game
+    UnsupportedVersion(u32),
+    /// The file is valid JSON but not a save object
+    NotAnObject,
+}
+
+impl fmt::Display for SaveError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SaveError::Io(e) => write!(f, "could not access {SAVE_FILE_NAME}: {e}"),
+            SaveError::Parse(e) => write!(f, "{SAVE_FILE_NAME} is damaged: {e}"),
+            SaveError::UnsupportedVersion(version) => write!(
+                f,
+                "{SAVE_FILE_NAME} uses save format v{version}, but this build only understands up to v{CURRENT_SAVE_VERSION}"
+            ),
+            SaveError::NotAnObject => {
+                write!(f, "{SAVE_FILE_NAME} does not contain a save object")
             }
         }
-    } else {
+    }
+}
+
+impl SaveError {
+    /// Whether the file itself is damaged, as opposed to unreadable or from a
+    /// newer version
+    #[must_use]
+    pub fn is_damage(&self) -> bool {
+        matches!(self, SaveError::Parse(_) | SaveError::NotAnObject)
+    }
+}
+
+impl std::error::Error for SaveError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            SaveError::Io(e) => Some(e),
+            SaveError::Parse(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for SaveError {
+    fn from(e: io::Error) -> Self {
+        SaveError::Io(e)
+    }
+}
+
+impl From<serde_json::Error> for SaveError {
+    fn from(e: serde_json::Error) -> Self {
+        SaveError::Parse(e)
+    }
+}
+
+/// Load the save file, upgrading older formats to the current version.
+///
+/// # Errors
+///
+/// Returns an error if the file exists but cannot be read, is not a valid
+/// save, or was written by a newer version. The file is never replaced with
+/// a fresh bankroll in that case.
+pub fn load_save_data(path: &Path) -> Result<SaveData, SaveError> {
+    let Some(save_data) = read_save_data(path)? else {
         println!("No previous save found. Starting new game!");
-        SaveData::default()
+        return Ok(SaveData::default());
+    };
+    println!(
+        "✓ Loaded previous game data: {} coins, {} wins, {} losses",
+        save_data.money, save_data.games_won, save_data.games_lost
+    );
+    Ok(save_data)
+}
+
+/// Read the save file at `path`, or `None` if there is no save yet. A
+/// damaged file is restored from the newest valid backup.
+///
+/// # Errors
+///
+/// Returns an error if the file exists but neither it nor any backup can be
+/// loaded.
+pub fn read_save_data(path: &Path) -> Result<Option<SaveData>, SaveError> {
+    safe_file::read_with_recovery(path, parse_save_data, SaveError::is_damage)
+}
+
+/// Parse save file contents of any supported version.
+///
+/// # Errors
+///
+/// Returns an error if the contents are not a valid save or come from a
+/// newer version of the game.
+pub fn parse_save_data(content: &str) -> Result<SaveData, SaveError> {
+    let Value::Object(mut fields) = serde_json::from_str(content)? else {
+        return Err(SaveError::NotAnObject);
+    };
+    migrate(&mut fields)?;
+    Ok(serde_json::from_value(Value::Object(fields))?)
+}
+
+fn save_version(fields: &Map<String, Value>) -> Result<u32, SaveError> {
+    match fields.get("version") {
+        None => Ok(1),
+        Some(version) => {
+            let version = serde_json::from_value::<u32>(version.clone())?;
+            Ok(version)
+        }
     }
 }
 
-/// Save game data to file.
+/// Upgrade save fields to `CURRENT_SAVE_VERSION`, one version at a time
+#[allow(clippy::cast_possible_truncation)]
+fn migrate(fields: &mut Map<String, Value>) -> Result<(), SaveError> {
+    let version = save_version(fields)?;
+    if version == 0 || version > CURRENT_SAVE_VERSION {
+        return Err(SaveError::UnsupportedVersion(version));
+    }
+
+    for (i, migration) in MIGRATIONS.iter().enumerate().skip(version as usize - 1) {
+        migration(fields);
+        fields.insert("version".to_string(), Value::from(i as u32 + 2));
+    }
+    Ok(())
+}
+
+/// Version 1 files were the three counters with no version field
+fn migrate_v1_to_v2(_fields: &mut Map<String, Value>) {}
+
+/// Version 3 stores the player's table rules
+fn migrate_v2_to_v3(fields: &mut Map<String, Value>) {
+    fields
+        .entry("settings")
+        .or_insert_with(|| serde_json::to_value(TableRules::new()).unwrap_or_default());
+}
+
+/// Save game data to file, keeping the previous versions as backups. Fields
+/// in the existing file that this version does not know about are carried
+/// over.
 ///
 /// # Errors
 ///
 /// Returns an error if serialization or file writing fails.
-pub fn save_game_data(save_data: &SaveData) -> Result<(), Box<dyn std::error::Error>> {
-    let json_data = serde_json::to_string_pretty(save_data)?;
-    fs::write(SAVE_FILE, json_data)?;
+pub fn save_game_data(save_data: &SaveData, path: &Path) -> Result<(), SaveError> {
+    let mut value = serde_json::to_value(save_data)?;
+    if let (Some(fields), Ok(existing)) = (value.as_object_mut(), read_existing_fields(path)) {
+        for (key, field) in existing {
+            fields.entry(key).or_insert(field);
+        }
+    }
+
+    let json_data = serde_json::to_string_pretty(&value)?;
+    safe_file::write_with_backups(path, &json_data)?;
     println!("✓ Game progress saved!");
     Ok(())
 }
 
-pub fn auto_save(save_data: &SaveData) {
-    if let Err(e) = save_game_data(save_data) {
+fn read_existing_fields(path: &Path) -> Result<Map<String, Value>, SaveError> {
+    let content = fs::read_to_string(path)?;
+    match serde_json::from_str(&content)? {
+        Value::Object(fields) => Ok(fields),
+        _ => Err(SaveError::NotAnObject),
+    }
+}
+
+pub fn auto_save(save_data: &SaveData, path: &Path) {
+    if let Err(e) = save_game_data(save_data, path) {
         eprintln!("⚠ Warning: Could not save game data: {e}");
     }
 }
 
+/// Store new table rules in the save at `path`, keeping everything else.
+///
+/// # Errors
+///
+/// Returns an error if the existing save cannot be loaded or the file
+/// cannot be written.
+pub fn save_settings(settings: &TableRules, path: &Path) -> Result<(), SaveError> {
+    let mut save_data = read_save_data(path)?.unwrap_or_default();
+    save_data.settings = settings.clone();
+    save_game_data(&save_data, path)
+}
+
 #[must_use]
-pub fn create_save_data(money: i32, games_won: i32, games_lost: i32) -> SaveData {
+pub fn create_save_data(
+    money: i32,
+    games_won: i32,
+    games_lost: i32,
+    settings: TableRules,
+) -> SaveData {
     SaveData {
         money,
         games_won,
         games_lost,
+        settings,
+        ..SaveData::default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::side_bets::SideBetKind;
+    use crate::table_rules::GameVariant;
+
+    const V1_SAVE: &str = r#"{"money": 250, "games_won": 7, "games_lost": 3}"#;
+    const V2_SAVE: &str = r#"{"version": 2, "money": 40, "games_won": 1, "games_lost": 9}"#;
+    const V3_SAVE_WITH_UNKNOWN_FIELDS: &str = r#"{
+        "version": 3,
+        "money": 180,
+        "games_won": 12,
+        "games_lost": 10,
+        "settings": {"variant": "Pontoon", "side_bets": ["LuckyLadies"]},
+        "nickname": "Ace",
+        "cosmetics": {"table_felt": "blue", "unlocked": [1, 2]}
+    }"#;
+
+    fn temp_save(test: &str) -> std::path::PathBuf {
+        let dir = std::env::temp_dir().join(format!("save_system_{test}_{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        dir.join(SAVE_FILE_NAME)
+    }
+
+    #[test]
+    fn version_1_save_migrates_with_default_settings() {
+        let save = parse_save_data(V1_SAVE).unwrap();
+        assert_eq!(save.version, CURRENT_SAVE_VERSION);
+        assert_eq!((save.money, save.games_won, save.games_lost), (250, 7, 3));
+        assert_eq!(save.settings, TableRules::new());
+        assert!(save.extra.is_empty());
+    }
+
+    #[test]
+    fn version_2_save_migrates_with_default_settings() {
+        let save = parse_save_data(V2_SAVE).unwrap();
+        assert_eq!(save.version, CURRENT_SAVE_VERSION);
+        assert_eq!((save.money, save.games_won, save.games_lost), (40, 1, 9));
+        assert_eq!(save.settings, TableRules::new());
+    }
+
+    #[test]
+    fn unknown_fields_survive_a_load_and_save() {
+        let path = temp_save("unknown_fields");
+        fs::write(&path, V3_SAVE_WITH_UNKNOWN_FIELDS).unwrap();
+
+        let mut save = read_save_data(&path).unwrap().unwrap();
+        assert_eq!(save.settings.variant, GameVariant::Pontoon);
+        assert_eq!(save.settings.side_bets, [SideBetKind::LuckyLadies]);
+        assert_eq!(save.extra["nickname"], "Ace");
+        save.money = 200;
+        save_game_data(&save, &path).unwrap();
+
+        // A save built from scratch keeps the file's unknown fields too
+        let fresh = create_save_data(210, 13, 10, save.settings.clone());
+        save_game_data(&fresh, &path).unwrap();
+
+        let written: Value = serde_json::from_str(&fs::read_to_string(&path).unwrap()).unwrap();
+        assert_eq!(written["money"], 210);
+        assert_eq!(written["nickname"], "Ace");
+        assert_eq!(
+            written["cosmetics"],
+            serde_json::json!({"table_felt": "blue", "unlocked": [1, 2]})
+        );
+        fs::remove_dir_all(path.parent().unwrap()).unwrap();
+    }
+
+    #[test]
+    fn newer_save_is_refused_and_left_untouched() {
+        let newer = r#"{"version": 4, "money": 5000, "games_won": 90, "games_lost": 80}"#;
+        assert!(matches!(
+            parse_save_data(newer),
+            Err(SaveError::UnsupportedVersion(4))
+        ));
+
+        let path = temp_save("newer");
+        fs::write(&path, newer).unwrap();
+        assert!(matches!(
+            load_save_data(&path),
+            Err(SaveError::UnsupportedVersion(4))
+        ));
+        assert!(matches!(
+            save_settings(&TableRules::new(), &path),
+            Err(SaveError::UnsupportedVersion(4))
+        ));
+        assert_eq!(fs::read_to_string(&path).unwrap(), newer);
+        fs::remove_dir_all(path.parent().unwrap()).unwrap();
     }
 }
@@ -0,0 +1,655 @@
+use crate::deck_manager::{create_deck, create_deck_for};
+use crate::formatting::{format_money, BoxFormatter};
+use crate::game_loop;
+use crate::game_state::GameState;
+use crate::table_rules::GameVariant;
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+const SUITS: [&str; 4] = ["Hearts", "Diamonds", "Clubs", "Spades"];
+
+/// Width of the boxes a scenario is shown in
+const BOX_WIDTH: usize = 60;
+
+/// Something a scenario asks the player to achieve
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Goal {
+    /// Win at least this many hands
+    WinHands(u32),
+    /// Finish with at least this many coins
+    EndWithAtLeast(i32),
+}
+
+impl Goal {
+    /// Read a goal written as "win 3 hands", "end with >= 200" or
+    /// "end with at least 200"
+    #[must_use]
+    pub fn parse(text: &str) -> Option<Self> {
+        let text = text.to_lowercase().replace('≥', ">=").replace(">=", " >= ");
+        let words: Vec<&str> = text.split_whitespace().collect();
+        match words.as_slice() {
+            ["win", count, "hand" | "hands"] => {
+                count.parse().ok().filter(|&n| n > 0).map(Goal::WinHands)
+            }
+            ["end", "with", ">=", coins] | ["end", "with", "at", "least", coins] => {
+                coins.parse().ok().map(Goal::EndWithAtLeast)
+            }
+            _ => None,
+        }
+    }
+
+    /// Whether the goal has been reached at the table `state`
+    #[must_use]
+    pub fn is_met(self, state: &GameState) -> bool {
+        match self {
+            Goal::WinHands(count) => state.history.total_wins >= count,
+            Goal::EndWithAtLeast(coins) => state.money >= coins,
+        }
+    }
+}
+
+impl fmt::Display for Goal {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Goal::WinHands(1) => write!(f, "Win 1 hand"),
+            Goal::WinHands(count) => write!(f, "Win {count} hands"),
+            Goal::EndWithAtLeast(coins) => write!(f, "End with at least {coins} coins"),
+        }
+    }
+}
+
+/// Why a scenario file could not be used
+#[derive(Debug)]
+pub enum ScenarioError {
+    Io(io::Error),
+    /// A line that is neither blank, a comment nor `key: value`
+    NotKeyValue(usize),
+    UnknownKey {
+        line: usize,
+        key: String,
+    },
+    /// A key that may only be given once, given again
+    RepeatedKey {
+        line: usize,
+        key: String,
+    },
+    InvalidBankroll {
+        line: usize,
+        value: String,
+    },
+    UnknownVariant {
+        line: usize,
+        value: String,
+    },
+    InvalidGoal {
+        line: usize,
+        value: String,
+    },
+    InvalidCard {
+        line: usize,
+        card: String,
+    },
+    /// A card the variant's deck does not have, such as a ten in Spanish 21
+    CardNotInDeck {
+        line: usize,
+        card: String,
+        variant: GameVariant,
+    },
+    /// The same card twice in one hand, which is dealt from a single deck
+    RepeatedCard {
+        line: usize,
+        card: String,
+    },
+    /// A hand without enough cards for the opening deal
+    TooFewCards {
+        line: usize,
+        found: usize,
+        needed: usize,
+    },
+    /// A required key that is not in the file
+    Missing(&'static str),
+}
+
+impl fmt::Display for ScenarioError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ScenarioError::Io(e) => write!(f, "{e}"),
+            ScenarioError::NotKeyValue(line) => {
+                write!(f, "line {line}: expected \"key: value\"")
+            }
+            ScenarioError::UnknownKey { line, key } => write!(
+                f,
+                "line {line}: unknown key \"{key}\" (use name, description, bankroll, variant, goal or hand)"
+            ),
+            ScenarioError::RepeatedKey { line, key } => {
+                write!(f, "line {line}: \"{key}\" is given more than once")
+            }
+            ScenarioError::InvalidBankroll { line, value } => write!(
+                f,
+                "line {line}: bankroll \"{value}\" is not a positive number of coins"
+            ),
+            ScenarioError::UnknownVariant { line, value } => {
+                let names: Vec<String> = GameVariant::ALL.iter().map(ToString::to_string).collect();
+                write!(
+                    f,
+                    "line {line}: unknown variant \"{value}\" (use one of {})",
+                    names.join(", ")
+                )
+            }
+            ScenarioError::InvalidGoal { line, value } => write!(
+                f,
+                "line {line}: goal \"{value}\" is not understood (use \"win N hands\" or \"end with >= N\")"
+            ),
+            ScenarioError::InvalidCard { line, card } => write!(
+                f,
+                "line {line}: \"{card}\" is not a card (write cards like \"A Hearts\", \"10 Clubs\" or \"Qs\")"
+            ),
+            ScenarioError::CardNotInDeck {
+                line,
+                card,
+                variant,
+            } => write!(f, "line {line}: {variant} is played without the {card}"),
+            ScenarioError::RepeatedCard { line, card } => {
+                write!(f, "line {line}: the {card} is dealt twice in one hand")
+            }
+            ScenarioError::TooFewCards {
+                line,
+                found,
+                needed,
+            } => write!(
+                f,
+                "line {line}: a hand needs at least {needed} cards for the deal, found {found}"
+            ),
+            ScenarioError::Missing(key) => write!(f, "no \"{key}\" line"),
+        }
+    }
+}
+
+impl std::error::Error for ScenarioError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            ScenarioError::Io(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for ScenarioError {
+    fn from(e: io::Error) -> Self {
+        ScenarioError::Io(e)
+    }
+}
+
+/// A practice situation: a stacked shoe, a starting bankroll and the goals
+/// to reach with them.
+///
+/// Scenario files hold one `key: value` per line; blank lines and lines
+/// starting with `#` are skipped. `bankroll`, at least one `goal` and at
+/// least one `hand` are required; `name`, `description` and `variant`
+/// (Classic by default) are optional. Each `hand` lists, separated by
+/// commas, the cards of one round in the order they are dealt: the
+/// player's two cards, the dealer's upcard and hole card, the second hand
+/// in Blackjack Switch, then every card drawn. Cards left to draw once the
+/// list runs out come from a shuffled deck.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Scenario {
+    pub name: String,
+    pub description: String,
+    pub bankroll: i32,
+    pub variant: GameVariant,
+    pub goals: Vec<Goal>,
+    /// Card order of each round, in play order
+    pub hands: Vec<Vec<String>>,
+}
+
+impl Scenario {
+    /// Read a scenario from the contents of a scenario file, named
+    /// `default_name` if it has no `name` line.
+    ///
+    /// # Errors
+    ///
+    /// Returns the first line that cannot be read, or the first rule the
+    /// scenario breaks.
+    pub fn parse(content: &str, default_name: &str) -> Result<Self, ScenarioError> {
+        let mut name = None;
+        let mut description = None;
+        let mut bankroll = None;
+        let mut variant = None;
+        let mut goals = Vec::new();
+        let mut hands: Vec<(usize, Vec<String>)> = Vec::new();
+
+        for (i, line) in content.lines().enumerate() {
+            let line_number = i + 1;
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let (key, value) = line
+                .split_once(':')
+                .ok_or(ScenarioError::NotKeyValue(line_number))?;
+            let key = key.trim().to_lowercase();
+            let value = value.trim();
+            match key.as_str() {
+                "name" => set_once(&mut name, value.to_string(), line_number, &key)?,
+                "description" => {
+                    set_once(&mut description, value.to_string(), line_number, &key)?;
+                }
+                "bankroll" => {
+                    let coins = value
+                        .parse()
+                        .ok()
+                        .filter(|&coins: &i32| coins > 0)
+                        .ok_or_else(|| ScenarioError::InvalidBankroll {
+                            line: line_number,
+                            value: value.to_string(),
+                        })?;
+                    set_once(&mut bankroll, coins, line_number, &key)?;
+                }
+                "variant" => {
+                    let found = GameVariant::ALL
+                        .into_iter()
+                        .find(|variant| variant.to_string().eq_ignore_ascii_case(value))
+                        .ok_or_else(|| ScenarioError::UnknownVariant {
+                            line: line_number,
+                            value: value.to_string(),
+                        })?;
+                    set_once(&mut variant, found, line_number, &key)?;
+                }
+                "goal" => {
+                    let goal = Goal::parse(value).ok_or_else(|| ScenarioError::InvalidGoal {
+                        line: line_number,
+                        value: value.to_string(),
+                    })?;
+                    goals.push(goal);
+                }
+                "hand" => {
+                    let cards = value
+                        .split(',')
+                        .map(|card| {
+                            parse_card(card).ok_or_else(|| ScenarioError::InvalidCard {
+                                line: line_number,
+                                card: card.trim().to_string(),
+                            })
+                        })
+                        .collect::<Result<Vec<_>, _>>()?;
+                    hands.push((line_number, cards));
+                }
+                _ => {
+                    return Err(ScenarioError::UnknownKey {
+                        line: line_number,
+                        key,
+                    })
+                }
+            }
+        }
+
+        let bankroll = bankroll.ok_or(ScenarioError::Missing("bankroll"))?;
+        if goals.is_empty() {
+            return Err(ScenarioError::Missing("goal"));
+        }
+        if hands.is_empty() {
+            return Err(ScenarioError::Missing("hand"));
+        }
+        let variant = variant.unwrap_or_default();
+        for (line, cards) in &hands {
+            check_hand(*line, cards, variant)?;
+        }
+
+        Ok(Scenario {
+            name: name.unwrap_or_else(|| default_name.to_string()),
+            description: description.unwrap_or_default(),
+            bankroll,
+            variant,
+            goals,
+            hands: hands.into_iter().map(|(_, cards)| cards).collect(),
+        })
+    }
+
+    /// The scenario's name, description, table and goals in a box
+    #[must_use]
+    pub fn summary(&self) -> String {
+        let mut formatter = BoxFormatter::new(BOX_WIDTH, "SCENARIO");
+        formatter.add_line(&self.name);
+        if !self.description.is_empty() {
+            formatter.add_separator();
+            for line in wrap(&self.description, BOX_WIDTH - 4) {
+                formatter.add_line(&line);
+            }
+        }
+        formatter.add_separator();
+        formatter.add_field_aligned("Table", &self.variant);
+        formatter.add_field_aligned("Bankroll", &self.bankroll);
+        formatter.add_field_aligned("Hands", &self.hands.len());
+        formatter.add_empty_line();
+        formatter.add_line("Goals:");
+        for goal in &self.goals {
+            formatter.add_line(&format!("  - {goal}"));
+        }
+        formatter.build()
+    }
+}
+
+fn set_once<T>(
+    slot: &mut Option<T>,
+    value: T,
+    line: usize,
+    key: &str,
+) -> Result<(), ScenarioError> {
+    if slot.is_some() {
+        return Err(ScenarioError::RepeatedKey {
+            line,
+            key: key.to_string(),
+        });
+    }
+    *slot = Some(value);
+    Ok(())
+}
+
+/// Read a card written as the deck names it ("10 Hearts", any case) or in
+/// short, as its rank and the first letter of its suit ("10h", "QS")
+fn parse_card(text: &str) -> Option<String> {
+    let text = text.trim();
+    let (rank, suit) = match text.split_once(char::is_whitespace) {
+        Some((rank, suit)) => (rank, suit.trim()),
+        None => text.split_at_checked(text.len().checked_sub(1)?)?,
+    };
+    let suit = SUITS.into_iter().find(|name| {
+        name.eq_ignore_ascii_case(suit) || (suit.len() == 1 && name[..1].eq_ignore_ascii_case(suit))
+    })?;
+    let card = format!("{} {suit}", rank.to_uppercase());
+    create_deck().contains(&card).then_some(card)
+}
+
+/// Check that the cards of the hand on `line` can be dealt at a `variant`
+/// table
+fn check_hand(line: usize, cards: &[String], variant: GameVariant) -> Result<(), ScenarioError> {
+    let deck = create_deck_for(variant);
+    let needed = if variant == GameVariant::BlackjackSwitch {
+        6
+    } else {
+        4
+    };
+    if cards.len() < needed {
+        return Err(ScenarioError::TooFewCards {
+            line,
+            found: cards.len(),
+            needed,
+        });
+    }
+    for (i, card) in cards.iter().enumerate() {
+        if !deck.contains(card) {
+            return Err(ScenarioError::CardNotInDeck {
+                line,
+                card: card.clone(),
+                variant,
+            });
+        }
+        if cards[..i].contains(card) {
+            return Err(ScenarioError::RepeatedCard {
+                line,
+                card: card.clone(),
+            });
+        }
+    }
+    Ok(())
+}
+
+/// Split `text` into lines of at most `width` characters, between words
+fn wrap(text: &str, width: usize) -> Vec<String> {
+    let mut lines: Vec<String> = Vec::new();
+    for word in text.split_whitespace() {
+        match lines.last_mut() {
+            Some(line) if line.len() + 1 + word.len() <= width => {
+                line.push(' ');
+                line.push_str(word);
+            }
+            _ => lines.push(word.to_string()),
+        }
+    }
+    lines
+}
+
+/// Load the scenario file at `path`, named after the file if it has no
+/// `name` line.
+///
+/// # Errors
+///
+/// Returns an error if the file cannot be read or is not a valid scenario.
+pub fn load_scenario(path: &Path) -> Result<Scenario, ScenarioError> {
+    let content = fs::read_to_string(path)?;
+    let default_name = path
+        .file_stem()
+        .map(|stem| stem.to_string_lossy().replace('_', " "))
+        .unwrap_or_default();
+    Scenario::parse(&content, &default_name)
+}
+
+/// Play `scenario` at a table of its own, one round per stacked hand, then
+/// show which goals were reached
+pub fn play_scenario(scenario: &Scenario) {
+    let mut state = GameState::with_money(scenario.bankroll);
+    state.rules.variant = scenario.variant;
+    state.stacked_rounds = scenario.hands.iter().cloned().collect();
+
+    println!("\n{}", scenario.summary());
+    let rounds = scenario.hands.len();
+    for round in 1..=rounds {
+        if state.money <= 0 {
+            println!("\nYou're out of coins.");
+            break;
+        }
+        println!("\n=== {}: hand {round} of {rounds} ===", scenario.name);
+        if !game_loop::start_round(&mut state) {
+            break;
+        }
+        game_loop::play_current_round(&mut state);
+    }
+
+    let met: Vec<bool> = scenario
+        .goals
+        .iter()
+        .map(|goal| goal.is_met(&state))
+        .collect();
+    let passed = met.iter().all(|&met| met);
+
+    let mut formatter = BoxFormatter::new(
+        BOX_WIDTH,
+        if passed {
+            "SCENARIO COMPLETE"
+        } else {
+            "SCENARIO FAILED"
+        },
+    );
+    formatter.add_field_aligned("Hands played", &state.history.total_games_played);
+    formatter.add_field_aligned("Hands won", &state.history.total_wins);
+    formatter.add_field_aligned(
+        "Bankroll",
+        &format!(
+            "{} ({})",
+            state.money,
+            format_money(state.money - scenario.bankroll)
+        ),
+    );
+    formatter.add_separator();
+    for (goal, met) in scenario.goals.iter().zip(met) {
+        formatter.add_line(&format!("[{}] {goal}", if met { "x" } else { " " }));
+    }
+    println!("\n{}\n", formatter.build());
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const HEADER: &str = "bankroll: 100\ngoal: win 1 hand\n";
+
+    /// Parse a scenario made of the required lines followed by `extra`
+    fn parse_with(extra: &str) -> Result<Scenario, ScenarioError> {
+        Scenario::parse(&format!("{HEADER}{extra}"), "test")
+    }
+
+    #[test]
+    fn parses_a_full_scenario() {
+        let scenario = Scenario::parse(
+            "# comment\n\nname: Practice\ndescription: Two hands\nvariant: pontoon\nbankroll: 50\ngoal: win 2 hands\ngoal: end with ≥ 60\nhand: A Hearts, 10h, QS, 2 clubs\nhand: 9d, 9c, 7h, 7s, 3h\n",
+            "fallback",
+        )
+        .unwrap();
+        assert_eq!(scenario.name, "Practice");
+        assert_eq!(scenario.description, "Two hands");
+        assert_eq!(scenario.variant, GameVariant::Pontoon);
+        assert_eq!(scenario.bankroll, 50);
+        assert_eq!(
+            scenario.goals,
+            [Goal::WinHands(2), Goal::EndWithAtLeast(60)]
+        );
+        assert_eq!(
+            scenario.hands[0],
+            ["A Hearts", "10 Hearts", "Q Spades", "2 Clubs"]
+        );
+        assert_eq!(scenario.hands[1].len(), 5);
+
+        let unnamed = parse_with("hand: Ah, Kh, 2c, 3c").unwrap();
+        assert_eq!(unnamed.name, "test");
+        assert_eq!(unnamed.variant, GameVariant::Classic);
+    }
+
+    #[test]
+    fn goals_accept_each_spelling() {
+        for (text, goal) in [
+            ("win 1 hand", Some(Goal::WinHands(1))),
+            ("Win 3 hands", Some(Goal::WinHands(3))),
+            ("end with >= 200", Some(Goal::EndWithAtLeast(200))),
+            ("end with >=200", Some(Goal::EndWithAtLeast(200))),
+            ("end with ≥ 150", Some(Goal::EndWithAtLeast(150))),
+            ("end with at least 80", Some(Goal::EndWithAtLeast(80))),
+            ("win 0 hands", None),
+            ("win many hands", None),
+            ("end with 200", None),
+        ] {
+            assert_eq!(Goal::parse(text), goal, "{text}");
+        }
+    }
+
+    #[test]
+    fn cards_accept_long_and_short_names() {
+        for (text, card) in [
+            ("A Hearts", Some("A Hearts")),
+            ("  q spades ", Some("Q Spades")),
+            ("10h", Some("10 Hearts")),
+            ("QS", Some("Q Spades")),
+            ("jd", Some("J Diamonds")),
+            ("1 Hearts", None),
+            ("11h", None),
+            ("Kx", None),
+            ("K", None),
+            ("", None),
+        ] {
+            assert_eq!(parse_card(text).as_deref(), card, "{text:?}");
+        }
+    }
+
+    #[test]
+    fn reports_lines_that_are_not_keys_and_values() {
+        assert!(matches!(
+            parse_with("hand Ah, Kh, 2c, 3c"),
+            Err(ScenarioError::NotKeyValue(3))
+        ));
+        assert!(matches!(
+            parse_with("dealer: soft 17"),
+            Err(ScenarioError::UnknownKey { line: 3, ref key }) if key == "dealer"
+        ));
+        assert!(matches!(
+            parse_with("name: One\nname: Two"),
+            Err(ScenarioError::RepeatedKey { line: 4, ref key }) if key == "name"
+        ));
+        assert!(matches!(
+            parse_with("bankroll: 200"),
+            Err(ScenarioError::RepeatedKey { line: 3, ref key }) if key == "bankroll"
+        ));
+    }
+
+    #[test]
+    fn reports_bad_values() {
+        for bankroll in ["0", "-5", "lots"] {
+            assert!(matches!(
+                Scenario::parse(&format!("bankroll: {bankroll}"), "test"),
+                Err(ScenarioError::InvalidBankroll { line: 1, ref value }) if value == bankroll
+            ));
+        }
+        assert!(matches!(
+            parse_with("variant: Baccarat"),
+            Err(ScenarioError::UnknownVariant { line: 3, ref value }) if value == "Baccarat"
+        ));
+        assert!(matches!(
+            parse_with("goal: win big"),
+            Err(ScenarioError::InvalidGoal { line: 3, ref value }) if value == "win big"
+        ));
+        assert!(matches!(
+            parse_with("hand: Ah, Kh, 1c, 3c"),
+            Err(ScenarioError::InvalidCard { line: 3, ref card }) if card == "1c"
+        ));
+    }
+
+    #[test]
+    fn reports_hands_that_cannot_be_dealt() {
+        assert!(matches!(
+            parse_with("variant: Spanish 21\nhand: Ah, 10h, 2c, 3c"),
+            Err(ScenarioError::CardNotInDeck { line: 4, ref card, variant: GameVariant::Spanish21 })
+                if card == "10 Hearts"
+        ));
+        assert!(matches!(
+            parse_with("hand: Ah, Kh, 2c, 3c\nhand: 5d, 6d, 5d, 7d"),
+            Err(ScenarioError::RepeatedCard { line: 4, ref card }) if card == "5 Diamonds"
+        ));
+        assert!(matches!(
+            parse_with("hand: Ah, Kh, 2c"),
+            Err(ScenarioError::TooFewCards {
+                line: 3,
+                found: 3,
+                needed: 4
+            })
+        ));
+        assert!(matches!(
+            parse_with("variant: Blackjack Switch\nhand: Ah, Kh, 2c, 3c, 4c"),
+            Err(ScenarioError::TooFewCards {
+                line: 4,
+                found: 5,
+                needed: 6
+            })
+        ));
+    }
+
+    #[test]
+    fn reports_missing_required_keys() {
+        let hand = "hand: Ah, Kh, 2c, 3c";
+        for (content, missing) in [
+            (format!("goal: win 1 hand\n{hand}"), "bankroll"),
+            (format!("bankroll: 100\n{hand}"), "goal"),
+            ("bankroll: 100\ngoal: win 1 hand".to_string(), "hand"),
+        ] {
+            assert!(matches!(
+                Scenario::parse(&content, "test"),
+                Err(ScenarioError::Missing(key)) if key == missing
+            ));
+        }
+    }
+
+    #[test]
+    fn bundled_scenarios_are_valid() {
+        let dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("scenarios");
+        let mut checked = 0;
+        for entry in fs::read_dir(dir).unwrap() {
+            let path = entry.unwrap().path();
+            if path.extension().is_some_and(|ext| ext == "scenario") {
+                if let Err(e) = load_scenario(&path) {
+                    panic!("{}: {e}", path.display());
+                }
+                checked += 1;
+            }
+        }
+        assert!(checked >= 2);
+    }
+}
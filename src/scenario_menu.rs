@@ -0,0 +1,133 @@
+use crate::formatting::BoxFormatter;
+use crate::profiles;
+use crate::scenario::{load_scenario, play_scenario, Scenario, ScenarioError};
+use crate::text_handler;
+use std::fs;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+
+/// Scenarios that come with the game, as file name and contents
+const BUILT_IN: [(&str, &str); 2] = [
+    (
+        "soft_18_vs_9.scenario",
+        include_str!("../scenarios/soft_18_vs_9.scenario"),
+    ),
+    (
+        "eights_vs_ace.scenario",
+        include_str!("../scenarios/eights_vs_ace.scenario"),
+    ),
+];
+
+/// A scenario listed in the menu, or why its file could not be used
+struct Entry {
+    file_name: String,
+    built_in: bool,
+    scenario: Result<Scenario, ScenarioError>,
+}
+
+/// The built-in scenarios followed by the `.scenario` files in `dir`, by
+/// file name
+fn list_scenarios(dir: &Path) -> Vec<Entry> {
+    let mut entries: Vec<Entry> = BUILT_IN
+        .iter()
+        .map(|(file_name, content)| Entry {
+            file_name: (*file_name).to_string(),
+            built_in: true,
+            scenario: Scenario::parse(content, file_name),
+        })
+        .collect();
+
+    let mut paths: Vec<PathBuf> = fs::read_dir(dir)
+        .map(|read_dir| {
+            read_dir
+                .filter_map(Result::ok)
+                .map(|entry| entry.path())
+                .filter(|path| path.extension().is_some_and(|ext| ext == "scenario"))
+                .collect()
+        })
+        .unwrap_or_default();
+    paths.sort();
+    entries.extend(paths.into_iter().map(|path| {
+        Entry {
+            file_name: path
+                .file_name()
+                .map(|name| name.to_string_lossy().into_owned())
+                .unwrap_or_default(),
+            built_in: false,
+            scenario: load_scenario(&path),
+        }
+    }));
+    entries
+}
+
+pub fn show_scenario_menu() {
+    let dir = profiles::scenarios_dir();
+    loop {
+        let entries = list_scenarios(&dir);
+        print_scenario_menu(&entries, &dir);
+        let Ok(input) = text_handler::read_menu_input() else {
+            break;
+        };
+
+        match input.trim().to_lowercase().as_str() {
+            "f" => play_file(),
+            "b" | "back" => break,
+            choice => match choice
+                .parse::<usize>()
+                .ok()
+                .and_then(|n| entries.get(n.checked_sub(1)?))
+            {
+                Some(Entry {
+                    scenario: Ok(scenario),
+                    ..
+                }) => play_scenario(scenario),
+                Some(Entry {
+                    file_name,
+                    scenario: Err(e),
+                    ..
+                }) => eprintln!("✗ {file_name} is not a valid scenario: {e}"),
+                None => println!("Invalid option. Please try again."),
+            },
+        }
+    }
+}
+
+fn print_scenario_menu(entries: &[Entry], dir: &Path) {
+    let mut formatter = BoxFormatter::new(60, "SCENARIOS");
+    for (i, entry) in entries.iter().enumerate() {
+        let label = match &entry.scenario {
+            Ok(scenario) if entry.built_in => format!("{} (built in)", scenario.name),
+            Ok(scenario) => scenario.name.clone(),
+            Err(_) => format!("{} (invalid)", entry.file_name),
+        };
+        formatter.add_line(&format!("({}) {label}", i + 1));
+    }
+    formatter.add_empty_line();
+    formatter.add_line("(f) Load a scenario file");
+    formatter.add_line("(b) Back to main menu");
+    println!("\n{}", formatter.build());
+
+    for entry in entries {
+        if let Err(e) = &entry.scenario {
+            println!("✗ {}: {e}", entry.file_name);
+        }
+    }
+    println!("Add your own .scenario files to {}", dir.display());
+    print!("Choose a scenario: ");
+    io::stdout().flush().ok();
+}
+
+fn play_file() {
+    print!("Path of the scenario file: ");
+    io::stdout().flush().ok();
+    let Ok(input) = text_handler::read_menu_input() else {
+        return;
+    };
+    if input.is_empty() {
+        return;
+    }
+    match load_scenario(Path::new(&input)) {
+        Ok(scenario) => play_scenario(&scenario),
+        Err(e) => eprintln!("✗ Could not load {input}: {e}"),
+    }
+}
@@ -0,0 +1,166 @@
+use crate::formatting::{format_duration_between, format_money, BoxFormatter};
+use crate::profiles::Profile;
+use crate::safe_file;
+use crate::save_system::{self, STARTING_MONEY};
+use chrono::{DateTime, Local, TimeDelta};
+use serde::{Deserialize, Serialize};
+use std::io;
+use std::path::Path;
+
+/// Sessions shown in the sessions list, most recent first
+const SESSIONS_SHOWN: usize = 20;
+
+/// One launch of the game with a profile, from opening it to quitting or
+/// switching to another profile
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Session {
+    /// Counts up from 1 within a profile; rounds from before sessions were
+    /// tracked have session 0
+    pub id: u32,
+    pub start: DateTime<Local>,
+    /// When the last hand finished or the session was closed
+    pub end: DateTime<Local>,
+    pub starting_money: i32,
+    pub ending_money: i32,
+    pub hands_played: u32,
+}
+
+impl Session {
+    #[must_use]
+    pub fn duration(&self) -> TimeDelta {
+        self.end - self.start
+    }
+
+    #[must_use]
+    pub fn net(&self) -> i32 {
+        self.ending_money - self.starting_money
+    }
+
+    /// Hands played per hour, or `None` if the session lasted under a minute
+    #[must_use]
+    #[allow(clippy::cast_precision_loss)]
+    pub fn hands_per_hour(&self) -> Option<f64> {
+        let minutes = self.duration().num_minutes();
+        (minutes > 0).then(|| f64::from(self.hands_played) * 60.0 / minutes as f64)
+    }
+
+    /// Count a finished hand that left the bankroll at `money_after`
+    pub fn record_hand(&mut self, money_after: i32) {
+        self.hands_played += 1;
+        self.ending_money = money_after;
+        self.end = Local::now();
+    }
+
+    /// A box summarising the session, shown when the player quits
+    #[must_use]
+    pub fn summary_card(&self) -> String {
+        let mut formatter = BoxFormatter::new(40, "SESSION SUMMARY");
+        formatter.add_field_aligned("Started", &self.start.format("%Y-%m-%d %H:%M"));
+        formatter.add_field_aligned("Length", &format_duration_between(&self.start, &self.end));
+        formatter.add_field_aligned("Hands Played", &self.hands_played);
+        formatter.add_field_aligned("Starting Bankroll", &self.starting_money);
+        formatter.add_field_aligned("Ending Bankroll", &self.ending_money);
+        formatter.add_field_aligned("Net Result", &format_money(self.net()));
+        formatter.add_field_aligned("Hands per Hour", &hands_per_hour_label(self));
+        formatter.build()
+    }
+}
+
+fn hands_per_hour_label(session: &Session) -> String {
+    session
+        .hands_per_hour()
+        .map_or("-".to_string(), |rate| format!("{rate:.0}"))
+}
+
+/// Store `session` at `path`, replacing the stored session with the same id.
+///
+/// # Errors
+///
+/// Returns an error if the stored sessions cannot be read or the file
+/// cannot be written.
+pub fn save_session(path: &Path, session: &Session) -> io::Result<()> {
+    let mut sessions: Vec<Session> = safe_file::read_json(path)?.unwrap_or_default();
+    match sessions.iter_mut().find(|stored| stored.id == session.id) {
+        Some(stored) => *stored = session.clone(),
+        None => sessions.push(session.clone()),
+    }
+    safe_file::write_json(path, &sessions)
+}
+
+/// Start and store a new session for `profile`, beginning with the bankroll
+/// in its save
+#[must_use]
+pub fn begin_session(profile: &Profile) -> Session {
+    let path = profile.sessions_path();
+    let sessions: Vec<Session> = safe_file::read_json(&path)
+        .unwrap_or_else(|e| {
+            eprintln!("⚠ Warning: Could not read your past sessions: {e}");
+            None
+        })
+        .unwrap_or_default();
+    let money = save_system::read_save_data(&profile.save_path())
+        .ok()
+        .flatten()
+        .map_or(STARTING_MONEY, |save_data| save_data.money);
+    let now = Local::now();
+
+    let session = Session {
+        id: sessions.iter().map(|s| s.id).max().unwrap_or(0) + 1,
+        start: now,
+        end: now,
+        starting_money: money,
+        ending_money: money,
+        hands_played: 0,
+    };
+    store(profile, &session);
+    session
+}
+
+/// Close `session` now with `money` in the bankroll and store it
+pub fn end_session(profile: &Profile, session: &mut Session, money: i32) {
+    session.end = Local::now();
+    session.ending_money = money;
+    store(profile, session);
+}
+
+/// Store `session` for `profile`, warning if it cannot be written
+pub fn store(profile: &Profile, session: &Session) {
+    if let Err(e) = save_session(&profile.sessions_path(), session) {
+        eprintln!("⚠ Warning: Could not save the session: {e}");
+    }
+}
+
+/// Show the most recent sessions as a table
+pub fn display_sessions(sessions: &[Session]) {
+    let mut formatter = BoxFormatter::new(78, "SESSIONS");
+    if sessions.is_empty() {
+        formatter.add_line("No sessions recorded yet.");
+    } else {
+        formatter.add_line(&format!(
+            "{:>4}  {:<16} {:>11} {:>6} {:>7} {:>7} {:>7} {:>8}",
+            "#", "Started", "Length", "Hands", "Start", "End", "Net", "Hands/h"
+        ));
+        formatter.add_separator();
+        for session in sessions.iter().rev().take(SESSIONS_SHOWN) {
+            formatter.add_line(&format!(
+                "{:>4}  {:<16} {:>11} {:>6} {:>7} {:>7} {:>7} {:>8}",
+                session.id,
+                session.start.format("%Y-%m-%d %H:%M"),
+                format_duration_between(&session.start, &session.end),
+                session.hands_played,
+                session.starting_money,
+                session.ending_money,
+                format_money(session.net()),
+                hands_per_hour_label(session)
+            ));
+        }
+        if sessions.len() > SESSIONS_SHOWN {
+            formatter.add_separator();
+            formatter.add_line(&format!(
+                "Showing the last {SESSIONS_SHOWN} of {} sessions",
+                sessions.len()
+            ));
+        }
+    }
+    println!("\n{}\n", formatter.build());
+}
@@ -0,0 +1,237 @@
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// When a side bet is settled during a round
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SideBetTiming {
+    /// Settled as soon as the initial four cards are dealt
+    AfterDeal,
+    /// Settled once the dealer has finished drawing
+    AfterDealerTurn,
+}
+
+/// A single line of a side bet pay table, paying `numerator:denominator`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PayLine {
+    pub label: &'static str,
+    pub numerator: i32,
+    pub denominator: i32,
+}
+
+impl PayLine {
+    pub const fn new(label: &'static str, numerator: i32, denominator: i32) -> Self {
+        PayLine {
+            label,
+            numerator,
+            denominator,
+        }
+    }
+
+    /// Winnings for a stake on this line, not including the returned stake
+    #[must_use]
+    pub fn winnings(&self, stake: i32) -> i32 {
+        stake * self.numerator / self.denominator
+    }
+}
+
+impl fmt::Display for PayLine {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} pays {}:{}",
+            self.label, self.numerator, self.denominator
+        )
+    }
+}
+
+/// A pluggable side bet that can be offered at a table
+pub trait SideBet {
+    fn name(&self) -> &'static str;
+
+    fn timing(&self) -> SideBetTiming;
+
+    fn pay_table(&self) -> &'static [PayLine];
+
+    /// Returns the index of the winning pay line, or `None` if the bet loses
+    fn evaluate(&self, player_cards: &[String], dealer_cards: &[String]) -> Option<usize>;
+}
+
+/// The side bets shipped with the game
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SideBetKind {
+    BusterBlackjack,
+    LuckyLadies,
+    RoyalMatch,
+}
+
+impl SideBetKind {
+    pub const ALL: [SideBetKind; 3] = [
+        SideBetKind::BusterBlackjack,
+        SideBetKind::LuckyLadies,
+        SideBetKind::RoyalMatch,
+    ];
+
+    #[must_use]
+    pub fn side_bet(self) -> &'static dyn SideBet {
+        match self {
+            SideBetKind::BusterBlackjack => &BusterBlackjack,
+            SideBetKind::LuckyLadies => &LuckyLadies,
+            SideBetKind::RoyalMatch => &RoyalMatch,
+        }
+    }
+}
+
+impl fmt::Display for SideBetKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.side_bet().name())
+    }
+}
+
+/// A side bet wager placed for the current round
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PlacedSideBet {
+    pub kind: SideBetKind,
+    pub amount: i32,
+}
+
+fn card_rank(card: &str) -> &str {
+    card.split_whitespace().next().unwrap_or("")
+}
+
+fn card_suit(card: &str) -> &str {
+    card.split_whitespace().nth(1).unwrap_or("")
+}
+
+fn card_points(card: &str) -> i32 {
+    match card_rank(card) {
+        "A" => 11,
+        "K" | "Q" | "J" => 10,
+        rank => rank.parse::<i32>().unwrap_or(0),
+    }
+}
+
+/// Pays when the dealer busts, more the more cards the dealer needed to bust
+pub struct BusterBlackjack;
+
+impl SideBet for BusterBlackjack {
+    fn name(&self) -> &'static str {
+        "Buster Blackjack"
+    }
+
+    fn timing(&self) -> SideBetTiming {
+        SideBetTiming::AfterDealerTurn
+    }
+
+    fn pay_table(&self) -> &'static [PayLine] {
+        const TABLE: [PayLine; 6] = [
+            PayLine::new("Dealer busts with 8+ cards", 250, 1),
+            PayLine::new("Dealer busts with 7 cards", 50, 1),
+            PayLine::new("Dealer busts with 6 cards", 15, 1),
+            PayLine::new("Dealer busts with 5 cards", 4, 1),
+            PayLine::new("Dealer busts with 4 cards", 2, 1),
+            PayLine::new("Dealer busts with 3 cards", 1, 1),
+        ];
+        &TABLE
+    }
+
+    fn evaluate(&self, _player_cards: &[String], dealer_cards: &[String]) -> Option<usize> {
+        if crate::player_handler::hand_value(dealer_cards) <= 21 {
+            return None;
+        }
+        match dealer_cards.len() {
+            8.. => Some(0),
+            7 => Some(1),
+            6 => Some(2),
+            5 => Some(3),
+            4 => Some(4),
+            3 => Some(5),
+            _ => None,
+        }
+    }
+}
+
+/// Pays when the player's first two cards total 20
+pub struct LuckyLadies;
+
+impl SideBet for LuckyLadies {
+    fn name(&self) -> &'static str {
+        "Lucky Ladies"
+    }
+
+    fn timing(&self) -> SideBetTiming {
+        SideBetTiming::AfterDeal
+    }
+
+    fn pay_table(&self) -> &'static [PayLine] {
+        const TABLE: [PayLine; 5] = [
+            PayLine::new("Queen of Hearts pair with dealer blackjack", 1000, 1),
+            PayLine::new("Queen of Hearts pair", 200, 1),
+            PayLine::new("Matched 20", 25, 1),
+            PayLine::new("Suited 20", 10, 1),
+            PayLine::new("Any 20", 4, 1),
+        ];
+        &TABLE
+    }
+
+    fn evaluate(&self, player_cards: &[String], dealer_cards: &[String]) -> Option<usize> {
+        let [first, second, ..] = player_cards else {
+            return None;
+        };
+        if card_points(first) + card_points(second) != 20 {
+            return None;
+        }
+
+        let queen_of_hearts = |card: &str| card_rank(card) == "Q" && card_suit(card) == "Hearts";
+        if queen_of_hearts(first) && queen_of_hearts(second) {
+            let dealer_blackjack =
+                dealer_cards.len() == 2 && crate::player_handler::hand_value(dealer_cards) == 21;
+            return if dealer_blackjack { Some(0) } else { Some(1) };
+        }
+
+        let suited = card_suit(first) == card_suit(second);
+        if suited && card_rank(first) == card_rank(second) {
+            Some(2)
+        } else if suited {
+            Some(3)
+        } else {
+            Some(4)
+        }
+    }
+}
+
+/// Pays when the player's first two cards share a suit
+pub struct RoyalMatch;
+
+impl SideBet for RoyalMatch {
+    fn name(&self) -> &'static str {
+        "Royal Match"
+    }
+
+    fn timing(&self) -> SideBetTiming {
+        SideBetTiming::AfterDeal
+    }
+
+    fn pay_table(&self) -> &'static [PayLine] {
+        const TABLE: [PayLine; 2] = [
+            PayLine::new("Suited King and Queen", 25, 1),
+            PayLine::new("Suited cards", 5, 2),
+        ];
+        &TABLE
+    }
+
+    fn evaluate(&self, player_cards: &[String], _dealer_cards: &[String]) -> Option<usize> {
+        let [first, second, ..] = player_cards else {
+            return None;
+        };
+        if card_suit(first) != card_suit(second) {
+            return None;
+        }
+
+        let ranks = [card_rank(first), card_rank(second)];
+        if ranks.contains(&"K") && ranks.contains(&"Q") {
+            Some(0)
+        } else {
+            Some(1)
+        }
+    }
+}
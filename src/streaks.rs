@@ -0,0 +1,88 @@
+use crate::game_history_core::{GameOutcome, GameRound};
+use std::collections::BTreeMap;
+
+/// Whether a run of games was won or lost
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StreakKind {
+    Win,
+    Loss,
+}
+
+impl StreakKind {
+    /// The kind of streak `outcome` adds to; ties do not count towards or
+    /// break a streak
+    #[must_use]
+    pub fn of(outcome: GameOutcome) -> Option<Self> {
+        match outcome {
+            GameOutcome::PlayerWin | GameOutcome::DealerBust => Some(StreakKind::Win),
+            GameOutcome::DealerWin | GameOutcome::PlayerBust | GameOutcome::Surrender => {
+                Some(StreakKind::Loss)
+            }
+            GameOutcome::Tie => None,
+        }
+    }
+}
+
+/// A run of games with the same result
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Streak {
+    pub kind: StreakKind,
+    pub length: usize,
+    /// Position in the rounds of the game that started the run
+    pub start: usize,
+}
+
+/// Every win and loss streak in a list of games, in play order
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct StreakStats {
+    pub streaks: Vec<Streak>,
+}
+
+impl StreakStats {
+    #[must_use]
+    pub fn from_rounds(rounds: &[GameRound]) -> Self {
+        let mut streaks: Vec<Streak> = Vec::new();
+        for (i, round) in rounds.iter().enumerate() {
+            let Some(kind) = StreakKind::of(round.outcome) else {
+                continue;
+            };
+            match streaks.last_mut() {
+                Some(streak) if streak.kind == kind => streak.length += 1,
+                _ => streaks.push(Streak {
+                    kind,
+                    length: 1,
+                    start: i,
+                }),
+            }
+        }
+        StreakStats { streaks }
+    }
+
+    /// The longest streak of `kind`, the earliest if several are as long
+    #[must_use]
+    pub fn longest(&self, kind: StreakKind) -> Option<Streak> {
+        self.streaks
+            .iter()
+            .filter(|streak| streak.kind == kind)
+            .fold(None, |best: Option<Streak>, &streak| match best {
+                Some(best) if best.length >= streak.length => Some(best),
+                _ => Some(streak),
+            })
+    }
+
+    /// The streak the most recent games belong to
+    #[must_use]
+    pub fn current(&self) -> Option<Streak> {
+        self.streaks.last().copied()
+    }
+
+    /// How many streaks of `kind` there were of each length
+    #[must_use]
+    pub fn distribution(&self, kind: StreakKind) -> BTreeMap<usize, usize> {
+        let mut counts = BTreeMap::new();
+        for streak in self.streaks.iter().filter(|streak| streak.kind == kind) {
+            *counts.entry(streak.length).or_insert(0) += 1;
+        }
+        counts
+    }
+}
@@ -0,0 +1,130 @@
+use crate::side_bets::SideBetKind;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::str::FromStr;
+
+/// The blackjack variant dealt at a table
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum GameVariant {
+    #[default]
+    Classic,
+    Spanish21,
+    BlackjackSwitch,
+    DoubleExposure,
+    FreeBet,
+    Pontoon,
+}
+
+impl GameVariant {
+    pub const ALL: [GameVariant; 6] = [
+        GameVariant::Classic,
+        GameVariant::Spanish21,
+        GameVariant::BlackjackSwitch,
+        GameVariant::DoubleExposure,
+        GameVariant::FreeBet,
+        GameVariant::Pontoon,
+    ];
+
+    /// Number of hands the player plays each round
+    #[must_use]
+    pub fn hands_per_round(self) -> i32 {
+        match self {
+            GameVariant::BlackjackSwitch => 2,
+            _ => 1,
+        }
+    }
+
+    /// Number of the dealer's initial cards dealt face up
+    #[must_use]
+    pub fn dealer_cards_face_up(self) -> usize {
+        match self {
+            GameVariant::DoubleExposure => 2,
+            GameVariant::Pontoon => 0,
+            _ => 1,
+        }
+    }
+
+    /// Header of the `art.txt` section holding this variant's own messages
+    #[must_use]
+    pub fn message_section(self) -> Option<&'static str> {
+        match self {
+            GameVariant::Pontoon => {
+                Some("// --- Pontoon Prompts and Messages (from player_handler.rs) ---")
+            }
+            _ => None,
+        }
+    }
+
+    /// Whether a dealer total of exactly 22 pushes non-blackjack hands
+    #[must_use]
+    pub fn dealer_22_pushes(self) -> bool {
+        matches!(self, GameVariant::BlackjackSwitch | GameVariant::FreeBet)
+    }
+
+    /// Short description of what sets the variant apart
+    #[must_use]
+    pub fn description(self) -> &'static str {
+        match self {
+            GameVariant::Classic => "Standard rules, dealer hits soft 17",
+            GameVariant::Spanish21 => "No tens in the deck, player 21 always wins",
+            GameVariant::BlackjackSwitch => "Two hands, swap second cards, dealer 22 pushes",
+            GameVariant::DoubleExposure => "Both dealer cards face up, dealer wins ties",
+            GameVariant::FreeBet => "Free doubles on hard 9-11 and free splits, dealer 22 pushes",
+            GameVariant::Pontoon => "British rules: twist, stick or buy, five card trick",
+        }
+    }
+}
+
+impl fmt::Display for GameVariant {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            GameVariant::Classic => write!(f, "Classic"),
+            GameVariant::Spanish21 => write!(f, "Spanish 21"),
+            GameVariant::BlackjackSwitch => write!(f, "Blackjack Switch"),
+            GameVariant::DoubleExposure => write!(f, "Double Exposure"),
+            GameVariant::FreeBet => write!(f, "Free Bet Blackjack"),
+            GameVariant::Pontoon => write!(f, "Pontoon"),
+        }
+    }
+}
+
+impl FromStr for GameVariant {
+    type Err = String;
+
+    /// Parse a variant as written by its `Display` implementation
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        GameVariant::ALL
+            .into_iter()
+            .find(|variant| variant.to_string() == s)
+            .ok_or_else(|| format!("unknown variant \"{s}\""))
+    }
+}
+
+/// Rules configured for the table the player sits down at
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+pub struct TableRules {
+    pub variant: GameVariant,
+    pub side_bets: Vec<SideBetKind>,
+}
+
+impl TableRules {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    #[must_use]
+    pub fn is_side_bet_enabled(&self, kind: SideBetKind) -> bool {
+        self.side_bets.contains(&kind)
+    }
+
+    /// Enable a side bet if it is disabled, or disable it if it is enabled
+    pub fn toggle_side_bet(&mut self, kind: SideBetKind) {
+        if let Some(pos) = self.side_bets.iter().position(|k| *k == kind) {
+            self.side_bets.remove(pos);
+        } else {
+            self.side_bets.push(kind);
+        }
+    }
+}
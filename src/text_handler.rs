@@ -7,8 +7,15 @@ impl ShowSplash {
     }
 }
 
+/// Clear the terminal and its scrollback, so what was on screen cannot be
+/// scrolled back to
+pub fn clear_screen() {
+    print!("\x1b[2J\x1b[3J\x1b[H");
+    io::stdout().flush().ok();
+}
+
 pub fn print_menu() {
-    println!("Choose an option: (a)bout, (n)ew game, (h)elp, (g)uide, (r)ecords, (q)uit: ");
+    println!("Choose an option: (a)bout, (n)ew game, (h)elp, (g)uide, (r)ecords, (t)able rules, t(o)urnament, (d)uplicate, daily (c)hallenge, (s)cenarios, (p)rofiles, achie(v)ements, (l)eaderboard, (q)uit: ");
 }
 
 /// Read user input from stdin.
@@ -0,0 +1,553 @@
+use crate::basic_strategy::{self, StrategyAction};
+use crate::csv_io::{self, CsvRecord};
+use crate::deck_manager::{create_deck, shuffle_deck};
+use crate::enemy_ai_handler;
+use crate::formatting::BoxFormatter;
+use crate::game_history::GameOutcome;
+use crate::game_loop::take_played_hands;
+use crate::game_state::{GameState, PlayedHand};
+use crate::player_handler::{draw, hand_value, player_turn, print_initial_game_state};
+use crate::win_checker::{compare_totals, settle_outcome};
+use chrono::{DateTime, Local, NaiveDateTime, TimeZone};
+use rand::seq::SliceRandom;
+use rand::thread_rng;
+use std::fs::{self, OpenOptions};
+use std::io::{self, Write};
+use std::path::Path;
+
+const TOURNAMENT_HISTORY_HEADER: &str =
+    "Timestamp,Hands,Entrants,StartingChips,Position,FinalChips,Winner";
+
+/// AI opponents and the share of their chips they bet each hand, in percent
+const OPPONENTS: [(&str, i32); 4] = [
+    ("Steady Sue", 10),
+    ("Lucky Lou", 15),
+    ("Counting Carl", 20),
+    ("Reckless Rita", 30),
+];
+
+/// Settings for a tournament
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TournamentConfig {
+    pub hands: u32,
+    pub starting_chips: i32,
+    pub min_bet: i32,
+    pub opponents: usize,
+    /// Hands after which the entrant with the fewest chips is eliminated
+    pub elimination_hands: Vec<u32>,
+}
+
+impl Default for TournamentConfig {
+    fn default() -> Self {
+        TournamentConfig {
+            hands: 15,
+            starting_chips: 500,
+            min_bet: 10,
+            opponents: 3,
+            elimination_hands: vec![5, 10],
+        }
+    }
+}
+
+/// A seat at the tournament table
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Entrant {
+    pub name: String,
+    pub chips: i32,
+    /// Chips held when the last hand was dealt, before betting
+    pub chips_before_hand: i32,
+    pub is_player: bool,
+    /// Share of their chips an AI opponent bets each hand, in percent
+    pub aggression: i32,
+    /// The last hand the entrant played before being knocked out
+    pub eliminated_after: Option<u32>,
+}
+
+impl Entrant {
+    #[must_use]
+    pub fn is_active(&self) -> bool {
+        self.eliminated_after.is_none()
+    }
+
+    fn bot_bet(&self, min_bet: i32) -> i32 {
+        let bet = self.chips * self.aggression / 100 / 5 * 5;
+        bet.max(min_bet).min(self.chips)
+    }
+}
+
+/// The outcome of a finished tournament from the player's point of view
+#[derive(Debug, Clone, PartialEq)]
+pub struct TournamentResult {
+    pub timestamp: DateTime<Local>,
+    pub hands_played: u32,
+    pub entrants: usize,
+    pub starting_chips: i32,
+    pub position: usize,
+    pub final_chips: i32,
+    pub winner: String,
+}
+
+impl TournamentResult {
+    fn to_csv_line(&self) -> String {
+        csv_io::write_row(&[
+            self.timestamp.format("%Y-%m-%d %H:%M:%S").to_string(),
+            self.hands_played.to_string(),
+            self.entrants.to_string(),
+            self.starting_chips.to_string(),
+            self.position.to_string(),
+            self.final_chips.to_string(),
+            self.winner.clone(),
+        ])
+    }
+
+    fn from_csv_record(record: &CsvRecord) -> Option<Self> {
+        let parts = &record.fields;
+        if parts.len() != 7 {
+            return None;
+        }
+        let naive = NaiveDateTime::parse_from_str(&parts[0], "%Y-%m-%d %H:%M:%S").ok()?;
+        Some(TournamentResult {
+            timestamp: Local.from_local_datetime(&naive).single()?,
+            hands_played: parts[1].parse().ok()?,
+            entrants: parts[2].parse().ok()?,
+            starting_chips: parts[3].parse().ok()?,
+            position: parts[4].parse().ok()?,
+            final_chips: parts[5].parse().ok()?,
+            winner: parts[6].clone(),
+        })
+    }
+}
+
+pub struct Tournament {
+    config: TournamentConfig,
+    entrants: Vec<Entrant>,
+    /// Table state for the player's hands; its money is tournament chips only
+    table: GameState,
+    hands_played: u32,
+}
+
+impl Tournament {
+    #[must_use]
+    pub fn new(config: TournamentConfig) -> Self {
+        let mut entrants = vec![Entrant {
+            name: "You".to_string(),
+            chips: config.starting_chips,
+            chips_before_hand: config.starting_chips,
+            is_player: true,
+            aggression: 0,
+            eliminated_after: None,
+        }];
+        for (name, aggression) in OPPONENTS.iter().take(config.opponents) {
+            entrants.push(Entrant {
+                name: (*name).to_string(),
+                chips: config.starting_chips,
+                chips_before_hand: config.starting_chips,
+                is_player: false,
+                aggression: *aggression,
+                eliminated_after: None,
+            });
+        }
+
+        let table = GameState::with_money(config.starting_chips);
+        Tournament {
+            config,
+            entrants,
+            table,
+            hands_played: 0,
+        }
+    }
+
+    /// Play the tournament to the end and return the player's result
+    pub fn run(&mut self) -> TournamentResult {
+        for hand_number in 1..=self.config.hands {
+            self.eliminate_busted(hand_number - 1);
+            if self.active_count() <= 1 {
+                break;
+            }
+
+            self.print_standings(&format!("HAND {hand_number} OF {}", self.config.hands));
+            self.play_hand(hand_number);
+            self.hands_played = hand_number;
+
+            if self.config.elimination_hands.contains(&hand_number) {
+                self.eliminate_short_stack(hand_number);
+            }
+        }
+        self.eliminate_busted(self.hands_played);
+
+        let standings = self.standings();
+        self.print_leaderboard(&standings);
+
+        let position = standings
+            .iter()
+            .position(|entrant| entrant.is_player)
+            .map_or(standings.len(), |i| i + 1);
+        TournamentResult {
+            timestamp: Local::now(),
+            hands_played: self.hands_played,
+            entrants: self.entrants.len(),
+            starting_chips: self.config.starting_chips,
+            position,
+            final_chips: self.entrants[0].chips,
+            winner: standings[0].name.clone(),
+        }
+    }
+
+    /// Entrants ranked by how long they lasted, then by chips
+    #[must_use]
+    pub fn standings(&self) -> Vec<&Entrant> {
+        let mut standings: Vec<&Entrant> = self.entrants.iter().collect();
+        standings.sort_by_key(|entrant| {
+            (
+                std::cmp::Reverse(entrant.eliminated_after.unwrap_or(u32::MAX)),
+                std::cmp::Reverse(entrant.chips),
+            )
+        });
+        standings
+    }
+
+    fn active_count(&self) -> usize {
+        self.entrants.iter().filter(|e| e.is_active()).count()
+    }
+
+    fn play_hand(&mut self, hand_number: u32) {
+        let mut deck = create_deck();
+        deck.extend(create_deck());
+        shuffle_deck(&mut deck);
+        self.table.card_deck = deck;
+        self.table.deck_index = 0;
+
+        for entrant in &mut self.entrants {
+            entrant.chips_before_hand = entrant.chips;
+        }
+        let mut bets = vec![0; self.entrants.len()];
+        for (seat, entrant) in self.entrants.iter_mut().enumerate() {
+            if !entrant.is_active() {
+                continue;
+            }
+            let bet = if entrant.is_player {
+                ask_player_bet(entrant.chips, self.config.min_bet)
+            } else {
+                Some(entrant.bot_bet(self.config.min_bet))
+            };
+            if let Some(bet) = bet {
+                entrant.chips -= bet;
+                bets[seat] = bet;
+            } else {
+                println!("You leave the tournament.");
+                entrant.eliminated_after = Some(hand_number - 1);
+            }
+        }
+
+        let mut seat_cards: Vec<Vec<String>> = bets
+            .iter()
+            .map(|&bet| {
+                if bet > 0 {
+                    vec![draw(&mut self.table), draw(&mut self.table)]
+                } else {
+                    Vec::new()
+                }
+            })
+            .collect();
+        self.table.dealer_cards = vec![draw(&mut self.table), draw(&mut self.table)];
+        self.table.dealer_card_count = 2;
+
+        let mut played: Vec<Vec<PlayedHand>> = vec![Vec::new(); self.entrants.len()];
+        for seat in 0..self.entrants.len() {
+            if bets[seat] == 0 {
+                continue;
+            }
+            let cards = std::mem::take(&mut seat_cards[seat]);
+            played[seat] = if self.entrants[seat].is_player {
+                self.play_player_seat(bets[seat], cards)
+            } else {
+                vec![self.play_bot_seat(seat, bets[seat], cards)]
+            };
+        }
+
+        let any_alive = played.iter().enumerate().any(|(seat, hands)| {
+            hands.iter().any(|hand| {
+                hand_value(&hand.cards) <= 21
+                    && !(self.entrants[seat].is_player && self.table.surrendered)
+            })
+        });
+        if any_alive {
+            enemy_ai_handler::dealer_turn(&mut self.table);
+        }
+
+        for (seat, hands) in played.into_iter().enumerate() {
+            if self.entrants[seat].is_player {
+                self.settle_player_seat(hands);
+            } else {
+                for hand in hands {
+                    self.settle_bot_hand(seat, &hand);
+                }
+            }
+        }
+    }
+
+    #[allow(clippy::cast_possible_truncation, clippy::cast_possible_wrap)]
+    fn play_player_seat(&mut self, bet: i32, cards: Vec<String>) -> Vec<PlayedHand> {
+        let table = &mut self.table;
+        table.money = self.entrants[0].chips;
+        table.bet = bet;
+        table.free_bet = 0;
+        table.free_double_bet = 0;
+        table.was_double_down = false;
+        table.surrendered = false;
+        table.was_switched = false;
+        table.split_hands.clear();
+        table.actions.clear();
+        table.player_cards = cards;
+        table.player_card_count = table.player_cards.len() as i32;
+
+        println!("\n--- Your hand ---");
+        print_initial_game_state(table);
+        player_turn(table);
+        self.entrants[0].chips = table.money;
+        take_played_hands(table)
+    }
+
+    fn play_bot_seat(&mut self, seat: usize, bet: i32, mut cards: Vec<String>) -> PlayedHand {
+        let can_double = self.entrants[seat].chips >= bet;
+        let actions = basic_strategy::play_hand(&mut self.table, &mut cards, can_double);
+        let was_double_down = actions.contains(&StrategyAction::Double);
+        let mut bet = bet;
+        if was_double_down {
+            self.entrants[seat].chips -= bet;
+            bet *= 2;
+        }
+
+        let total = hand_value(&cards);
+        println!(
+            "{} bets {bet}{}: {} (total {total}{})",
+            self.entrants[seat].name,
+            if was_double_down { " and doubles" } else { "" },
+            cards.join(", "),
+            if total > 21 { ", bust" } else { "" }
+        );
+        PlayedHand {
+            cards,
+            bet,
+            free_bet: 0,
+            free_double_bet: 0,
+            was_double_down,
+            was_split: false,
+            was_switched: false,
+        }
+    }
+
+    #[allow(clippy::cast_possible_truncation, clippy::cast_possible_wrap)]
+    fn settle_player_seat(&mut self, hands: Vec<PlayedHand>) {
+        if hands.is_empty() {
+            return;
+        }
+        println!("\n--- Settling your hand ---");
+        self.table.money = self.entrants[0].chips;
+        for hand in hands {
+            self.table.player_cards = hand.cards;
+            self.table.player_card_count = self.table.player_cards.len() as i32;
+            self.table.bet = hand.bet;
+            self.table.free_bet = hand.free_bet;
+            self.table.free_double_bet = hand.free_double_bet;
+            self.table.was_double_down = hand.was_double_down;
+            self.table.was_split = hand.was_split;
+            self.table.was_switched = hand.was_switched;
+            settle_outcome(&mut self.table);
+        }
+        self.entrants[0].chips = self.table.money;
+    }
+
+    fn settle_bot_hand(&mut self, seat: usize, hand: &PlayedHand) {
+        let d_total = hand_value(&self.table.dealer_cards);
+        let outcome = compare_totals(hand_value(&hand.cards), d_total);
+        let payout = match outcome {
+            GameOutcome::PlayerWin | GameOutcome::DealerBust => hand.bet * 2,
+            GameOutcome::Tie => hand.bet,
+            _ => 0,
+        };
+        let entrant = &mut self.entrants[seat];
+        entrant.chips += payout;
+        println!(
+            "{}: {outcome} ({:+} chips)",
+            entrant.name,
+            payout - hand.bet
+        );
+    }
+
+    /// Knock out every entrant who can no longer cover the minimum bet
+    fn eliminate_busted(&mut self, last_hand: u32) {
+        let min_bet = self.config.min_bet;
+        for entrant in &mut self.entrants {
+            if entrant.is_active() && entrant.chips < min_bet {
+                println!(
+                    "\x1b[1;31m{} can't cover the minimum bet and is out!\x1b[0m",
+                    entrant.name
+                );
+                entrant.eliminated_after = Some(last_hand);
+            }
+        }
+    }
+
+    /// Knock out the active entrant with the fewest chips
+    fn eliminate_short_stack(&mut self, hand_number: u32) {
+        if self.active_count() <= 1 {
+            return;
+        }
+        if let Some(seat) = short_stack(&self.entrants) {
+            let entrant = &mut self.entrants[seat];
+            println!(
+                "\x1b[1;31mElimination after hand {hand_number}: {} is out with {} chips!\x1b[0m",
+                entrant.name, entrant.chips
+            );
+            entrant.eliminated_after = Some(hand_number);
+        }
+    }
+
+    fn print_standings(&self, title: &str) {
+        let mut formatter = BoxFormatter::new(50, title);
+        for entrant in self.standings() {
+            if entrant.is_active() {
+                formatter.add_field_aligned(&entrant.name, &format!("{} chips", entrant.chips));
+            }
+        }
+        if let Some(next) = self
+            .config
+            .elimination_hands
+            .iter()
+            .find(|&&hand| hand > self.hands_played)
+        {
+            formatter.add_empty_line();
+            formatter.add_line(&format!("Next elimination after hand {next}"));
+        }
+        println!("\n{}", formatter.build());
+    }
+
+    fn print_leaderboard(&self, standings: &[&Entrant]) {
+        let mut formatter = BoxFormatter::new(60, "FINAL LEADERBOARD");
+        for (i, entrant) in standings.iter().enumerate() {
+            let status = match entrant.eliminated_after {
+                Some(hand) => format!("{} chips, out after hand {hand}", entrant.chips),
+                None => format!("{} chips", entrant.chips),
+            };
+            formatter.add_field_aligned(&format!("{}. {}", i + 1, entrant.name), &status);
+        }
+        formatter.add_empty_line();
+        formatter.add_field("Hands played", &self.hands_played);
+        println!("\n{}", formatter.build());
+    }
+}
+
+/// Seat of the active entrant to eliminate: the one with the fewest chips.
+/// A tie goes against whoever had fewer chips before the last hand, and a
+/// tie on both is settled at random, so no seat is favoured.
+#[must_use]
+pub fn short_stack(entrants: &[Entrant]) -> Option<usize> {
+    let shortest = entrants
+        .iter()
+        .filter(|entrant| entrant.is_active())
+        .map(|entrant| (entrant.chips, entrant.chips_before_hand))
+        .min()?;
+    let tied: Vec<usize> = entrants
+        .iter()
+        .enumerate()
+        .filter(|(_, entrant)| {
+            entrant.is_active() && (entrant.chips, entrant.chips_before_hand) == shortest
+        })
+        .map(|(seat, _)| seat)
+        .collect();
+    tied.choose(&mut thread_rng()).copied()
+}
+
+/// Ask the player for a bet, or `None` if they leave the tournament
+fn ask_player_bet(chips: i32, min_bet: i32) -> Option<i32> {
+    loop {
+        print!("Your bet ({min_bet}-{chips} chips, q to leave the tournament): ");
+        io::stdout().flush().ok();
+        let mut line = String::new();
+        match io::stdin().read_line(&mut line) {
+            Ok(0) | Err(_) => return None,
+            Ok(_) => {}
+        }
+
+        let trimmed = line.trim();
+        if trimmed.eq_ignore_ascii_case("q") {
+            return None;
+        }
+        match trimmed.parse::<i32>() {
+            Ok(n) if (min_bet..=chips).contains(&n) => return Some(n),
+            _ => println!("Please bet between {min_bet} and {chips} chips."),
+        }
+    }
+}
+
+/// Append a finished tournament to the tournament history file at `path`,
+/// which is kept apart from the regular game history.
+///
+/// # Errors
+///
+/// Returns an error if the history file cannot be opened or written.
+pub fn save_tournament_result(result: &TournamentResult, path: &Path) -> io::Result<()> {
+    let is_new = !path.exists();
+    let mut file = OpenOptions::new().create(true).append(true).open(path)?;
+    if is_new {
+        writeln!(file, "{TOURNAMENT_HISTORY_HEADER}")?;
+    }
+    writeln!(file, "{}", result.to_csv_line())
+}
+
+/// Load past tournament results, skipping lines that cannot be parsed
+#[must_use]
+pub fn load_tournament_results(path: &Path) -> Vec<TournamentResult> {
+    fs::read_to_string(path)
+        .map(|content| {
+            csv_io::parse(&content)
+                .iter()
+                .skip(1)
+                .filter_map(|record| record.as_ref().ok())
+                .filter_map(TournamentResult::from_csv_record)
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entrant(name: &str, chips: i32, chips_before_hand: i32) -> Entrant {
+        Entrant {
+            name: name.to_string(),
+            chips,
+            chips_before_hand,
+            is_player: name == "You",
+            aggression: 10,
+            eliminated_after: None,
+        }
+    }
+
+    #[test]
+    fn short_stack_tie_goes_against_fewer_chips_before_the_hand() {
+        let entrants = [
+            entrant("You", 100, 300),
+            entrant("Steady Sue", 100, 150),
+            entrant("Lucky Lou", 400, 400),
+        ];
+        assert_eq!(short_stack(&entrants), Some(1));
+    }
+
+    #[test]
+    fn short_stack_skips_eliminated_entrants() {
+        let mut out = entrant("Steady Sue", 0, 20);
+        out.eliminated_after = Some(3);
+        let entrants = [entrant("You", 100, 100), out, entrant("Lucky Lou", 50, 90)];
+        assert_eq!(short_stack(&entrants), Some(2));
+    }
+
+    #[test]
+    fn full_tie_does_not_always_eliminate_the_first_seat() {
+        let entrants = [entrant("You", 100, 100), entrant("Steady Sue", 100, 100)];
+        let seats: Vec<usize> = (0..64).filter_map(|_| short_stack(&entrants)).collect();
+        assert!(seats.contains(&0) && seats.contains(&1));
+    }
+}
@@ -0,0 +1,110 @@
+use crate::formatting::BoxFormatter;
+use crate::profiles::Profile;
+use crate::text_handler;
+use crate::tournament::{
+    load_tournament_results, save_tournament_result, Tournament, TournamentConfig,
+};
+use std::io::{self, Write};
+use std::path::PathBuf;
+
+pub struct TournamentMenu {
+    config: TournamentConfig,
+    history_path: PathBuf,
+}
+
+impl TournamentMenu {
+    #[must_use]
+    pub fn new(config: TournamentConfig, history_path: PathBuf) -> Self {
+        TournamentMenu {
+            config,
+            history_path,
+        }
+    }
+
+    pub fn show_menu(&self) {
+        loop {
+            self.print_tournament_menu();
+            let Ok(input) = text_handler::read_menu_input() else {
+                break;
+            };
+
+            match input.trim().to_lowercase().as_str() {
+                "p" => self.play_tournament(),
+                "r" => self.show_past_results(),
+                "b" | "back" => break,
+                _ => println!("Invalid option. Please try again."),
+            }
+        }
+    }
+
+    fn print_tournament_menu(&self) {
+        let mut formatter = BoxFormatter::new(50, "TOURNAMENT");
+        formatter.add_field_aligned("Hands", &self.config.hands);
+        formatter.add_field_aligned("Starting chips", &self.config.starting_chips);
+        formatter.add_field_aligned("Minimum bet", &self.config.min_bet);
+        formatter.add_field_aligned("AI opponents", &self.config.opponents);
+        let eliminations: Vec<String> = self
+            .config
+            .elimination_hands
+            .iter()
+            .map(ToString::to_string)
+            .collect();
+        formatter.add_field_aligned("Eliminations after hands", &eliminations.join(", "));
+        formatter.add_line("Classic rules. Tournament chips are separate");
+        formatter.add_line("from your saved coins.");
+        formatter.add_empty_line();
+        formatter.add_line("(p) Play a tournament");
+        formatter.add_line("(r) Show past results");
+        formatter.add_line("(b) Back to main menu");
+
+        println!("\n{}", formatter.build());
+        print!("Choose an option: ");
+        io::stdout().flush().ok();
+    }
+
+    fn play_tournament(&self) {
+        let mut tournament = Tournament::new(self.config.clone());
+        let result = tournament.run();
+        println!(
+            "You finished {} of {} with {} chips.",
+            result.position, result.entrants, result.final_chips
+        );
+        match save_tournament_result(&result, &self.history_path) {
+            Ok(()) => println!("✓ Result saved to {}", self.history_path.display()),
+            Err(e) => eprintln!("⚠ Warning: Could not save tournament result: {e}"),
+        }
+    }
+
+    fn show_past_results(&self) {
+        let results = load_tournament_results(&self.history_path);
+        if results.is_empty() {
+            println!("No tournaments played yet!");
+            return;
+        }
+
+        let mut formatter = BoxFormatter::new(60, "PAST TOURNAMENTS");
+        for result in results.iter().rev().take(10) {
+            formatter.add_line(&format!(
+                "{}  {} of {}, {} chips, won by {}",
+                result.timestamp.format("%Y-%m-%d %H:%M"),
+                result.position,
+                result.entrants,
+                result.final_chips,
+                result.winner
+            ));
+        }
+        let wins = results.iter().filter(|r| r.position == 1).count();
+        formatter.add_empty_line();
+        formatter.add_field("Tournaments played", &results.len());
+        formatter.add_field("Tournaments won", &wins);
+        println!("\n{}", formatter.build());
+    }
+}
+
+pub fn show_tournament_menu(profile: &Profile) {
+    let menu = TournamentMenu::new(
+        TournamentConfig::default(),
+        profile.tournament_history_path(),
+    );
+    menu.show_menu();
+}
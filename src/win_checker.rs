@@ -1,83 +1,597 @@
+use crate::achievements;
 use crate::art_handler::get_message;
 use crate::game_history::{GameOutcome, GameRound};
 use crate::game_state::GameState;
+use crate::history_log;
+use crate::leaderboard;
 use crate::player_handler::{hand_value, player_wins};
+use crate::sessions;
+use crate::side_bets::{PayLine, SideBetTiming};
+use crate::table_rules::GameVariant;
 use chrono::Local;
 use std::cmp::Ordering;
-use std::fs;
 
 pub fn determine_winner(state: &mut GameState) {
+    let outcome = settle_outcome(state);
+    resolve_side_bets(state, SideBetTiming::AfterDealerTurn);
+    record_game_result(state, outcome);
+}
+
+/// Settle the current hand against the dealer under the table's rules,
+/// paying out to `state.money` without recording the round anywhere. A
+/// busted hand loses before any variant rule is looked at, even when the
+/// dealer has played on for a side bet and busted too.
+pub fn settle_outcome(state: &mut GameState) -> GameOutcome {
     let p_total = hand_value(&state.player_cards);
     let d_total = hand_value(&state.dealer_cards);
     println!("Your total: {p_total}");
     println!("Dealer's total: {d_total}");
 
-    let outcome = match (p_total > 21, d_total > 21, p_total.cmp(&d_total)) {
-        (_, true, _) => {
-            player_wins(state);
-            GameOutcome::DealerBust
+    if state.surrendered {
+        println!("You surrendered this hand.");
+        state.games_lost += 1;
+        GameOutcome::Surrender
+    } else if p_total > 21 {
+        dealer_wins(state);
+        GameOutcome::PlayerBust
+    } else if state.rules.variant == GameVariant::Spanish21 && p_total == 21 {
+        spanish21_player_21(state, d_total > 21)
+    } else if state.rules.variant == GameVariant::FreeBet {
+        settle_free_bet(state, p_total, d_total)
+    } else if state.rules.variant.dealer_22_pushes() && d_total == 22 && !has_natural(state) {
+        println!("Dealer 22 pushes!");
+        push_hand(state);
+        GameOutcome::Tie
+    } else if state.rules.variant == GameVariant::Pontoon {
+        settle_pontoon(state, p_total, d_total)
+    } else if state.rules.variant == GameVariant::DoubleExposure {
+        settle_double_exposure(state, p_total, d_total)
+    } else {
+        settle_hand(state, p_total, d_total)
+    }
+}
+
+fn is_blackjack(cards: &[String]) -> bool {
+    cards.len() == 2 && hand_value(cards) == 21
+}
+
+/// Whether the player's hand is a blackjack. A two-card 21 made by
+/// switching cards in Blackjack Switch does not count.
+fn has_natural(state: &GameState) -> bool {
+    !state.was_switched && is_blackjack(&state.player_cards)
+}
+
+/// Classic outcome of a player total against a dealer total. A busted
+/// player loses even when the dealer busts too.
+#[must_use]
+pub fn compare_totals(p_total: i32, d_total: i32) -> GameOutcome {
+    match (p_total > 21, d_total > 21, p_total.cmp(&d_total)) {
+        (true, _, _) => GameOutcome::PlayerBust,
+        (_, true, _) => GameOutcome::DealerBust,
+        (_, _, Ordering::Greater) => GameOutcome::PlayerWin,
+        (_, _, Ordering::Less) => GameOutcome::DealerWin,
+        _ => GameOutcome::Tie,
+    }
+}
+
+fn settle_hand(state: &mut GameState, p_total: i32, d_total: i32) -> GameOutcome {
+    let outcome = compare_totals(p_total, d_total);
+    match outcome {
+        GameOutcome::PlayerWin | GameOutcome::DealerBust => player_wins(state),
+        GameOutcome::Tie => {
+            println!("It's a tie!");
+            push_hand(state);
+        }
+        _ => dealer_wins(state),
+    }
+    outcome
+}
+
+/// Free Bet Blackjack: dealer 22 pushes every hand but a blackjack, and
+/// the free part of a wager is never paid back, only the winnings on it.
+/// The player has not busted.
+fn settle_free_bet(state: &mut GameState, p_total: i32, d_total: i32) -> GameOutcome {
+    let outcome = if d_total == 22 && !has_natural(state) {
+        println!("Dealer 22 pushes!");
+        push_hand(state);
+        GameOutcome::Tie
+    } else {
+        settle_hand(state, p_total, d_total)
+    };
+    if outcome != GameOutcome::DealerWin {
+        state.money -= state.free_bet;
+    }
+    outcome
+}
+
+/// Double Exposure: the dealer wins ties, except that two blackjacks push.
+/// A player blackjack pays even money like any other win. The player has
+/// not busted.
+fn settle_double_exposure(state: &mut GameState, p_total: i32, d_total: i32) -> GameOutcome {
+    let player_blackjack = has_natural(state);
+    let dealer_blackjack = is_blackjack(&state.dealer_cards);
+
+    if player_blackjack && dealer_blackjack {
+        println!("Both have blackjack - it's a push!");
+        push_hand(state);
+        GameOutcome::Tie
+    } else if player_blackjack {
+        player_wins(state);
+        GameOutcome::PlayerWin
+    } else if d_total > 21 {
+        player_wins(state);
+        GameOutcome::DealerBust
+    } else if p_total > d_total {
+        player_wins(state);
+        GameOutcome::PlayerWin
+    } else {
+        if p_total == d_total {
+            println!("Ties go to the dealer in Double Exposure.");
         }
-        (true, _, _) => {
-            dealer_wins(state);
-            GameOutcome::PlayerBust
+        dealer_wins(state);
+        GameOutcome::DealerWin
+    }
+}
+
+/// Ranks the special Pontoon hands above plain totals: a pontoon (ace and a
+/// ten-value card) beats a five card trick, which beats everything else.
+fn pontoon_hand_rank(cards: &[String]) -> u8 {
+    if is_blackjack(cards) {
+        2
+    } else if cards.len() >= 5 && hand_value(cards) <= 21 {
+        1
+    } else {
+        0
+    }
+}
+
+/// Pontoon: the banker wins every tie. A pontoon or five card trick pays 2:1.
+/// The player has not busted.
+fn settle_pontoon(state: &mut GameState, p_total: i32, d_total: i32) -> GameOutcome {
+    const PONTOON: PayLine = PayLine::new("Pontoon bonus", 2, 1);
+    const FIVE_CARD_TRICK: PayLine = PayLine::new("Five Card Trick", 2, 1);
+
+    let player_rank = pontoon_hand_rank(&state.player_cards);
+    let dealer_rank = if d_total > 21 {
+        0
+    } else {
+        pontoon_hand_rank(&state.dealer_cards)
+    };
+    let player_ahead = match player_rank.cmp(&dealer_rank) {
+        Ordering::Greater => true,
+        Ordering::Less => false,
+        Ordering::Equal => player_rank == 0 && (d_total > 21 || p_total > d_total),
+    };
+
+    if !player_ahead {
+        if player_rank == dealer_rank && (player_rank > 0 || p_total == d_total) {
+            println!("{}", get_message("Ties go to the dealer", Some(state)));
         }
-        (_, _, Ordering::Greater) => {
+        dealer_wins(state);
+        return GameOutcome::DealerWin;
+    }
+
+    player_wins(state);
+    let bonus = match player_rank {
+        2 => Some(PONTOON),
+        1 => Some(FIVE_CARD_TRICK),
+        _ => None,
+    };
+    if let Some(line) = bonus {
+        println!("{}", get_message(line.label, Some(state)));
+        state.money += line.winnings(state.bet) - state.bet;
+    }
+
+    if d_total > 21 {
+        GameOutcome::DealerBust
+    } else {
+        GameOutcome::PlayerWin
+    }
+}
+
+/// Spanish 21: a player 21 always wins, and some 21s pay a bonus instead of
+/// even money. Bonuses do not apply to doubled hands.
+fn spanish21_player_21(state: &mut GameState, dealer_busted: bool) -> GameOutcome {
+    match spanish21_bonus(&state.player_cards).filter(|_| !state.was_double_down) {
+        Some(line) => {
+            println!("\x1b[1;32m{line}!\x1b[0m");
             player_wins(state);
-            GameOutcome::PlayerWin
+            state.money += line.winnings(state.bet) - state.bet;
         }
-        (_, _, Ordering::Less) => {
-            dealer_wins(state);
-            GameOutcome::DealerWin
+        None => player_wins(state),
+    }
+
+    if dealer_busted {
+        GameOutcome::DealerBust
+    } else {
+        GameOutcome::PlayerWin
+    }
+}
+
+fn spanish21_bonus(cards: &[String]) -> Option<PayLine> {
+    const SEVEN_PLUS_CARD: PayLine = PayLine::new("7+ card 21", 3, 1);
+    const SIX_CARD: PayLine = PayLine::new("6 card 21", 2, 1);
+    const FIVE_CARD: PayLine = PayLine::new("5 card 21", 3, 2);
+    const SPADED: PayLine = PayLine::new("Spaded 6-7-8 / 7-7-7", 3, 1);
+    const SUITED: PayLine = PayLine::new("Suited 6-7-8 / 7-7-7", 2, 1);
+    const MIXED: PayLine = PayLine::new("Mixed 6-7-8 / 7-7-7", 3, 2);
+
+    if cards.len() == 3 {
+        let mut ranks: Vec<&str> = cards
+            .iter()
+            .map(|card| card.split_whitespace().next().unwrap_or(""))
+            .collect();
+        ranks.sort_unstable();
+        if ranks == ["6", "7", "8"] || ranks == ["7", "7", "7"] {
+            let suits: Vec<&str> = cards
+                .iter()
+                .map(|card| card.split_whitespace().nth(1).unwrap_or(""))
+                .collect();
+            return if suits.iter().all(|suit| *suit == "Spades") {
+                Some(SPADED)
+            } else if suits.iter().all(|suit| *suit == suits[0]) {
+                Some(SUITED)
+            } else {
+                Some(MIXED)
+            };
         }
-        _ => {
-            println!("It's a tie!");
-            state.money += state.bet;
-            GameOutcome::Tie
+    }
+
+    match cards.len() {
+        7.. => Some(SEVEN_PLUS_CARD),
+        6 => Some(SIX_CARD),
+        5 => Some(FIVE_CARD),
+        _ => None,
+    }
+}
+
+/// Settle every placed side bet that resolves at `timing`, paying winners
+/// and removing the settled bets from the round.
+pub fn resolve_side_bets(state: &mut GameState, timing: SideBetTiming) {
+    let (due, pending): (Vec<_>, Vec<_>) = state
+        .side_bets
+        .iter()
+        .partition(|bet| bet.kind.side_bet().timing() == timing);
+    state.side_bets = pending;
+
+    for placed in due {
+        let side_bet = placed.kind.side_bet();
+        match side_bet.evaluate(&state.player_cards, &state.dealer_cards) {
+            Some(line_idx) => {
+                let line = side_bet.pay_table()[line_idx];
+                let winnings = line.winnings(placed.amount);
+                state.money += placed.amount + winnings;
+                println!(
+                    "\x1b[1;32m{}: {line}! You win {winnings} coins.\x1b[0m",
+                    side_bet.name()
+                );
+            }
+            None => {
+                println!(
+                    "{} side bet loses ({} coins).",
+                    side_bet.name(),
+                    placed.amount
+                );
+            }
         }
-    };
+    }
+}
 
-    record_game_result(state, outcome);
+fn push_hand(state: &mut GameState) {
+    state.money += state.bet;
 }
 
 fn dealer_wins(state: &mut GameState) {
-    println!("\x1b[1;31m{}\x1b[0m", get_message("Dealer Wins!", None));
+    println!(
+        "\x1b[1;31m{}\x1b[0m",
+        get_message("Dealer Wins!", Some(state))
+    );
     state.games_lost += 1;
 }
 
 fn record_game_result(state: &mut GameState, outcome: GameOutcome) {
     let p_total = hand_value(&state.player_cards);
     let d_total = hand_value(&state.dealer_cards);
-    let money_change = state.money - state.current_round_start_money;
+    let money_after = state.money + state.unsettled_stakes;
+    let money_change = money_after - state.current_round_start_money;
 
     let round = GameRound {
         round_number: state.history.total_games_played + 1,
+        session: state.session.as_ref().map_or(0, |session| session.id),
         timestamp: Local::now(),
+        variant: state.rules.variant,
         bet_amount: state.bet,
+        free_bet_amount: state.free_bet,
+        free_double_amount: state.free_double_bet,
         player_cards: state.player_cards.clone(),
         dealer_cards: state.dealer_cards.clone(),
         player_total: p_total,
         dealer_total: d_total,
         outcome,
         money_change,
-        money_after: state.money,
+        money_after,
         was_double_down: state.was_double_down,
+        was_split: state.was_split,
+        was_switched: state.was_switched,
+        actions: state.actions.iter().map(|action| action.code()).collect(),
         player_busted: p_total > 21,
         dealer_busted: d_total > 21,
     };
 
     state.history.add_round(round);
 
-    // Automatically save game history to CSV file
-    let csv_content = state.history.export_to_csv();
-
-    // Create path relative to the project directory
-    let mut path = std::env::current_dir().unwrap_or_default();
-    path.push("stats.csv");
+    // Append the round to the profile's history log
+    if let (Some(profile), Some(round)) = (&state.profile, state.history.rounds.last()) {
+        if let Err(e) =
+            history_log::record_round(&profile.history_log_path(), &state.history, round)
+        {
+            eprintln!("Failed to save game history: {e}");
+        }
+    }
 
-    if let Err(e) = fs::write(&path, csv_content) {
-        eprintln!("Failed to save game history to stats.csv: {e}");
+    if let (Some(profile), Some(session)) = (&state.profile, &mut state.session) {
+        session.record_hand(money_after);
+        sessions::store(profile, session);
     }
 
+    leaderboard::record_hand(state);
+    achievements::check_hand(state);
+
     // Save the current game state (money, wins, losses) to JSON file
     state.save_to_disk();
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::table_rules::GameVariant;
+
+    fn cards(names: &[&str]) -> Vec<String> {
+        names.iter().map(ToString::to_string).collect()
+    }
+
+    /// A table with `bet` already taken from a 100 coin bankroll
+    fn table(variant: GameVariant, player: &[&str], dealer: &[&str], bet: i32) -> GameState {
+        let mut state = GameState::with_money(100 - bet);
+        state.rules.variant = variant;
+        state.player_cards = cards(player);
+        state.dealer_cards = cards(dealer);
+        state.bet = bet;
+        state
+    }
+
+    #[test]
+    fn busted_player_loses_when_dealer_busts_too() {
+        assert_eq!(compare_totals(22, 23), GameOutcome::PlayerBust);
+        for variant in GameVariant::ALL {
+            let mut state = table(
+                variant,
+                &["K Hearts", "6 Clubs", "9 Spades"],
+                &["K Spades", "6 Hearts", "Q Clubs"],
+                10,
+            );
+            assert_eq!(settle_outcome(&mut state), GameOutcome::PlayerBust);
+            assert_eq!(state.money, 90, "{variant}");
+        }
+    }
+
+    #[test]
+    fn free_split_stake_is_never_paid_back() {
+        for (player, dealer, outcome, money) in [
+            (
+                ["10 Hearts", "9 Clubs"],
+                ["10 Spades", "8 Clubs"],
+                GameOutcome::PlayerWin,
+                110,
+            ),
+            (
+                ["10 Hearts", "8 Clubs"],
+                ["10 Spades", "8 Hearts"],
+                GameOutcome::Tie,
+                100,
+            ),
+            (
+                ["10 Hearts", "7 Clubs"],
+                ["10 Spades", "8 Hearts"],
+                GameOutcome::DealerWin,
+                100,
+            ),
+        ] {
+            let mut state = table(GameVariant::FreeBet, &player, &dealer, 10);
+            state.money = 100;
+            state.free_bet = 10;
+            assert_eq!(settle_outcome(&mut state), outcome);
+            assert_eq!(state.money, money, "{outcome}");
+        }
+    }
+
+    #[test]
+    fn free_double_wins_on_both_stakes_and_dealer_22_pushes() {
+        let mut state = table(
+            GameVariant::FreeBet,
+            &["6 Hearts", "4 Clubs", "9 Spades"],
+            &["10 Spades", "7 Clubs"],
+            10,
+        );
+        state.bet = 20;
+        state.free_bet = 10;
+        state.free_double_bet = 10;
+        assert_eq!(settle_outcome(&mut state), GameOutcome::PlayerWin);
+        assert_eq!(state.money, 120);
+
+        let mut state = table(
+            GameVariant::FreeBet,
+            &["10 Hearts", "7 Clubs"],
+            &["10 Spades", "6 Clubs", "6 Hearts"],
+            10,
+        );
+        assert_eq!(settle_outcome(&mut state), GameOutcome::Tie);
+        assert_eq!(state.money, 100);
+    }
+
+    #[test]
+    fn other_tables_pay_the_whole_bet() {
+        let mut state = table(
+            GameVariant::Classic,
+            &["10 Hearts", "9 Clubs"],
+            &["10 Spades", "8 Clubs"],
+            10,
+        );
+        assert_eq!(settle_outcome(&mut state), GameOutcome::PlayerWin);
+        assert_eq!(state.money, 110);
+    }
+
+    #[test]
+    fn switched_21_is_not_a_blackjack_against_dealer_22() {
+        for (switched, outcome, money) in [
+            (false, GameOutcome::DealerBust, 110),
+            (true, GameOutcome::Tie, 100),
+        ] {
+            let mut state = table(
+                GameVariant::BlackjackSwitch,
+                &["A Hearts", "K Clubs"],
+                &["10 Spades", "6 Clubs", "6 Hearts"],
+                10,
+            );
+            state.was_switched = switched;
+            assert_eq!(settle_outcome(&mut state), outcome);
+            assert_eq!(state.money, money);
+        }
+    }
+
+    #[test]
+    fn compare_totals_ranks_busts_before_totals() {
+        for (player, dealer, outcome) in [
+            (25, 18, GameOutcome::PlayerBust),
+            (18, 25, GameOutcome::DealerBust),
+            (20, 18, GameOutcome::PlayerWin),
+            (17, 18, GameOutcome::DealerWin),
+            (19, 19, GameOutcome::Tie),
+        ] {
+            assert_eq!(compare_totals(player, dealer), outcome);
+        }
+    }
+
+    #[test]
+    fn pontoon_pays_bonus_hands_two_to_one_and_banker_wins_ties() {
+        for (player, dealer, outcome, money) in [
+            (
+                &["A Hearts", "K Clubs"][..],
+                &["10 Spades", "9 Clubs"][..],
+                GameOutcome::PlayerWin,
+                120,
+            ),
+            (
+                &["2 Hearts", "3 Clubs", "4 Spades", "2 Clubs", "5 Hearts"],
+                &["7 Spades", "K Clubs", "4 Hearts"],
+                GameOutcome::PlayerWin,
+                120,
+            ),
+            (
+                &["2 Hearts", "3 Clubs", "4 Spades", "2 Clubs", "5 Hearts"],
+                &["A Spades", "Q Clubs"],
+                GameOutcome::DealerWin,
+                90,
+            ),
+            (
+                &["10 Hearts", "9 Clubs"],
+                &["10 Spades", "9 Hearts"],
+                GameOutcome::DealerWin,
+                90,
+            ),
+            (
+                &["10 Hearts", "8 Clubs"],
+                &["10 Spades", "6 Hearts", "7 Clubs"],
+                GameOutcome::DealerBust,
+                110,
+            ),
+        ] {
+            let mut state = table(GameVariant::Pontoon, player, dealer, 10);
+            let (p_total, d_total) = (
+                hand_value(&state.player_cards),
+                hand_value(&state.dealer_cards),
+            );
+            assert_eq!(settle_pontoon(&mut state, p_total, d_total), outcome);
+            assert_eq!(state.money, money, "{player:?} v {dealer:?}");
+        }
+    }
+
+    #[test]
+    fn double_exposure_dealer_wins_ties_but_blackjacks_push() {
+        for (player, dealer, outcome, money) in [
+            (
+                ["A Hearts", "K Clubs"],
+                ["A Spades", "Q Clubs"],
+                GameOutcome::Tie,
+                100,
+            ),
+            (
+                ["A Hearts", "K Clubs"],
+                ["10 Spades", "Q Clubs"],
+                GameOutcome::PlayerWin,
+                110,
+            ),
+            (
+                ["10 Hearts", "8 Clubs"],
+                ["10 Spades", "8 Hearts"],
+                GameOutcome::DealerWin,
+                90,
+            ),
+            (
+                ["10 Hearts", "9 Clubs"],
+                ["10 Spades", "8 Hearts"],
+                GameOutcome::PlayerWin,
+                110,
+            ),
+        ] {
+            let mut state = table(GameVariant::DoubleExposure, &player, &dealer, 10);
+            let (p_total, d_total) = (
+                hand_value(&state.player_cards),
+                hand_value(&state.dealer_cards),
+            );
+            assert_eq!(
+                settle_double_exposure(&mut state, p_total, d_total),
+                outcome
+            );
+            assert_eq!(state.money, money, "{player:?} v {dealer:?}");
+        }
+    }
+
+    #[test]
+    fn spanish21_bonus_finds_the_best_paying_21() {
+        for (hand, label) in [
+            (
+                &["7 Spades", "7 Spades", "7 Spades"][..],
+                Some("Spaded 6-7-8 / 7-7-7"),
+            ),
+            (
+                &["6 Hearts", "8 Hearts", "7 Hearts"],
+                Some("Suited 6-7-8 / 7-7-7"),
+            ),
+            (
+                &["6 Hearts", "7 Clubs", "8 Hearts"],
+                Some("Mixed 6-7-8 / 7-7-7"),
+            ),
+            (
+                &["2 Hearts", "3 Clubs", "4 Spades", "2 Clubs", "K Hearts"],
+                Some("5 card 21"),
+            ),
+            (
+                &[
+                    "A Hearts", "2 Clubs", "3 Spades", "4 Clubs", "5 Hearts", "6 Hearts",
+                ],
+                Some("6 card 21"),
+            ),
+            (
+                &[
+                    "A Hearts", "A Clubs", "2 Spades", "3 Clubs", "4 Hearts", "5 Hearts", "5 Clubs",
+                ],
+                Some("7+ card 21"),
+            ),
+            (&["A Hearts", "K Clubs"], None),
+            (&["9 Hearts", "5 Clubs", "7 Spades"], None),
+        ] {
+            assert_eq!(spanish21_bonus(&cards(hand)).map(|line| line.label), label);
+        }
+    }
+}